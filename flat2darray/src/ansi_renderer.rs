@@ -0,0 +1,725 @@
+// Copyright (c) 2026 Nazmul Idris. Licensed under Apache License, Version 2.0.
+
+#![allow(dead_code, clippy::wildcard_imports)]
+
+use crate::flat_2d_array::Flat2DArray;
+use std::io::Write as _;
+use unicode_width::{UnicodeWidthChar as _, UnicodeWidthStr as _};
+
+/// A cell that [AnsiRenderer] can paint. Each cell is 1 column of the terminal.
+///
+/// A wide glyph (eg: `'中'`) takes up 2 columns, so the cell after it must be a
+/// continuation cell (w/ a [RenderCell::display_width] of 0), which isn't painted.
+pub trait RenderCell: Clone + PartialEq {
+    /// Everything about how the cell looks, except its glyph. Consecutive cells w/ the
+    /// same style are painted w/out any escape sequences in between.
+    type Style: Copy + PartialEq;
+
+    fn style(&self) -> Self::Style;
+
+    /// Write the UTF-8 bytes of the glyph.
+    fn write_glyph(&self, out: &mut Vec<u8>);
+
+    /// The number of columns that the terminal moves the cursor by, when it prints the
+    /// glyph: 1, 2 for a wide glyph, or 0 for a continuation cell.
+    fn display_width(&self) -> usize;
+
+    /// Write the SGR sequence that switches to `style`, from any other style.
+    fn write_style(style: &Self::Style, out: &mut Vec<u8>);
+}
+
+/// The continuation cell of a wide `char`, eg: `['中', CONTINUATION_CHAR]`.
+pub const CONTINUATION_CHAR: char = '\0';
+
+/// Plain text w/out any style. See [CONTINUATION_CHAR] for wide glyphs.
+impl RenderCell for char {
+    type Style = ();
+
+    fn style(&self) -> Self::Style {}
+
+    fn write_glyph(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.encode_utf8(&mut [0; 4]).as_bytes());
+    }
+
+    /// Control chars (which don't have a width) are counted as 1 column, like the
+    /// replacement glyph that most terminals show for them.
+    fn display_width(&self) -> usize {
+        if *self == CONTINUATION_CHAR {
+            0
+        } else {
+            self.width().unwrap_or(1)
+        }
+    }
+
+    fn write_style(_: &Self::Style, _: &mut Vec<u8>) {}
+}
+
+/// A subset of SGR: 256 color palette indices and a few attributes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AnsiStyle {
+    pub fg: Option<u8>,
+    pub bg: Option<u8>,
+    pub bold: bool,
+    pub underline: bool,
+}
+
+/// A glyph w/ an [AnsiStyle]. The continuation cell of a wide glyph has a
+/// [CONTINUATION_CHAR] glyph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnsiCell {
+    pub glyph: char,
+    pub style: AnsiStyle,
+}
+
+impl Default for AnsiCell {
+    fn default() -> Self {
+        Self {
+            glyph: ' ',
+            style: AnsiStyle::default(),
+        }
+    }
+}
+
+impl RenderCell for AnsiCell {
+    type Style = AnsiStyle;
+
+    fn style(&self) -> Self::Style {
+        self.style
+    }
+
+    fn write_glyph(&self, out: &mut Vec<u8>) {
+        self.glyph.write_glyph(out);
+    }
+
+    fn display_width(&self) -> usize {
+        self.glyph.display_width()
+    }
+
+    /// Always reset first (`0`), so that the previous style doesn't leak through.
+    fn write_style(style: &Self::Style, out: &mut Vec<u8>) {
+        out.extend_from_slice(b"\x1b[0");
+        if style.bold {
+            out.extend_from_slice(b";1");
+        }
+        if style.underline {
+            out.extend_from_slice(b";4");
+        }
+        if let Some(fg) = style.fg {
+            let _ = write!(out, ";38;5;{fg}");
+        }
+        if let Some(bg) = style.bg {
+            let _ = write!(out, ";48;5;{bg}");
+        }
+        out.push(b'm');
+    }
+}
+
+/// A grapheme cluster (one or more `char`s, eg `"e\u{301}"`) w/ an [AnsiStyle]. Since it
+/// isn't `Copy`, the `simd_*` paths of [Flat2DArray] aren't available for it. The
+/// continuation cell of a wide grapheme has an empty `grapheme`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphemeCell {
+    pub grapheme: String,
+    pub style: AnsiStyle,
+}
+
+impl Default for GraphemeCell {
+    fn default() -> Self {
+        Self {
+            grapheme: String::from(" "),
+            style: AnsiStyle::default(),
+        }
+    }
+}
+
+impl RenderCell for GraphemeCell {
+    type Style = AnsiStyle;
+
+    fn style(&self) -> Self::Style {
+        self.style
+    }
+
+    fn write_glyph(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.grapheme.as_bytes());
+    }
+
+    fn display_width(&self) -> usize {
+        self.grapheme.width()
+    }
+
+    fn write_style(style: &Self::Style, out: &mut Vec<u8>) {
+        AnsiCell::write_style(style, out);
+    }
+}
+
+/// Rewriting up to this many unchanged cells is never longer than the shortest cursor
+/// move over them (`ESC [ n C` is at least 3 bytes), as long as each of them is 1 byte,
+/// see [is_cheap_gap]. So those small gaps are merged into the surrounding runs.
+const MAX_GAP_TO_REWRITE: usize = 3;
+
+/// Turns frames into the minimal ANSI byte stream that paints them on a terminal.
+///
+/// # Logic
+///
+/// 1. Only the rows that are dirty in the next frame are compared, and a row slice that
+///    is equal in both frames (one `memcmp`) is skipped.
+/// 2. The changed cells in a row are coalesced into runs. Small gaps of unchanged cells
+///    between runs are rewritten, when that is shorter than moving the cursor (ie: the
+///    gap cells have 1 byte glyphs, and the same style as the end of the run before).
+/// 3. The cursor is moved w/ the shortest sequence (relative, absolute, or `\r`), and
+///    not at all if it is already there after painting the previous run.
+/// 4. A style is only emitted when it differs from the last one emitted.
+///
+/// ```text
+/// prev row: │ a │ b │ c │ d │ e │ f │ g │ h │ i │ j │
+/// next row: │ a │ X │ c │ Y │ e │ f │ g │ h │ Z │ j │
+///                 ^^^^^^^^^^^                 ^^^
+///                 run 1 (gap "c" rewritten)   run 2 (gap "efgh" skipped w/ ESC[4C)
+/// ```
+///
+/// The renderer remembers the cursor position and style across frames. Call
+/// [AnsiRenderer::invalidate] if anything else writes to the terminal in between.
+#[derive(Debug, Clone)]
+pub struct AnsiRenderer<T: RenderCell> {
+    /// 0 based `(row, col)`, or `None` if unknown.
+    cursor: Option<(usize, usize)>,
+    /// The last style emitted, or `None` if unknown.
+    style: Option<T::Style>,
+}
+
+impl<T: RenderCell> Default for AnsiRenderer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: RenderCell> AnsiRenderer<T> {
+    pub fn new() -> Self {
+        Self {
+            cursor: None,
+            style: None,
+        }
+    }
+
+    /// Forget the cursor position and style, so that the next render doesn't rely on
+    /// them.
+    pub fn invalidate(&mut self) {
+        self.cursor = None;
+        self.style = None;
+    }
+
+    /// Paint every cell of `frame`, and clear its dirty rows.
+    pub fn render_full(&mut self, frame: &mut Flat2DArray<T>, out: &mut Vec<u8>) {
+        let cols = frame.cols.as_usize();
+        if cols > 0 {
+            for (row_index, row) in frame.data.chunks_exact(cols).enumerate() {
+                self.paint_run(row_index, 0, row, cols, out);
+            }
+        }
+        frame.clear_dirty_rows();
+    }
+
+    /// Paint only what changed between `prev` (what is on the terminal) and `next`, and
+    /// clear the dirty rows of `next`. If the sizes differ, everything is painted.
+    ///
+    /// Only the rows that are dirty in `next` are compared, so `next` must be the same as
+    /// `prev` in every other row, ie: `next` started out as a copy of `prev` (after `prev`
+    /// was rendered), and was only changed after that. For a double buffer, copy the
+    /// frame that was just rendered into the other buffer before drawing into it, eg:
+    /// `back.clone_from(&front)`. This is checked in debug builds.
+    pub fn render_diff(
+        &mut self,
+        prev: &Flat2DArray<T>,
+        next: &mut Flat2DArray<T>,
+        out: &mut Vec<u8>,
+    ) {
+        if prev.rows != next.rows || prev.cols != next.cols {
+            self.render_full(next, out);
+            return;
+        }
+
+        debug_assert!(
+            (0..next.rows.as_usize())
+                .filter(|row_index| !next.is_row_dirty(*row_index))
+                .all(|row_index| prev[row_index] == next[row_index]),
+            "`next` differs from `prev` in a row that isn't dirty"
+        );
+
+        if next.is_clean() {
+            return;
+        }
+
+        let cols = next.cols.as_usize();
+        for row_index in next.dirty_row_indices() {
+            let range = row_index * cols..(row_index + 1) * cols;
+            let prev_row = &prev.data[range.clone()];
+            let next_row = &next.data[range];
+            if prev_row == next_row {
+                continue;
+            }
+            for (start, end) in changed_runs(prev_row, next_row) {
+                self.paint_run(row_index, start, &next_row[start..end], cols, out);
+            }
+        }
+
+        next.clear_dirty_rows();
+    }
+
+    fn paint_run(
+        &mut self,
+        row_index: usize,
+        col_index: usize,
+        cells: &[T],
+        cols: usize,
+        out: &mut Vec<u8>,
+    ) {
+        for (offset, cell) in cells.iter().enumerate() {
+            // A continuation cell is already covered by the wide glyph before it.
+            let width = cell.display_width();
+            if width == 0 {
+                continue;
+            }
+
+            // This doesn't emit anything, unless a glyph didn't move the cursor to the
+            // next cell.
+            self.move_cursor(row_index, col_index + offset, out);
+
+            let style = cell.style();
+            if self.style != Some(style) {
+                T::write_style(&style, out);
+                self.style = Some(style);
+            }
+            cell.write_glyph(out);
+
+            // After the last column the terminal is in a "pending wrap" state, which
+            // differs between terminals, so the cursor position is treated as unknown.
+            let end = col_index + offset + width;
+            self.cursor = if end < cols {
+                Some((row_index, end))
+            } else {
+                None
+            };
+        }
+    }
+
+    fn move_cursor(&mut self, row_index: usize, col_index: usize, out: &mut Vec<u8>) {
+        let target = (row_index, col_index);
+        if self.cursor == Some(target) {
+            return;
+        }
+
+        // CUP is 1 based, and `ESC [ H` is the home position.
+        let mut best = Vec::with_capacity(12);
+        if target == (0, 0) {
+            best.extend_from_slice(b"\x1b[H");
+        } else {
+            let _ = write!(best, "\x1b[{};{}H", row_index + 1, col_index + 1);
+        }
+
+        if let Some((cursor_row, cursor_col)) = self.cursor {
+            let mut relative = Vec::with_capacity(12);
+            match cursor_row.cmp(&row_index) {
+                std::cmp::Ordering::Less => write_csi(&mut relative, row_index - cursor_row, b'B'),
+                std::cmp::Ordering::Greater => {
+                    write_csi(&mut relative, cursor_row - row_index, b'A')
+                }
+                std::cmp::Ordering::Equal => {}
+            }
+            if col_index > cursor_col {
+                write_csi(&mut relative, col_index - cursor_col, b'C');
+            } else if col_index < cursor_col {
+                if col_index == 0 {
+                    relative.push(b'\r');
+                } else {
+                    write_csi(&mut relative, cursor_col - col_index, b'D');
+                }
+            }
+            if relative.len() < best.len() {
+                best = relative;
+            }
+        }
+
+        out.extend_from_slice(&best);
+        self.cursor = Some(target);
+    }
+}
+
+/// `ESC [ n <final>`, where `n` is left out when it is 1.
+fn write_csi(out: &mut Vec<u8>, n: usize, final_byte: u8) {
+    out.extend_from_slice(b"\x1b[");
+    if n != 1 {
+        let _ = write!(out, "{n}");
+    }
+    out.push(final_byte);
+}
+
+/// The `[start, end)` column ranges to repaint, w/ small gaps of unchanged cells merged
+/// into the surrounding runs, and w/out splitting a wide glyph.
+fn changed_runs<T: RenderCell>(prev_row: &[T], next_row: &[T]) -> Vec<(usize, usize)> {
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    let mut glyph = Vec::with_capacity(4);
+
+    for (col_index, (prev_cell, next_cell)) in prev_row.iter().zip(next_row).enumerate() {
+        if prev_cell == next_cell {
+            continue;
+        }
+        match runs.last_mut() {
+            Some((_, end))
+                if col_index - *end <= MAX_GAP_TO_REWRITE
+                    && is_cheap_gap(
+                        &next_row[*end - 1],
+                        &next_row[*end..col_index],
+                        &mut glyph,
+                    ) =>
+            {
+                *end = col_index + 1;
+            }
+            _ => runs.push((col_index, col_index + 1)),
+        }
+    }
+
+    cover_wide_glyphs(runs, next_row)
+}
+
+/// Widen each run that starts or ends in the middle of a wide glyph (eg: when only the
+/// style of its continuation cell changed), since the glyph can only be painted whole.
+/// Runs that overlap after that are merged.
+fn cover_wide_glyphs<T: RenderCell>(
+    runs: Vec<(usize, usize)>,
+    next_row: &[T],
+) -> Vec<(usize, usize)> {
+    let mut widened: Vec<(usize, usize)> = Vec::with_capacity(runs.len());
+
+    for (mut start, mut end) in runs {
+        while start > 0 && next_row[start].display_width() == 0 {
+            start -= 1;
+        }
+        while end < next_row.len() && next_row[end].display_width() == 0 {
+            end += 1;
+        }
+        match widened.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end.max(*last_end),
+            _ => widened.push((start, end)),
+        }
+    }
+
+    widened
+}
+
+/// Rewriting the `gap` right after painting `last` costs 1 byte per cell, if every cell
+/// in it has the same style as `last` (so no SGR sequence is emitted) and a 1 byte glyph.
+fn is_cheap_gap<T: RenderCell>(last: &T, gap: &[T], glyph: &mut Vec<u8>) -> bool {
+    let style = last.style();
+    gap.iter().all(|cell| {
+        glyph.clear();
+        cell.write_glyph(glyph);
+        cell.style() == style && glyph.len() == 1
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use r3bl_tui::{col, height, row, width};
+
+    fn frame(lines: &[&str]) -> Flat2DArray<char> {
+        let cols = lines[0].chars().count();
+        let mut it = Flat2DArray::new(width(cols) + height(lines.len()), ' ');
+        for (row_index, line) in lines.iter().enumerate() {
+            for (col_index, glyph) in line.chars().enumerate() {
+                it[row_index][col_index] = glyph;
+            }
+        }
+        it
+    }
+
+    #[test]
+    fn test_changed_runs() {
+        let prev: Vec<char> = "abcdefghij".chars().collect();
+        let next: Vec<char> = "aXcYefghZj".chars().collect();
+        // The 1 cell gap is rewritten, the 4 cell gap is skipped.
+        assert_eq!(changed_runs(&prev, &next), vec![(1, 4), (8, 9)]);
+        assert!(changed_runs(&prev, &prev).is_empty());
+
+        // A multi-byte glyph in the gap is longer than a cursor move over it.
+        let prev: Vec<char> = "abéde".chars().collect();
+        let next: Vec<char> = "aXéYe".chars().collect();
+        assert_eq!(changed_runs(&prev, &next), vec![(1, 2), (3, 4)]);
+    }
+
+    #[test]
+    fn test_changed_runs_wide_glyphs() {
+        let prev: Vec<char> = "a中\0bcd".chars().collect();
+        // Only the wide glyph changed, so its continuation cell is added to the run.
+        let next: Vec<char> = "a文\0bcd".chars().collect();
+        assert_eq!(changed_runs(&prev, &next), vec![(1, 3)]);
+
+        // Only the continuation cell changed, so the wide glyph is added to the run.
+        let cell = |glyph, bold| AnsiCell {
+            glyph,
+            style: AnsiStyle {
+                bold,
+                ..AnsiStyle::default()
+            },
+        };
+        let prev = [
+            cell('中', false),
+            cell(CONTINUATION_CHAR, false),
+            cell('a', false),
+        ];
+        let next = [
+            cell('中', false),
+            cell(CONTINUATION_CHAR, true),
+            cell('a', false),
+        ];
+        assert_eq!(changed_runs(&prev, &next), vec![(0, 2)]);
+    }
+
+    #[test]
+    fn test_render_diff_wide_glyphs() {
+        let mut prev = frame(&["abcdefghij"]);
+        let mut renderer = AnsiRenderer::new();
+        renderer.render_full(&mut prev, &mut vec![]);
+
+        let mut next = prev.clone();
+        next[0][1] = '中';
+        next[0][2] = CONTINUATION_CHAR;
+        next[0][8] = 'Z';
+
+        // The continuation cell isn't painted, and the wide glyph moves the cursor 2
+        // columns, so the relative move after it lands on column 8.
+        let mut out = vec![];
+        renderer.render_diff(&prev, &mut next, &mut out);
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1b[1;2H中\x1b[5CZ");
+
+        // A wide grapheme.
+        let mut prev = Flat2DArray::new(width(4) + height(1), GraphemeCell::default());
+        let mut renderer = AnsiRenderer::new();
+        renderer.render_full(&mut prev, &mut vec![]);
+        let mut next = prev.clone();
+        next[0][0] = GraphemeCell {
+            grapheme: String::from("👍"),
+            style: AnsiStyle::default(),
+        };
+        next[0][1] = GraphemeCell {
+            grapheme: String::new(),
+            style: AnsiStyle::default(),
+        };
+        next[0][2] = GraphemeCell {
+            grapheme: String::from("x"),
+            style: AnsiStyle::default(),
+        };
+        let mut out = vec![];
+        renderer.render_diff(&prev, &mut next, &mut out);
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1b[H👍x");
+    }
+
+    #[test]
+    fn test_changed_runs_styled_gap() {
+        let cell = |glyph, fg| AnsiCell {
+            glyph,
+            style: AnsiStyle {
+                fg,
+                ..AnsiStyle::default()
+            },
+        };
+        let prev = [
+            cell('a', None),
+            cell('b', None),
+            cell('c', Some(1)),
+            cell('d', None),
+        ];
+
+        // Rewriting the gap would need 2 SGR sequences (to its style, and back).
+        let next = [
+            cell('X', None),
+            cell('b', None),
+            cell('c', Some(1)),
+            cell('Y', None),
+        ];
+        assert_eq!(changed_runs(&prev, &next), vec![(0, 1), (3, 4)]);
+
+        // The gap is only rewritten when all of it has the style of the end of the run
+        // before it.
+        let next = [
+            cell('X', Some(1)),
+            cell('b', None),
+            cell('c', Some(1)),
+            cell('Y', None),
+        ];
+        assert_eq!(changed_runs(&prev, &next), vec![(0, 1), (3, 4)]);
+        let prev = [
+            cell('a', None),
+            cell('b', Some(1)),
+            cell('c', Some(1)),
+            cell('d', None),
+        ];
+        let next = [
+            cell('X', Some(1)),
+            cell('b', Some(1)),
+            cell('c', Some(1)),
+            cell('Y', None),
+        ];
+        assert_eq!(changed_runs(&prev, &next), vec![(0, 4)]);
+
+        // The merged run is painted after a single cursor move.
+        let mut prev_frame = Flat2DArray::new(width(4) + height(1), AnsiCell::default());
+        let mut renderer = AnsiRenderer::new();
+        for (col_index, it) in prev.iter().enumerate() {
+            prev_frame[0][col_index] = *it;
+        }
+        renderer.render_full(&mut prev_frame, &mut vec![]);
+        let mut next_frame = prev_frame.clone();
+        for (col_index, it) in next.iter().enumerate() {
+            next_frame[0][col_index] = *it;
+        }
+        let mut out = vec![];
+        renderer.render_diff(&prev_frame, &mut next_frame, &mut out);
+        assert_eq!(out, b"\x1b[H\x1b[0;38;5;1mXbc\x1b[0mY");
+    }
+
+    #[test]
+    fn test_render_full() {
+        let mut next = frame(&["ab", "cd"]);
+        let mut renderer = AnsiRenderer::new();
+        let mut out = vec![];
+        renderer.render_full(&mut next, &mut out);
+        assert_eq!(out, b"\x1b[Hab\x1b[2;1Hcd");
+        assert!(next.is_clean());
+    }
+
+    #[test]
+    fn test_render_diff_minimal_output() {
+        let mut prev = frame(&["abcdefghij", "klmnopqrst"]);
+        let mut renderer = AnsiRenderer::new();
+        renderer.render_full(&mut prev, &mut vec![]);
+
+        let mut next = prev.clone();
+        next[0][1] = 'X';
+        next[0][3] = 'Y';
+        next[0][8] = 'Z';
+        next[1][0] = 'K';
+
+        let mut out = vec![];
+        renderer.render_diff(&prev, &mut next, &mut out);
+        // The cursor is unknown after the full render (it ended on the last column), so
+        // the first move is absolute, and the rest are relative.
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[1;2HXcY\x1b[4CZ\x1b[B\rK"
+        );
+        assert!(next.is_clean());
+
+        // Nothing is dirty, so nothing is painted.
+        let mut out = vec![];
+        renderer.render_diff(&next.clone(), &mut next, &mut out);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_render_diff_skips_clean_and_unchanged_rows() {
+        let mut prev = frame(&["ab", "cd", "ef"]);
+        prev.clear_dirty_rows();
+        let mut next = prev.clone();
+
+        // Tracked, but the value is the same.
+        next[row(2) + col(0)][0] = 'e';
+
+        let mut renderer = AnsiRenderer::new();
+        let mut out = vec![];
+        renderer.render_diff(&prev, &mut next, &mut out);
+        assert!(out.is_empty());
+
+        next[0][0] = 'X';
+        renderer.render_diff(&prev, &mut next, &mut out);
+        assert_eq!(out, b"\x1b[HX");
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "in a row that isn't dirty")]
+    fn test_render_diff_next_must_match_prev_in_clean_rows() {
+        // Like a double buffer that isn't copied before drawing: row 0 differs, but it
+        // isn't dirty.
+        let mut prev = frame(&["ab", "cd"]);
+        prev.clear_dirty_rows();
+        let mut next = prev.clone();
+        next[0][0] = 'X';
+        next.clear_dirty_rows();
+        AnsiRenderer::new().render_diff(&prev, &mut next, &mut vec![]);
+    }
+
+    #[test]
+    fn test_render_diff_style_changes_only_when_needed() {
+        let red = AnsiStyle {
+            fg: Some(1),
+            bold: true,
+            ..AnsiStyle::default()
+        };
+        let plain = AnsiCell::default();
+        let mut prev = Flat2DArray::new(width(4) + height(1), plain);
+        let mut renderer = AnsiRenderer::new();
+        let mut out = vec![];
+        renderer.render_full(&mut prev, &mut out);
+        assert_eq!(out, b"\x1b[H\x1b[0m    ");
+
+        let mut next = prev.clone();
+        for col_index in 0..3 {
+            next[0][col_index] = AnsiCell {
+                glyph: 'r',
+                style: red,
+            };
+        }
+
+        let mut out = vec![];
+        renderer.render_diff(&prev, &mut next, &mut out);
+        assert_eq!(out, b"\x1b[H\x1b[0;1;38;5;1mrrr");
+
+        // The style is remembered across frames, until invalidated.
+        let prev = next.clone();
+        next[0][1] = AnsiCell {
+            glyph: 'R',
+            style: red,
+        };
+        let mut out = vec![];
+        renderer.render_diff(&prev, &mut next, &mut out);
+        assert_eq!(out, b"\x1b[2DR");
+
+        renderer.invalidate();
+        let prev = next.clone();
+        next[0][1] = AnsiCell {
+            glyph: 'r',
+            style: red,
+        };
+        let mut out = vec![];
+        renderer.render_diff(&prev, &mut next, &mut out);
+        assert_eq!(out, b"\x1b[1;2H\x1b[0;1;38;5;1mr");
+    }
+
+    #[test]
+    fn test_render_diff_grapheme_cells() {
+        let mut prev = Flat2DArray::new(width(3) + height(1), GraphemeCell::default());
+        let mut renderer = AnsiRenderer::new();
+        renderer.render_full(&mut prev, &mut vec![]);
+
+        let mut next = prev.clone();
+        next[0][1] = GraphemeCell {
+            grapheme: String::from("e\u{301}"),
+            style: AnsiStyle::default(),
+        };
+
+        let mut out = vec![];
+        renderer.render_diff(&prev, &mut next, &mut out);
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1b[1;2He\u{301}");
+    }
+
+    #[test]
+    fn test_render_diff_size_change_paints_everything() {
+        let prev = frame(&["ab"]);
+        let mut next = frame(&["ab", "cd"]);
+        next.clear_dirty_rows();
+        let mut out = vec![];
+        AnsiRenderer::new().render_diff(&prev, &mut next, &mut out);
+        assert_eq!(out, b"\x1b[Hab\x1b[2;1Hcd");
+    }
+}
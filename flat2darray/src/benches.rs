@@ -10,6 +10,7 @@
 
 extern crate test;
 
+use crate::ansi_renderer::{AnsiCell, AnsiRenderer, AnsiStyle, RenderCell};
 use crate::flat_2d_array::Flat2DArray;
 use crate::vec_2d_array::Vec2DArray;
 use r3bl_tui::{ColWidth, PixelChar, RowHeight, Size, height, width};
@@ -268,3 +269,97 @@ fn spills_l1_g6_traversal_by_row_col_flat1darray_simd(b: &mut Bencher) {
             });
     });
 }
+
+// GROUP 7: Render Benchmarks
+//
+// Spills L1 Cache (200x100) Benchmarks:
+// - 1. Naive full ANSI repaint  -> moves to each row, and emits the style of every cell
+// - 2. AnsiRenderer render_full -> paints every cell
+// - 3. AnsiRenderer render_diff -> 1 dirty row w/ a few changed cells
+// - 4. AnsiRenderer render_diff -> no dirty rows, so the diff is skipped
+//
+// All 4 produce the ANSI bytes that paint the frame on a terminal, so they are like for
+// like. (1) is the baseline, ie: what a renderer that doesn't track the cursor, style,
+// or dirty rows does on every frame.
+
+fn styled_cell(glyph: char) -> AnsiCell {
+    AnsiCell {
+        glyph,
+        style: AnsiStyle {
+            fg: Some(2),
+            ..AnsiStyle::default()
+        },
+    }
+}
+
+/// Paint every cell w/ its own SGR sequence, after an absolute move to each row.
+fn naive_full_repaint(grid: &Flat2DArray<AnsiCell>, out: &mut Vec<u8>) {
+    use std::io::Write as _;
+    let cols = grid.cols.as_usize();
+    for (row_index, row) in grid.data.chunks_exact(cols).enumerate() {
+        let _ = write!(out, "\x1b[{};1H", row_index + 1);
+        for cell in row {
+            AnsiCell::write_style(&cell.style, out);
+            cell.write_glyph(out);
+        }
+    }
+}
+
+#[bench]
+fn spills_l1_g7_render_naive_full_repaint_flat1darray(b: &mut Bencher) {
+    let size = spills_l1_cache::size();
+    let grid = Flat2DArray::<AnsiCell>::new(size, AnsiCell::default());
+    let mut out = Vec::new();
+    b.iter(|| {
+        out.clear();
+        naive_full_repaint(&grid, &mut out);
+        let _ = black_box(&out);
+    });
+}
+
+#[bench]
+fn spills_l1_g7_render_full_flat1darray(b: &mut Bencher) {
+    let size = spills_l1_cache::size();
+    let mut grid = Flat2DArray::<AnsiCell>::new(size, AnsiCell::default());
+    let mut renderer = AnsiRenderer::new();
+    let mut out = Vec::new();
+    b.iter(|| {
+        out.clear();
+        renderer.invalidate();
+        renderer.render_full(&mut grid, &mut out);
+        let _ = black_box(&out);
+    });
+}
+
+#[bench]
+fn spills_l1_g7_render_diff_one_dirty_row_flat1darray(b: &mut Bencher) {
+    let size = spills_l1_cache::size();
+    let prev = Flat2DArray::<AnsiCell>::new(size, AnsiCell::default());
+    let mut next = prev.clone();
+    next.clear_dirty_rows();
+    let mut renderer = AnsiRenderer::new();
+    let mut out = Vec::new();
+    b.iter(|| {
+        out.clear();
+        next[50][10] = styled_cell('a');
+        next[50][12] = styled_cell('b');
+        next[50][150] = styled_cell('c');
+        renderer.render_diff(&prev, &mut next, &mut out);
+        let _ = black_box(&out);
+    });
+}
+
+#[bench]
+fn spills_l1_g7_render_diff_clean_flat1darray(b: &mut Bencher) {
+    let size = spills_l1_cache::size();
+    let prev = Flat2DArray::<AnsiCell>::new(size, AnsiCell::default());
+    let mut next = prev.clone();
+    next.clear_dirty_rows();
+    let mut renderer = AnsiRenderer::new();
+    let mut out = Vec::new();
+    b.iter(|| {
+        out.clear();
+        renderer.render_diff(&prev, &mut next, &mut out);
+        let _ = black_box(&out);
+    });
+}
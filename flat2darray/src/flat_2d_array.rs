@@ -2,7 +2,7 @@
 
 #![allow(dead_code, unused_imports, clippy::wildcard_imports)]
 
-use r3bl_tui::{ColIndex, ColWidth, Pos, RowHeight, RowIndex, Size, height, width};
+use r3bl_tui::{ColIndex, ColWidth, Pos, RowHeight, RowIndex, Size, col, height, row, width};
 use std::{
     fmt::Debug,
     ops::{Index, IndexMut},
@@ -81,14 +81,42 @@ use std::{
 /// Example: `index 5` with `width 3`
 /// - `row = index / width = 5 / 3 = 1`
 /// - `col = index % width = 5 % 3 = 2`
-#[derive(Debug, Clone, PartialEq)]
-pub struct Flat2DArray<T: Copy + PartialEq> {
-    pub data: Box<[T]>,
+///
+/// # Dirty Rows
+///
+/// Each row has a dirty bit in `dirty_rows` (64 rows per `u64`), which is set by every
+/// write through [IndexMut] (and by whole grid operations like `clear` and
+/// `scroll_up`). A renderer only has to diff the dirty rows, and then call
+/// [Flat2DArray::clear_dirty_rows]. `data` and `dirty_rows` aren't public, so that
+/// every write goes through one of these, and is tracked. Use [Flat2DArray::as_slice] to
+/// read all the cells at once.
+///
+/// ```text
+/// dirty_rows[0]: 0b...0100  <-- Only row 2 has to be diffed.
+/// ```
+///
+/// # Soft Wrapped Rows
+///
+/// A row is soft wrapped when its line of text continues on the next row (the cursor
+/// ran past the last column), as opposed to ending w/ a newline. These flags are kept
+/// in `soft_wrapped_rows`, move w/ their rows when scrolling, and let
+/// [Flat2DArray::resize_reflow] re-join and re-wrap the lines at the new width.
+///
+/// # Cell Types
+///
+/// Any `Clone + PartialEq` cell can be stored, eg a grapheme cluster plus its style.
+/// The `simd_*` fast paths use `memset` / `memmove` and so are only available for `Copy`
+/// cells.
+#[derive(Debug, Clone)]
+pub struct Flat2DArray<T: Clone + PartialEq> {
+    pub(crate) data: Box<[T]>,
     pub rows: RowHeight,
     pub cols: ColWidth,
+    pub(crate) dirty_rows: Box<[u64]>,
+    pub soft_wrapped_rows: Box<[bool]>,
 }
 
-impl<T: Copy + PartialEq + Debug> Flat2DArray<T> {
+impl<T: Clone + PartialEq + Debug> Flat2DArray<T> {
     pub fn new(arg_size: impl Into<Size>, default: T) -> Self {
         let size = arg_size.into();
         let num_cells = size.row_height.as_usize() * size.col_width.as_usize();
@@ -97,6 +125,9 @@ impl<T: Copy + PartialEq + Debug> Flat2DArray<T> {
             data: vec_1d.into_boxed_slice(),
             rows: size.row_height,
             cols: size.col_width,
+            // Nothing has been rendered yet, so every row starts out dirty.
+            dirty_rows: dirty_rows::new_all_dirty(size.row_height.as_usize()),
+            soft_wrapped_rows: vec![false; size.row_height.as_usize()].into_boxed_slice(),
         }
     }
 
@@ -106,18 +137,23 @@ impl<T: Copy + PartialEq + Debug> Flat2DArray<T> {
         for row in 0..rows {
             for col in 0..cols {
                 let index = row * cols + col;
-                self.data[index] = it;
+                self.data[index] = it.clone();
             }
         }
+        self.soft_wrapped_rows.fill(false);
+        self.mark_all_rows_dirty();
     }
 
-    pub fn simd_clear(&mut self, it: T) {
-        self.data.fill(it);
+    /// All the cells, row by row.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
     }
 
     pub fn get_mem_size(&self) -> usize {
         let mut total = std::mem::size_of::<Self>();
         total += self.data.len() * std::mem::size_of::<T>();
+        total += self.dirty_rows.len() * std::mem::size_of::<u64>();
+        total += self.soft_wrapped_rows.len() * std::mem::size_of::<bool>();
         total
     }
 
@@ -246,16 +282,226 @@ impl<T: Copy + PartialEq + Debug> Flat2DArray<T> {
                 let src_index = (row + 1) * cols + col;
                 let dest_index = row * cols + col;
                 // Move the value from the next row to the current row.
-                self.data[dest_index] = self.data[src_index];
+                self.data[dest_index] = self.data[src_index].clone();
             }
+            self.soft_wrapped_rows[row] = self.soft_wrapped_rows[row + 1];
         }
 
         // Duplicate the last row from the second-to-last row.
         for col in 0..cols {
             let src_index = (rows - 2) * cols + col;
             let dest_index = (rows - 1) * cols + col;
-            self.data[dest_index] = self.data[src_index];
+            self.data[dest_index] = self.data[src_index].clone();
+        }
+
+        self.mark_all_rows_dirty();
+    }
+
+    /// Scrolls the rows from `top` to `bottom` (inclusive) up by `count` rows, like a
+    /// terminal w/ a scroll region set by DECSTBM. The rows outside the region don't
+    /// move, the top `count` rows of the region are lost, and the bottom `count` rows
+    /// are set to `fill`.
+    ///
+    /// - `bottom` is clipped to the last row, and nothing happens if `top > bottom`.
+    /// - `count` is clipped to the height of the region.
+    ///
+    /// # ASCII Diagram
+    /// ```text
+    /// Before:              After scroll_up_in_region(1, 3, 1, .):
+    /// [ Row 0 ]            [ Row 0 ]
+    /// [ Row 1 ] <- top     [ Row 2 ]  <-- Shifted up, w/in the region
+    /// [ Row 2 ]       ==>  [ Row 3 ]
+    /// [ Row 3 ] <- bottom  [ . . . ]  <-- Filled
+    /// [ Row 4 ]            [ Row 4 ]  <-- Outside the region, so untouched
+    /// ```
+    pub fn scroll_up_in_region(&mut self, top: RowIndex, bottom: RowIndex, count: usize, fill: T) {
+        let Some((region, count)) = self.scroll_region_range(top, bottom, count) else {
+            return;
+        };
+        let cols = self.cols.as_usize();
+        let (start, end) = (region.start, region.end);
+
+        // Rotate (rather than copy) so that this works for cells that aren't `Copy`.
+        self.data[start * cols..end * cols].rotate_left(count * cols);
+        self.data[(end - count) * cols..end * cols].fill(fill);
+        self.soft_wrapped_rows[start..end].rotate_left(count);
+        self.soft_wrapped_rows[end - count..end].fill(false);
+    }
+
+    /// Same as [Flat2DArray::scroll_up_in_region], but the rows move down, the bottom
+    /// `count` rows of the region are lost, and the top `count` rows are set to `fill`.
+    ///
+    /// # ASCII Diagram
+    /// ```text
+    /// Before:              After scroll_down_in_region(1, 3, 1, .):
+    /// [ Row 0 ]            [ Row 0 ]
+    /// [ Row 1 ] <- top     [ . . . ]  <-- Filled
+    /// [ Row 2 ]       ==>  [ Row 1 ]  <-- Shifted down, w/in the region
+    /// [ Row 3 ] <- bottom  [ Row 2 ]
+    /// [ Row 4 ]            [ Row 4 ]  <-- Outside the region, so untouched
+    /// ```
+    pub fn scroll_down_in_region(
+        &mut self,
+        top: RowIndex,
+        bottom: RowIndex,
+        count: usize,
+        fill: T,
+    ) {
+        let Some((region, count)) = self.scroll_region_range(top, bottom, count) else {
+            return;
+        };
+        let cols = self.cols.as_usize();
+        let (start, end) = (region.start, region.end);
+
+        self.data[start * cols..end * cols].rotate_right(count * cols);
+        self.data[start * cols..(start + count) * cols].fill(fill);
+        self.soft_wrapped_rows[start..end].rotate_right(count);
+        self.soft_wrapped_rows[start..start + count].fill(false);
+    }
+
+    /// The range of rows in the (clipped) region, and the number of rows to shift by.
+    /// Marks the rows of the region dirty. Returns `None` if there is nothing to scroll.
+    fn scroll_region_range(
+        &mut self,
+        top: RowIndex,
+        bottom: RowIndex,
+        count: usize,
+    ) -> Option<(std::ops::Range<usize>, usize)> {
+        let top = top.as_usize();
+        let bottom = bottom.as_usize().min(self.rows.as_usize().checked_sub(1)?);
+        if top > bottom || count == 0 {
+            return None;
         }
+
+        let count = count.min(bottom - top + 1);
+        for row_index in top..=bottom {
+            self.mark_row_dirty(row_index);
+        }
+
+        Some((top..bottom + 1, count))
+    }
+}
+
+mod resize {
+    use super::*;
+
+    impl<T: Clone + PartialEq + Debug> Flat2DArray<T> {
+        /// Resizes the grid to `arg_new_size`, keeping the overlapping top left region,
+        /// and setting the new cells to `fill`. Every row is marked dirty.
+        ///
+        /// Soft wrap flags are only kept if the width doesn't change, since a row that's
+        /// been truncated or padded no longer runs into the next one. Use
+        /// [Flat2DArray::resize_reflow] to re-wrap the text instead.
+        ///
+        /// # ASCII Diagram
+        /// ```text
+        /// Before (3 x 3):   After resize(4 x 2, .):
+        /// [ a b c ]         [ a b c . ]
+        /// [ d e f ]   ==>   [ d e f . ]
+        /// [ g h i ]                      <-- Clipped
+        /// ```
+        pub fn resize(&mut self, arg_new_size: impl Into<Size>, fill: T) {
+            let new_size = arg_new_size.into();
+            let mut it = Self::new(new_size, fill);
+
+            let origin = row(0) + col(0);
+            it.blit(&self.view(origin, new_size), origin);
+
+            if it.cols == self.cols {
+                let rows = it.rows.as_usize().min(self.rows.as_usize());
+                it.soft_wrapped_rows[..rows].copy_from_slice(&self.soft_wrapped_rows[..rows]);
+            }
+
+            *self = it;
+        }
+
+        /// Resizes the grid to `arg_new_size` like a terminal does. Rows joined by soft
+        /// wraps are treated as one line, which is re-wrapped at the new width. Each line
+        /// ends at its last cell that isn't `fill`. Every row is marked dirty.
+        ///
+        /// If the re-wrapped lines don't fit, the blank rows at the bottom are dropped
+        /// first, and then the rows at the top (like they'd scroll into the scrollback),
+        /// so that the most recent output is kept.
+        ///
+        /// # ASCII Diagram
+        /// ```text
+        /// Before (4 x 3):       After resize_reflow(3 x 4, .):
+        /// [ a b c d ] ~         [ a b c ] ~
+        /// [ e f . . ]     ==>   [ d e f ]
+        /// [ g . . . ]           [ g . . ]
+        ///                       [ . . . ]
+        ///
+        /// Before (4 x 3):       After resize_reflow(2 x 3, .):
+        /// [ a b c d ] ~         [ c d ] ~   <-- "a b" is dropped from the top
+        /// [ e f . . ]     ==>   [ e f ]
+        /// [ g . . . ]           [ g . ]
+        ///
+        /// ~ means the row is soft wrapped.
+        /// ```
+        pub fn resize_reflow(&mut self, arg_new_size: impl Into<Size>, fill: T) {
+            let new_size = arg_new_size.into();
+            let mut it = Self::new(new_size, fill.clone());
+
+            let old_cols = self.cols.as_usize();
+            let new_cols = it.cols.as_usize();
+            let new_rows = it.rows.as_usize();
+            if old_cols == 0 || new_cols == 0 {
+                *self = it;
+                return;
+            }
+
+            let mut lines: Vec<Vec<T>> = vec![];
+            let mut line: Vec<T> = Vec::with_capacity(old_cols);
+            let last_row = self.rows.as_usize().saturating_sub(1);
+
+            for (row_index, row) in self.data.chunks_exact(old_cols).enumerate() {
+                line.extend_from_slice(row);
+                if self.soft_wrapped_rows[row_index] && row_index < last_row {
+                    continue;
+                }
+
+                // The line is complete, so drop its trailing blanks.
+                while line.last() == Some(&fill) {
+                    line.pop();
+                }
+                lines.push(std::mem::take(&mut line));
+            }
+
+            // Each re-wrapped row, and whether it is soft wrapped.
+            let mut out_rows: Vec<(&[T], bool)> = vec![];
+            for line in &lines {
+                // An empty line still takes up a row.
+                if line.is_empty() {
+                    out_rows.push((&[], false));
+                    continue;
+                }
+                let num_chunks = line.len().div_ceil(new_cols);
+                for (chunk_index, chunk) in line.chunks(new_cols).enumerate() {
+                    out_rows.push((chunk, chunk_index + 1 < num_chunks));
+                }
+            }
+
+            // Drop the blank rows at the bottom, and then the oldest rows at the top.
+            while out_rows.len() > new_rows && out_rows.last().is_some_and(|it| it.0.is_empty()) {
+                out_rows.pop();
+            }
+            let excess = out_rows.len().saturating_sub(new_rows);
+
+            for (row_index, (cells, soft_wrapped)) in out_rows[excess..].iter().enumerate() {
+                it.data[row_index * new_cols..][..cells.len()].clone_from_slice(cells);
+                it.soft_wrapped_rows[row_index] = *soft_wrapped;
+            }
+
+            *self = it;
+        }
+    }
+}
+
+impl<T: Copy + PartialEq + Debug> Flat2DArray<T> {
+    pub fn simd_clear(&mut self, it: T) {
+        self.data.fill(it);
+        self.soft_wrapped_rows.fill(false);
+        self.mark_all_rows_dirty();
     }
 
     /// Scrolls the grid up by one row.
@@ -275,13 +521,78 @@ impl<T: Copy + PartialEq + Debug> Flat2DArray<T> {
     pub fn simd_scroll_up(&mut self) {
         let src_range = self.cols.as_usize()..;
         self.data.copy_within(src_range, /*starting index*/ 0);
+        if !self.soft_wrapped_rows.is_empty() {
+            self.soft_wrapped_rows.copy_within(1.., 0);
+        }
+        self.mark_all_rows_dirty();
+    }
+}
+
+/// Compares the cells, the size, and the soft wraps, but not the `dirty_rows`, which are
+/// only a rendering hint.
+impl<T: Clone + PartialEq> PartialEq for Flat2DArray<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.rows == other.rows
+            && self.cols == other.cols
+            && self.data == other.data
+            && self.soft_wrapped_rows == other.soft_wrapped_rows
+    }
+}
+
+mod dirty_rows {
+    use super::*;
+
+    const BITS_PER_WORD: usize = u64::BITS as usize;
+
+    pub fn new_all_dirty(rows: usize) -> Box<[u64]> {
+        vec![u64::MAX; rows.div_ceil(BITS_PER_WORD)].into_boxed_slice()
+    }
+
+    impl<T: Clone + PartialEq> Flat2DArray<T> {
+        pub fn mark_row_dirty(&mut self, row_index: usize) {
+            self.dirty_rows[row_index / BITS_PER_WORD] |= 1 << (row_index % BITS_PER_WORD);
+        }
+
+        pub fn mark_all_rows_dirty(&mut self) {
+            self.dirty_rows.fill(u64::MAX);
+        }
+
+        pub fn is_row_dirty(&self, row_index: usize) -> bool {
+            self.dirty_rows[row_index / BITS_PER_WORD] & (1 << (row_index % BITS_PER_WORD)) != 0
+        }
+
+        /// `true` if no row has changed since the last [Flat2DArray::clear_dirty_rows], so
+        /// the diff can be skipped entirely.
+        pub fn is_clean(&self) -> bool {
+            self.dirty_rows.iter().all(|word| *word == 0)
+        }
+
+        /// Call this after the frame has been rendered.
+        pub fn clear_dirty_rows(&mut self) {
+            self.dirty_rows.fill(0);
+        }
+
+        /// The indices of the dirty rows, in order. Whole words of clean rows are skipped.
+        pub fn dirty_row_indices(&self) -> impl Iterator<Item = usize> + '_ {
+            let rows = self.rows.as_usize();
+            self.dirty_rows
+                .iter()
+                .enumerate()
+                .filter(|(_, word)| **word != 0)
+                .flat_map(|(word_index, word)| {
+                    (0..BITS_PER_WORD)
+                        .filter(move |bit| word & (1 << bit) != 0)
+                        .map(move |bit| word_index * BITS_PER_WORD + bit)
+                })
+                .take_while(move |row_index| *row_index < rows)
+        }
     }
 }
 
 mod impl_index_usize {
     use super::*;
 
-    impl<T: Copy + PartialEq> Index<usize> for Flat2DArray<T> {
+    impl<T: Clone + PartialEq> Index<usize> for Flat2DArray<T> {
         type Output = [T];
 
         fn index(&self, row_index: usize) -> &Self::Output {
@@ -292,8 +603,9 @@ mod impl_index_usize {
         }
     }
 
-    impl<T: Copy + PartialEq> IndexMut<usize> for Flat2DArray<T> {
+    impl<T: Clone + PartialEq> IndexMut<usize> for Flat2DArray<T> {
         fn index_mut(&mut self, row_index: usize) -> &mut Self::Output {
+            self.mark_row_dirty(row_index);
             let cols = self.cols.as_usize();
             let range_start = row_index * cols;
             let range_end = range_start + cols;
@@ -305,7 +617,7 @@ mod impl_index_usize {
 mod impl_index_pos {
     use super::*;
 
-    impl<T: Copy + PartialEq> Index<Pos> for Flat2DArray<T> {
+    impl<T: Clone + PartialEq> Index<Pos> for Flat2DArray<T> {
         type Output = [T];
 
         fn index(&self, pos: Pos) -> &Self::Output {
@@ -318,9 +630,10 @@ mod impl_index_pos {
         }
     }
 
-    impl<T: Copy + PartialEq> IndexMut<Pos> for Flat2DArray<T> {
+    impl<T: Clone + PartialEq> IndexMut<Pos> for Flat2DArray<T> {
         fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
             let row = pos.row_index.as_usize();
+            self.mark_row_dirty(row);
             let col = pos.col_index.as_usize();
             let cols = self.cols.as_usize();
             let range_start = row * cols + col;
@@ -384,10 +697,251 @@ mod tests {
         let mem_size = grid.get_mem_size();
         assert_eq!(
             mem_size,
-            std::mem::size_of::<Flat2DArray<usize>>() + 100 * std::mem::size_of::<usize>()
+            std::mem::size_of::<Flat2DArray<usize>>()
+                + 100 * std::mem::size_of::<usize>()
+                + /*dirty bits for 10 rows*/ std::mem::size_of::<u64>()
+                + /*soft wrap flags*/ 10 * std::mem::size_of::<bool>()
         );
     }
 
+    /// A grid of `char` from `lines`, where a line ending w/ `~` is soft wrapped.
+    fn text(cols: usize, lines: &[&str]) -> Flat2DArray<char> {
+        let mut it = Flat2DArray::new(width(cols) + height(lines.len()), '.');
+        for (row_index, line) in lines.iter().enumerate() {
+            let cells = line.trim_end_matches('~');
+            for (col_index, glyph) in cells.chars().enumerate() {
+                it[row_index][col_index] = glyph;
+            }
+            it.soft_wrapped_rows[row_index] = line.ends_with('~');
+        }
+        it
+    }
+
+    #[test]
+    fn test_resize() {
+        let mut array = text(3, &["abc", "def", "ghi"]);
+        array.clear_dirty_rows();
+
+        // Wider and shorter.
+        array.resize(width(4) + height(2), '.');
+        assert_eq!(array, text(4, &["abc.", "def."]));
+        assert_eq!(array.dirty_row_indices().count(), 2);
+
+        // Narrower and taller.
+        array.resize(width(2) + height(3), '.');
+        assert_eq!(array, text(2, &["ab", "de", ".."]));
+
+        // Soft wraps are only kept if the width doesn't change.
+        let mut array = text(2, &["ab~", "cd"]);
+        array.resize(width(2) + height(3), '.');
+        assert_eq!(array, text(2, &["ab~", "cd", ".."]));
+        array.resize(width(3) + height(3), '.');
+        assert_eq!(array, text(3, &["ab.", "cd.", "..."]));
+
+        // Down to nothing, and back.
+        array.resize(width(0) + height(0), '.');
+        assert!(array.data.is_empty());
+        array.resize(width(2) + height(1), '.');
+        assert_eq!(array, text(2, &[".."]));
+    }
+
+    #[test]
+    fn test_resize_reflow() {
+        // Narrower: the soft wrapped line is re-joined and re-wrapped.
+        let mut array = text(4, &["abcd~", "ef..", "g...", "...."]);
+        array.resize_reflow(width(3) + height(4), '.');
+        assert_eq!(array, text(3, &["abc~", "def", "g..", "..."]));
+
+        // Wider: the line fits on one row again.
+        array.resize_reflow(width(6) + height(3), '.');
+        assert_eq!(array, text(6, &["abcdef", "g.....", "......"]));
+
+        // Much narrower: the blank row at the bottom is dropped first, and then the
+        // oldest rows at the top, so the most recent output is kept.
+        array.resize_reflow(width(2) + height(3), '.');
+        assert_eq!(array, text(2, &["cd~", "ef", "g."]));
+
+        // The example from the docs.
+        let mut array = text(4, &["abcd~", "ef..", "g..."]);
+        array.resize_reflow(width(2) + height(3), '.');
+        assert_eq!(array, text(2, &["cd~", "ef", "g."]));
+
+        // Blank rows in between lines are kept.
+        let mut array = text(2, &["ab", "..", "cd"]);
+        array.resize_reflow(width(1) + height(4), '.');
+        assert_eq!(array, text(1, &["b", ".", "c~", "d"]));
+
+        // A soft wrap on the last row has nothing to join w/.
+        let mut array = text(2, &["ab", "cd~"]);
+        array.resize_reflow(width(4) + height(2), '.');
+        assert_eq!(array, text(4, &["ab..", "cd.."]));
+
+        // Trailing blanks in the middle of a line are kept.
+        let mut array = text(3, &["a..~", "b.."]);
+        array.resize_reflow(width(5) + height(1), '.');
+        assert_eq!(array, text(5, &["a..b."]));
+
+        // Zero width.
+        array.resize_reflow(width(0) + height(2), '.');
+        assert!(array.data.is_empty());
+        array.resize_reflow(width(2) + height(1), '.');
+        assert_eq!(array, text(2, &[".."]));
+    }
+
+    #[test]
+    fn test_soft_wraps_move_when_scrolling() {
+        let mut array = text(2, &["ab~", "cd", "ef~", "gh"]);
+        array.scroll_up_in_region(row(0), row(2), 1, '.');
+        assert_eq!(array, text(2, &["cd", "ef~", "..", "gh"]));
+
+        array.scroll_down_in_region(row(1), row(3), 1, '.');
+        assert_eq!(array, text(2, &["cd", "..", "ef~", ".."]));
+
+        array.simd_scroll_up();
+        assert_eq!(array, text(2, &["..", "ef~", "..", ".."]));
+
+        array.clear('.');
+        assert_eq!(array, text(2, &["..", "..", "..", ".."]));
+    }
+
+    /// A cell that isn't `Copy`, like a grapheme cluster plus its style.
+    #[derive(Debug, Clone, PartialEq)]
+    struct Cell {
+        grapheme: String,
+        bold: bool,
+    }
+
+    fn cell(grapheme: &str) -> Cell {
+        Cell {
+            grapheme: grapheme.to_string(),
+            bold: false,
+        }
+    }
+
+    #[test]
+    fn test_non_copy_cells() {
+        let mut array = Flat2DArray::new(width(2) + height(3), cell(" "));
+        array[0][0] = cell("e\u{301}");
+        array[1][1] = cell("👍");
+        array[2][0].bold = true;
+
+        let before = array.clone();
+        array.scroll_up();
+        assert_eq!(array.diff(&before).len(), 4);
+        assert_eq!(array[0][1], cell("👍"));
+
+        array.scroll_down_in_region(row(0), row(2), 1, cell(" "));
+        assert_eq!(array[1][1], cell("👍"));
+
+        array.resize(width(3) + height(2), cell(" "));
+        assert_eq!(array[1][1], cell("👍"));
+
+        // The blank first row stays, and the second is wrapped into 2 rows.
+        array.resize_reflow(width(1) + height(4), cell(" "));
+        assert_eq!(array[2][0], cell("👍"));
+        assert!(array.soft_wrapped_rows[1]);
+
+        array.clear(cell(" "));
+        assert!(array.data.iter().all(|it| *it == cell(" ")));
+    }
+
+    /// A 5 x 2 array where each cell is its row index.
+    fn rows_numbered() -> Flat2DArray<usize> {
+        let mut it = Flat2DArray::new(width(2) + height(5), 0usize);
+        for (index, cell) in it.data.iter_mut().enumerate() {
+            *cell = index / 2;
+        }
+        it
+    }
+
+    #[test]
+    fn test_scroll_in_region() {
+        // Scroll up w/in rows 1..=3, rows 0 and 4 don't move.
+        let mut array = rows_numbered();
+        array.clear_dirty_rows();
+        array.scroll_up_in_region(row(1), row(3), 1, 9);
+        assert_eq!(&array.data[..], &[0, 0, 2, 2, 3, 3, 9, 9, 4, 4]);
+        assert_eq!(array.dirty_row_indices().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        // Scroll down w/in rows 1..=3.
+        let mut array = rows_numbered();
+        array.scroll_down_in_region(row(1), row(3), 2, 9);
+        assert_eq!(&array.data[..], &[0, 0, 9, 9, 9, 9, 1, 1, 4, 4]);
+
+        // Full screen region behaves like a terminal scroll (no duplicated row).
+        let mut array = rows_numbered();
+        array.scroll_up_in_region(row(0), row(4), 1, 9);
+        assert_eq!(&array.data[..], &[1, 1, 2, 2, 3, 3, 4, 4, 9, 9]);
+    }
+
+    #[test]
+    fn test_scroll_in_region_clipping() {
+        // Count larger than the region clears the whole region.
+        let mut array = rows_numbered();
+        array.scroll_up_in_region(row(3), row(4), 10, 9);
+        assert_eq!(&array.data[..], &[0, 0, 1, 1, 2, 2, 9, 9, 9, 9]);
+
+        // Bottom past the last row is clipped to the last row.
+        let mut array = rows_numbered();
+        array.scroll_down_in_region(row(3), row(99), 1, 9);
+        assert_eq!(&array.data[..], &[0, 0, 1, 1, 2, 2, 9, 9, 3, 3]);
+
+        // Single row region.
+        let mut array = rows_numbered();
+        array.scroll_up_in_region(row(2), row(2), 1, 9);
+        assert_eq!(&array.data[..], &[0, 0, 1, 1, 9, 9, 3, 3, 4, 4]);
+
+        // Nothing happens for an inverted or out of bounds region, or a 0 count.
+        let mut array = rows_numbered();
+        array.clear_dirty_rows();
+        array.scroll_up_in_region(row(3), row(1), 1, 9);
+        array.scroll_down_in_region(row(5), row(9), 1, 9);
+        array.scroll_up_in_region(row(0), row(4), 0, 9);
+        assert_eq!(array, rows_numbered());
+        assert!(array.is_clean());
+
+        // An empty array has no rows to scroll.
+        let mut array = Flat2DArray::new(width(2) + height(0), 0usize);
+        array.scroll_up_in_region(row(0), row(0), 1, 9);
+        assert!(array.data.is_empty());
+    }
+
+    #[test]
+    fn test_dirty_rows() {
+        let w = width(3);
+        let h = height(70); // Spans 2 words of dirty bits.
+        let s = w + h;
+        let mut grid = Flat2DArray::new(s, 0usize);
+
+        // Everything is dirty until the first render.
+        assert!(grid.is_row_dirty(0));
+        assert!(grid.is_row_dirty(69));
+        assert_eq!(grid.dirty_row_indices().count(), 70);
+
+        grid.clear_dirty_rows();
+        assert!(grid.is_clean());
+        assert_eq!(grid.dirty_row_indices().count(), 0);
+
+        // IndexMut writes mark the row.
+        grid[2][0] = 1;
+        grid[row(65) + col(1)][0] = 1;
+        assert!(!grid.is_clean());
+        assert_eq!(grid.dirty_row_indices().collect::<Vec<_>>(), vec![2, 65]);
+
+        // Reads don't.
+        let _ = grid[3][0];
+        assert!(!grid.is_row_dirty(3));
+
+        // Dirty bits don't affect equality.
+        let mut other = grid.clone();
+        other.clear_dirty_rows();
+        assert_eq!(grid, other);
+
+        // Whole grid operations mark every row.
+        other.simd_scroll_up();
+        assert_eq!(other.dirty_row_indices().count(), 70);
+    }
+
     #[test]
     fn test_print_screen() {
         let w = width(3);
@@ -7,5 +7,7 @@
 mod benches;
 
 // Attach files.
+pub mod ansi_renderer;
 pub mod flat_2d_array;
 pub mod vec_2d_array;
+pub mod view;
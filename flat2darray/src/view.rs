@@ -0,0 +1,398 @@
+// Copyright (c) 2026 Nazmul Idris. Licensed under Apache License, Version 2.0.
+
+#![allow(dead_code, clippy::wildcard_imports)]
+
+use crate::flat_2d_array::Flat2DArray;
+use r3bl_tui::{ColIndex, Pos, RowIndex, Size, height, width};
+use std::ops::Range;
+
+/// A rectangle of a [Flat2DArray], clipped to its bounds, in `usize` (0 based).
+///
+/// ```text
+///        col 0   col 1   col 2   col 3
+///      ┌───────┬───────┬───────┬───────┐
+/// row 0│       │       │       │       │
+///      ├───────┼───────┼───────┼───────┤
+/// row 1│       │ ░░░░░ │ ░░░░░ │ ░░░░░ │ ░░░░░   <-- origin (1, 1), size 2 x 4,
+///      ├───────┼───────┼───────┼───────┤              clipped to 2 x 3
+/// row 2│       │ ░░░░░ │ ░░░░░ │ ░░░░░ │ ░░░░░
+///      └───────┴───────┴───────┴───────┘
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rect {
+    row: usize,
+    col: usize,
+    rows: usize,
+    cols: usize,
+}
+
+impl Rect {
+    fn clipped<T: Clone + PartialEq>(array: &Flat2DArray<T>, origin: Pos, size: Size) -> Self {
+        let array_rows = array.rows.as_usize();
+        let array_cols = array.cols.as_usize();
+        let row = origin.row_index.as_usize().min(array_rows);
+        let col = origin.col_index.as_usize().min(array_cols);
+        let rows = size.row_height.as_usize().min(array_rows - row);
+        let cols = size.col_width.as_usize().min(array_cols - col);
+        // A rect w/ no area has no rows either, so that it is never iterated.
+        if rows == 0 || cols == 0 {
+            return Self {
+                row,
+                col,
+                rows: 0,
+                cols: 0,
+            };
+        }
+        Self {
+            row,
+            col,
+            rows,
+            cols,
+        }
+    }
+
+    /// The 1d range of `row_index` (relative to the rect) in the array's `data`.
+    fn row_range(&self, array_cols: usize, row_index: usize) -> Range<usize> {
+        let start = (self.row + row_index) * array_cols + self.col;
+        start..start + self.cols
+    }
+
+    fn origin(&self) -> Pos {
+        RowIndex::from(self.row) + ColIndex::from(self.col)
+    }
+
+    fn size(&self) -> Size {
+        width(self.cols) + height(self.rows)
+    }
+}
+
+/// A borrowed, read only window into a [Flat2DArray]. Created w/ [Flat2DArray::view].
+#[derive(Debug)]
+pub struct View<'a, T: Clone + PartialEq> {
+    array: &'a Flat2DArray<T>,
+    rect: Rect,
+}
+
+// Not derived, since that would require `T: Copy`, and a view is only a reference.
+impl<T: Clone + PartialEq> Clone for View<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Clone + PartialEq> Copy for View<'_, T> {}
+
+/// A borrowed, writable window into a [Flat2DArray]. Created w/ [Flat2DArray::view_mut].
+/// Every row that is handed out mutably is marked dirty.
+#[derive(Debug)]
+pub struct ViewMut<'a, T: Clone + PartialEq> {
+    array: &'a mut Flat2DArray<T>,
+    rect: Rect,
+}
+
+mod impl_view {
+    use super::*;
+
+    impl<'a, T: Clone + PartialEq> View<'a, T> {
+        /// Where the view starts in the array, after clipping.
+        pub fn origin(&self) -> Pos {
+            self.rect.origin()
+        }
+
+        /// The size of the view, after clipping.
+        pub fn size(&self) -> Size {
+            self.rect.size()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.rect.rows == 0 || self.rect.cols == 0
+        }
+
+        /// The cells of `row_index`, relative to the view.
+        pub fn row(&self, row_index: usize) -> Option<&'a [T]> {
+            if row_index >= self.rect.rows {
+                return None;
+            }
+            let range = self.rect.row_range(self.array.cols.as_usize(), row_index);
+            Some(&self.array.data[range])
+        }
+
+        /// The cell at `pos`, relative to the view.
+        pub fn get(&self, pos: Pos) -> Option<&'a T> {
+            self.row(pos.row_index.as_usize())?
+                .get(pos.col_index.as_usize())
+        }
+
+        /// Each row of the view, top to bottom.
+        pub fn iter_rows(&self) -> impl DoubleEndedIterator<Item = &'a [T]> + ExactSizeIterator {
+            let array_cols = self.array.cols.as_usize();
+            let (rect, data) = (self.rect, &self.array.data);
+            (0..rect.rows).map(move |row_index| &data[rect.row_range(array_cols, row_index)])
+        }
+    }
+
+    impl<T: Clone + PartialEq> ViewMut<'_, T> {
+        pub fn origin(&self) -> Pos {
+            self.rect.origin()
+        }
+
+        pub fn size(&self) -> Size {
+            self.rect.size()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.rect.rows == 0 || self.rect.cols == 0
+        }
+
+        /// A read only view of the same rectangle.
+        pub fn as_view(&self) -> View<'_, T> {
+            View {
+                array: self.array,
+                rect: self.rect,
+            }
+        }
+
+        /// The cells of `row_index`, relative to the view. Marks the row dirty.
+        pub fn row_mut(&mut self, row_index: usize) -> Option<&mut [T]> {
+            if row_index >= self.rect.rows {
+                return None;
+            }
+            self.array.mark_row_dirty(self.rect.row + row_index);
+            let range = self.rect.row_range(self.array.cols.as_usize(), row_index);
+            Some(&mut self.array.data[range])
+        }
+
+        /// Each row of the view, top to bottom. Marks all of them dirty.
+        pub fn iter_rows_mut(
+            &mut self,
+        ) -> impl DoubleEndedIterator<Item = &mut [T]> + ExactSizeIterator {
+            let rect = self.rect;
+            for row_index in rect.row..rect.row + rect.rows {
+                self.array.mark_row_dirty(row_index);
+            }
+
+            let array_cols = self.array.cols.as_usize();
+            let col_range = rect.col..rect.col + rect.cols;
+            // An empty array has no rows to chunk (and chunks can't be 0 wide).
+            self.array
+                .data
+                .chunks_exact_mut(array_cols.max(1))
+                .skip(rect.row)
+                .take(rect.rows)
+                .map(move |row| &mut row[col_range.clone()])
+        }
+
+        /// Set every cell of the view to `it`.
+        pub fn fill(&mut self, it: T) {
+            for row in self.iter_rows_mut() {
+                row.fill(it.clone());
+            }
+        }
+    }
+}
+
+mod impl_flat_2d_array {
+    use super::*;
+
+    impl<T: Clone + PartialEq> Flat2DArray<T> {
+        /// A window at `origin` w/ `size`, clipped to the bounds of the array. If `origin`
+        /// is outside the array, the view is empty.
+        pub fn view(&self, origin: Pos, size: Size) -> View<'_, T> {
+            View {
+                array: self,
+                rect: Rect::clipped(self, origin, size),
+            }
+        }
+
+        /// Same as [Flat2DArray::view], but writable.
+        pub fn view_mut(&mut self, origin: Pos, size: Size) -> ViewMut<'_, T> {
+            let rect = Rect::clipped(self, origin, size);
+            ViewMut { array: self, rect }
+        }
+
+        /// Copy `src` (from another array) so that its top left cell lands on `dst_pos`.
+        /// Whatever doesn't fit in this array is clipped. Cells are cloned one at a time,
+        /// see [Flat2DArray::simd_blit] for `Copy` cells.
+        ///
+        /// ```text
+        /// src view:      dst array:             after blit(src, (1, 2)):
+        /// ┌───┬───┐      ┌───┬───┬───┬───┐      ┌───┬───┬───┬───┐
+        /// │ a │ b │      │ . │ . │ . │ . │      │ . │ . │ . │ . │
+        /// ├───┼───┤      ├───┼───┼───┼───┤      ├───┼───┼───┼───┤
+        /// │ c │ d │      │ . │ . │ . │ . │      │ . │ . │ a │ b │
+        /// ├───┼───┤      └───┴───┴───┴───┘      └───┴───┴───┴───┘
+        /// │ e │ f │                              "c d" and "e f" are clipped
+        /// └───┴───┘
+        /// ```
+        pub fn blit(&mut self, src: &View<'_, T>, dst_pos: Pos) {
+            let mut dst = self.view_mut(dst_pos, src.size());
+            let cols = dst.rect.cols;
+            for (dst_row, src_row) in dst.iter_rows_mut().zip(src.iter_rows()) {
+                dst_row.clone_from_slice(&src_row[..cols]);
+            }
+        }
+
+        /// Set every cell in the rectangle at `origin` w/ `size` (clipped to the bounds of
+        /// the array) to `it`.
+        pub fn fill_rect(&mut self, origin: Pos, size: Size, it: T) {
+            self.view_mut(origin, size).fill(it);
+        }
+    }
+
+    impl<T: Copy + PartialEq> Flat2DArray<T> {
+        /// Same as [Flat2DArray::blit], but each row is a single `memcpy`.
+        pub fn simd_blit(&mut self, src: &View<'_, T>, dst_pos: Pos) {
+            let mut dst = self.view_mut(dst_pos, src.size());
+            let cols = dst.rect.cols;
+            for (dst_row, src_row) in dst.iter_rows_mut().zip(src.iter_rows()) {
+                dst_row.copy_from_slice(&src_row[..cols]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use r3bl_tui::{col, row};
+
+    /// A 3 x 4 array where each cell is `row * 10 + col`.
+    fn numbered() -> Flat2DArray<usize> {
+        let mut it = Flat2DArray::new(width(4) + height(3), 0usize);
+        for (index, cell) in it.data.iter_mut().enumerate() {
+            *cell = (index / 4) * 10 + index % 4;
+        }
+        it
+    }
+
+    #[test]
+    fn test_view_rows() {
+        let array = numbered();
+        let view = array.view(row(1) + col(1), width(2) + height(2));
+        assert_eq!(view.origin(), row(1) + col(1));
+        assert_eq!(view.size(), width(2) + height(2));
+        assert_eq!(
+            view.iter_rows().collect::<Vec<_>>(),
+            vec![&[11, 12][..], &[21, 22][..]]
+        );
+        assert_eq!(view.iter_rows().next_back(), Some(&[21, 22][..]));
+        assert_eq!(view.row(1), Some(&[21, 22][..]));
+        assert_eq!(view.row(2), None);
+        assert_eq!(view.get(row(0) + col(1)), Some(&12));
+        assert_eq!(view.get(row(0) + col(2)), None);
+    }
+
+    #[test]
+    fn test_view_clipping_at_edges() {
+        let array = numbered();
+
+        // Overhangs the right and bottom edges.
+        let view = array.view(row(2) + col(2), width(5) + height(5));
+        assert_eq!(view.size(), width(2) + height(1));
+        assert_eq!(view.iter_rows().collect::<Vec<_>>(), vec![&[22, 23][..]]);
+
+        // Starts exactly at the edge.
+        let view = array.view(row(0) + col(4), width(1) + height(1));
+        assert!(view.is_empty());
+        assert_eq!(view.iter_rows().count(), 0);
+
+        // Starts outside.
+        let view = array.view(row(9) + col(9), width(2) + height(2));
+        assert!(view.is_empty());
+        assert_eq!(view.row(0), None);
+
+        // Zero sized.
+        let view = array.view(row(1) + col(1), width(0) + height(2));
+        assert!(view.is_empty());
+        assert_eq!(view.size(), width(0) + height(0));
+        assert_eq!(view.iter_rows().count(), 0);
+    }
+
+    #[test]
+    fn test_view_mut_marks_rows_dirty() {
+        let mut array = numbered();
+        array.clear_dirty_rows();
+
+        let mut view = array.view_mut(row(1) + col(3), width(3) + height(3));
+        assert_eq!(view.size(), width(1) + height(2));
+        view.row_mut(0).unwrap()[0] = 99;
+        assert_eq!(view.as_view().row(0), Some(&[99][..]));
+        assert!(view.row_mut(2).is_none());
+
+        assert_eq!(array.dirty_row_indices().collect::<Vec<_>>(), vec![1]);
+        assert_eq!(array[1], [10, 11, 12, 99]);
+    }
+
+    #[test]
+    fn test_fill_rect() {
+        let mut array = numbered();
+        array.clear_dirty_rows();
+
+        array.fill_rect(row(1) + col(2), width(9) + height(9), 0);
+        assert_eq!(&array.data[..], &[0, 1, 2, 3, 10, 11, 0, 0, 20, 21, 0, 0]);
+        assert_eq!(array.dirty_row_indices().collect::<Vec<_>>(), vec![1, 2]);
+
+        // Entirely outside, so nothing changes.
+        let before = array.clone();
+        array.fill_rect(row(3) + col(0), width(4) + height(1), 7);
+        array.fill_rect(row(0) + col(4), width(1) + height(3), 7);
+        assert_eq!(array, before);
+    }
+
+    #[test]
+    fn test_blit() {
+        let src_array = numbered();
+        let mut dst = Flat2DArray::new(width(4) + height(3), 0usize);
+
+        // Fits entirely.
+        let src = src_array.view(row(0) + col(0), width(2) + height(2));
+        dst.blit(&src, row(1) + col(1));
+        assert_eq!(&dst.data[..], &[0, 0, 0, 0, 0, 0, 1, 0, 0, 10, 11, 0]);
+
+        // Clipped at the right and bottom edges of the destination.
+        let mut dst = Flat2DArray::new(width(4) + height(3), 0usize);
+        dst.clear_dirty_rows();
+        let src = src_array.view(row(0) + col(0), width(4) + height(3));
+        dst.blit(&src, row(2) + col(2));
+        assert_eq!(&dst.data[..], &[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(dst.dirty_row_indices().collect::<Vec<_>>(), vec![2]);
+
+        // Destination outside, so nothing is copied.
+        let before = dst.clone();
+        dst.blit(&src, row(3) + col(0));
+        dst.blit(&src, row(0) + col(4));
+        assert_eq!(dst, before);
+
+        // Source clipped at its own edges.
+        let mut dst = Flat2DArray::new(width(4) + height(3), 0usize);
+        let src = src_array.view(row(2) + col(3), width(4) + height(4));
+        dst.blit(&src, row(0) + col(0));
+        assert_eq!(&dst.data[..4], &[23, 0, 0, 0]);
+        assert_eq!(&dst.data[4..], &[0; 8]);
+    }
+
+    #[test]
+    fn test_simd_blit_matches_blit() {
+        let src_array = numbered();
+        let src = src_array.view(row(1) + col(1), width(3) + height(2));
+
+        let mut dst = Flat2DArray::new(width(4) + height(3), 0usize);
+        dst.blit(&src, row(0) + col(2));
+        let mut simd_dst = Flat2DArray::new(width(4) + height(3), 0usize);
+        simd_dst.simd_blit(&src, row(0) + col(2));
+        assert_eq!(dst, simd_dst);
+    }
+
+    #[test]
+    fn test_view_of_non_copy_cells() {
+        let mut array = Flat2DArray::new(width(3) + height(2), String::from(" "));
+        array.fill_rect(row(0) + col(1), width(2) + height(1), String::from("é"));
+        let view = array.view(row(0) + col(0), width(3) + height(1));
+        let copy_of_view = view;
+        assert_eq!(copy_of_view.row(0).unwrap().concat(), " éé");
+
+        let mut dst = Flat2DArray::new(width(2) + height(1), String::new());
+        dst.blit(&view, row(0) + col(0));
+        assert_eq!(dst[0], [" ", "é"]);
+    }
+}
@@ -82,12 +82,17 @@
 //! strategy. Using it, you can easily go from having an object in memory, quickly
 //! serialize it to bytes, and then deserialize it back just as fast!
 
-use crossterm::style::Stylize;
+use crossterm::style::{StyledContent, Stylize};
 use miette::{Context, IntoDiagnostic};
 use rkv::backend::{SafeMode, SafeModeDatabase, SafeModeEnvironment};
 use rkv::{Manager, Rkv, SingleStore, StoreOptions, Value};
-use std::{fs, path::Path, sync::RwLockReadGuard};
-use std::{thread::sleep, time::Duration};
+use std::time::Duration;
+use std::{
+    fs,
+    path::Path,
+    sync::{Arc, RwLock, RwLockReadGuard},
+};
+use tokio::time::sleep;
 
 use kv_example::{random_number, MyKeyType, MyValueType};
 
@@ -112,11 +117,14 @@ pub async fn main() -> miette::Result<()> {
     )
     .into_diagnostic()?;
 
+    // Open the environment & store once, and reuse the handle for all the operations.
+    let db = AsyncRkvStore::open(MY_DB_FOLDER, MY_PAYLOAD_STORE_NAME).await?;
+
     let mut max_count = 5;
     loop {
-        sleep(Duration::from_secs(1));
+        sleep(Duration::from_secs(1)).await;
         println!("---------------------------------");
-        perform_db_operations().await?;
+        perform_db_operations(&db).await?;
         max_count -= 1;
         if max_count == 0 {
             break;
@@ -124,22 +132,14 @@ pub async fn main() -> miette::Result<()> {
     }
 
     // Clean up the database.
-    run_lambda_on_db_store(
-        MY_DB_FOLDER,
-        MY_PAYLOAD_STORE_NAME,
-        |single_store, environment| {
-            clear_op(single_store, environment)?;
-            println!("{}", "clear_op".red());
-            Ok(())
-        },
-    )
-    .await?;
+    db.clear().await?;
+    println!("{}", "clear_op".red());
 
     Ok(())
 }
 
-#[tracing::instrument]
-async fn perform_db_operations() -> miette::Result<()> {
+#[tracing::instrument(skip(db))]
+async fn perform_db_operations(db: &AsyncRkvStore) -> miette::Result<()> {
     {
         let key = format!("key_{}", random_number::<u8>());
 
@@ -153,63 +153,25 @@ async fn perform_db_operations() -> miette::Result<()> {
         );
 
         // Write to db.
-        run_lambda_on_db_store(
-            MY_DB_FOLDER,
-            MY_PAYLOAD_STORE_NAME,
-            |single_store, environment| {
-                let value = MyValueType {
-                    id: random_number::<f32>(),
-                    description: "first item".into(),
-                    data: vec![random_number::<u8>(), random_number::<u8>()],
-                };
-                write_op(single_store, environment, key.clone(), value.clone())?;
-                println!(
-                    "{}: key: {}, value: {}",
-                    "write_op".red(),
-                    key.clone().dark_magenta(),
-                    format!("{:?}", value).magenta()
-                );
-                Ok(())
-            },
-        )
-        .await?;
-
-        // Read from db.
-        run_lambda_on_db_store(
-            MY_DB_FOLDER,
-            MY_PAYLOAD_STORE_NAME,
-            |single_store, environment| {
-                let maybe_value = read_op(single_store, environment, key.clone())?;
-                if let Some(value) = maybe_value {
-                    println!(
-                        "{}: key: {}, value: {}",
-                        "read_op".green(),
-                        key.clone().dark_blue(),
-                        format!("{:?}", value).blue()
-                    )
-                }
-                Ok(())
-            },
-        )
-        .await?;
+        let value = MyValueType {
+            id: random_number::<f32>(),
+            description: "first item".into(),
+            data: vec![random_number::<u8>(), random_number::<u8>()],
+        };
+        db.put(key.clone(), value.clone()).await?;
+        report_op("write_op".red(), &key, Some(&value));
+
+        // Read from db. Run a few reads concurrently, since readers never block each
+        // other.
+        let (first, second) = tokio::try_join!(db.get(key.clone()), db.get(key.clone()))?;
+        for value in [first, second].into_iter().flatten() {
+            report_op("read_op".green(), &key, Some(&value));
+        }
 
         // Iterate over db.
-        run_lambda_on_db_store(
-            MY_DB_FOLDER,
-            MY_PAYLOAD_STORE_NAME,
-            |single_store, environment| {
-                iter_op(single_store, environment, |key, value| {
-                    println!(
-                        "{}: key: {}, value: {}",
-                        "iter_op".cyan(),
-                        key.clone().dark_blue(),
-                        format!("{:?}", value).blue()
-                    );
-                    Ok(())
-                })
-            },
-        )
-        .await?;
+        for (key, value) in db.iter().await? {
+            report_op("iter_op".cyan(), &key, Some(&value));
+        }
 
         // Sleep for random duration.
         sleep(Duration::from_secs(match random_number::<u8>() as u64 {
@@ -218,26 +180,33 @@ async fn perform_db_operations() -> miette::Result<()> {
             101..=150 => 4,
             151..=200 => 3,
             _ => 2,
-        }));
+        }))
+        .await;
 
         // Random deletions of the key from the db.
         if random_number::<bool>() {
-            run_lambda_on_db_store(
-                MY_DB_FOLDER,
-                MY_PAYLOAD_STORE_NAME,
-                |single_store, environment| {
-                    delete_op(single_store, environment, key.clone())?;
-                    println!("{}: key: {}", "delete_op".red(), key.clone().dark_blue());
-                    Ok(())
-                },
-            )
-            .await?;
+            db.delete(key.clone()).await?;
+            report_op("delete_op".red(), &key, None);
         }
     }
 
     Ok(())
 }
 
+/// Print the result of a db operation to stdout. All the operations in
+/// [perform_db_operations] report through here, so their output stays consistent.
+fn report_op(op_name: StyledContent<&str>, key: &MyKeyType, maybe_value: Option<&MyValueType>) {
+    match maybe_value {
+        Some(value) => println!(
+            "{}: key: {}, value: {}",
+            op_name,
+            key.clone().dark_blue(),
+            format!("{:?}", value).blue()
+        ),
+        None => println!("{}: key: {}", op_name, key.clone().dark_blue()),
+    }
+}
+
 pub fn iter_op<F>(
     store: SingleStore<SafeModeDatabase>,
     environment: RwLockReadGuard<'_, Rkv<SafeModeEnvironment>>,
@@ -388,18 +357,18 @@ pub fn read_op(
     Ok(None)
 }
 
-/// [FnMut] info: <https://g.co/bard/share/2f53e5a2e611>
-pub async fn run_lambda_on_db_store<'a, F>(
-    db_folder_path_str: &'a str,
-    store_name: &'a str,
-    mut lambda: F,
-) -> miette::Result<()>
-where
-    F: FnMut(
-        /* store */ SingleStore<SafeModeDatabase>,
-        /* environment */ RwLockReadGuard<'_, Rkv<SafeModeEnvironment>>,
-    ) -> miette::Result<()>,
-{
+/// Create the db folder (if needed), and get (or create) the environment for it from the
+/// [Manager]. Then open (or create) the store named `store_name` in it.
+///
+/// All the diagnostics for opening a db live here, so that there is only one place where
+/// these errors are reported.
+pub fn open_environment_and_store(
+    db_folder_path_str: &str,
+    store_name: &str,
+) -> miette::Result<(
+    Arc<RwLock<Rkv<SafeModeEnvironment>>>,
+    SingleStore<SafeModeDatabase>,
+)> {
     // First determine the path to the environment, which is represented on disk as a
     // directory containing two files:
     //   * a data file containing the key/value stores.
@@ -439,32 +408,13 @@ where
 
     // The `Manager` enforces that each process opens the same environment at most once by
     // caching a handle to each environment that it opens. Use it to retrieve the handle
-    // to an opened environment—or create one if it hasn't already been opened.
-    match Manager::<SafeModeEnvironment>::singleton().write() {
-        Ok(mut manager) => {
-            match manager
-                .get_or_create(db_folder_path, Rkv::new::<SafeMode>)
-                .into_diagnostic()
-                .wrap_err(CustomError::CouldNotGetManager)?
-                .read()
-            {
-                Ok(environment) => {
-                    // Then you can use the environment handle to get a handle to a datastore.
-                    let store = environment
-                        .open_single(store_name, StoreOptions::create())
-                        .into_diagnostic()?;
-                    let _ = lambda(store, environment);
-                }
-                _ => {
-                    tracing::error!(
-                        "Could not get environment and store to run lambda with, \
-                        for db_folder_path: {}, store_name: {}",
-                        db_folder_path_str,
-                        store_name
-                    );
-                }
-            }
-        }
+    // to an opened environment—or create one if it hasn't already been opened. The lock
+    // on the `Manager` is released at the end of this statement.
+    let environment = match Manager::<SafeModeEnvironment>::singleton().write() {
+        Ok(mut manager) => manager
+            .get_or_create(db_folder_path, Rkv::new::<SafeMode>)
+            .into_diagnostic()
+            .wrap_err(CustomError::CouldNotGetManager)?,
         Err(error) => {
             tracing::error!(
                 "Could not get manager, \
@@ -475,9 +425,134 @@ where
             );
             return Err(CustomError::CouldNotGetManager).into_diagnostic();
         }
+    };
+
+    // Then you can use the environment handle to get a handle to a datastore.
+    let store = environment
+        .read()
+        .map_err(|_| CustomError::CouldNotAcquireEnvironmentLock)?
+        .open_single(store_name, StoreOptions::create())
+        .into_diagnostic()
+        .wrap_err(CustomError::CouldNotGetOrCreateEnvironmentOrOpenStore {
+            store_name: store_name.to_string(),
+        })?;
+
+    Ok((environment, store))
+}
+
+/// Async friendly handle to a [SingleStore] in an [rkv] environment.
+///
+/// The `*_op` functions above are all synchronous, and they do blocking disk IO. Calling
+/// them directly from an `async fn` would block the tokio runtime thread that the task is
+/// running on. This handle runs each of them on tokio's blocking thread pool using
+/// [tokio::task::spawn_blocking] instead.
+///
+/// It also acts as a connection cache. The [Manager] singleton is only locked once, in
+/// [AsyncRkvStore::open], and the environment handle that it returns is kept around for
+/// the lifetime of this struct. This handle is cheap to clone, and all the clones share
+/// the same environment.
+///
+/// The environment is wrapped in a [std::sync::RwLock] by the [Manager]. Every
+/// operation only takes a read lock on it, since both [rkv::Reader]s and [rkv::Writer]s
+/// are created from a `&Rkv`. This means that:
+/// - Many [AsyncRkvStore::get] and [AsyncRkvStore::iter] calls can run concurrently.
+/// - Writes are still serialized by [rkv] itself, since there can only be one
+///   [rkv::Writer] for a given environment at a time.
+#[derive(Clone)]
+pub struct AsyncRkvStore {
+    environment: Arc<RwLock<Rkv<SafeModeEnvironment>>>,
+    store: SingleStore<SafeModeDatabase>,
+    store_name: String,
+}
+
+impl std::fmt::Debug for AsyncRkvStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncRkvStore")
+            .field("store_name", &self.store_name)
+            .finish()
     }
+}
 
-    Ok(())
+impl AsyncRkvStore {
+    /// Create the db folder (if needed), and get (or create) the environment for it from
+    /// the [Manager]. Then open (or create) the store named `store_name` in it. This runs
+    /// [open_environment_and_store] on tokio's blocking thread pool.
+    pub async fn open(db_folder_path_str: &str, store_name: &str) -> miette::Result<Self> {
+        let db_folder_path_str = db_folder_path_str.to_string();
+        let store_name = store_name.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let (environment, store) =
+                open_environment_and_store(&db_folder_path_str, &store_name)?;
+            Ok(Self {
+                environment,
+                store,
+                store_name,
+            })
+        })
+        .await
+        .into_diagnostic()
+        .wrap_err(CustomError::CouldNotJoinBlockingTask)?
+    }
+
+    pub async fn get(&self, key: MyKeyType) -> miette::Result<Option<MyValueType>> {
+        self.run_blocking(move |store, environment| read_op(store, environment, key))
+            .await
+    }
+
+    pub async fn put(&self, key: MyKeyType, value: MyValueType) -> miette::Result<()> {
+        self.run_blocking(move |store, environment| write_op(store, environment, key, value))
+            .await
+    }
+
+    pub async fn delete(&self, key: MyKeyType) -> miette::Result<()> {
+        self.run_blocking(move |store, environment| delete_op(store, environment, key))
+            .await
+    }
+
+    pub async fn clear(&self) -> miette::Result<()> {
+        self.run_blocking(clear_op).await
+    }
+
+    /// The iterator returned by [rkv] borrows the read transaction, which can't leave the
+    /// blocking thread. So all the key value pairs are collected into a [Vec] instead.
+    pub async fn iter(&self) -> miette::Result<Vec<(MyKeyType, MyValueType)>> {
+        self.run_blocking(|store, environment| {
+            let mut acc = vec![];
+            iter_op(store, environment, |key, value| {
+                acc.push((key, value));
+                Ok(())
+            })?;
+            Ok(acc)
+        })
+        .await
+    }
+
+    /// Run the `lambda` on tokio's blocking thread pool, with a read lock on the cached
+    /// environment. [FnOnce] info: <https://doc.rust-lang.org/std/ops/trait.FnOnce.html>
+    async fn run_blocking<T, F>(&self, lambda: F) -> miette::Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(
+                /* store */ SingleStore<SafeModeDatabase>,
+                /* environment */ RwLockReadGuard<'_, Rkv<SafeModeEnvironment>>,
+            ) -> miette::Result<T>
+            + Send
+            + 'static,
+    {
+        let environment = self.environment.clone();
+        let store = self.store;
+
+        tokio::task::spawn_blocking(move || {
+            let environment = environment
+                .read()
+                .map_err(|_| CustomError::CouldNotAcquireEnvironmentLock)?;
+            lambda(store, environment)
+        })
+        .await
+        .into_diagnostic()
+        .wrap_err(CustomError::CouldNotJoinBlockingTask)?
+    }
 }
 
 #[derive(thiserror::Error, Debug, miette::Diagnostic)]
@@ -494,6 +569,14 @@ pub enum CustomError {
     #[error("💾 Could not get or create environment, or open store")]
     CouldNotGetOrCreateEnvironmentOrOpenStore { store_name: String },
 
+    #[diagnostic(code(rkv::DatabaseError::StoreCreationOrAccessError))]
+    #[error("🔒 Could not acquire lock on the environment, it is poisoned")]
+    CouldNotAcquireEnvironmentLock,
+
+    #[diagnostic(code(rkv::DatabaseError::CouldNotJoinBlockingTask))]
+    #[error("🧵 Could not join the blocking task that runs the db operation")]
+    CouldNotJoinBlockingTask,
+
     #[diagnostic(code(rkv::DatabaseError::CouldNotGetReaderFromEnvironment))]
     #[error("💾 Could not create a read transaction from the environment")]
     CouldNotGetReaderFromEnvironment,
@@ -534,3 +617,65 @@ pub enum CustomError {
     #[error("💾 Could not get iterator from store")]
     CouldNotGetIteratorFromStore,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn make_value(id: f32) -> MyValueType {
+        MyValueType {
+            id,
+            description: format!("item {}", id),
+            data: vec![id as u8],
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_async_rkv_store_crud() -> miette::Result<()> {
+        let db_folder = std::env::temp_dir().join(format!(
+            "rkv_db_folder_test_{}_{}",
+            std::process::id(),
+            random_number::<u32>()
+        ));
+        let db_folder_str = db_folder.to_string_lossy().to_string();
+
+        let db = AsyncRkvStore::open(&db_folder_str, MY_PAYLOAD_STORE_NAME).await?;
+
+        // Put.
+        db.put("a".to_string(), make_value(1.0)).await?;
+        db.put("b".to_string(), make_value(2.0)).await?;
+
+        // Concurrent gets, on a clone of the handle.
+        let other_db = db.clone();
+        let (a, b, missing) = tokio::try_join!(
+            db.get("a".to_string()),
+            other_db.get("b".to_string()),
+            db.get("c".to_string()),
+        )?;
+        assert_eq!(a, Some(make_value(1.0)));
+        assert_eq!(b, Some(make_value(2.0)));
+        assert_eq!(missing, None);
+
+        // Iter.
+        assert_eq!(
+            db.iter().await?,
+            vec![
+                ("a".to_string(), make_value(1.0)),
+                ("b".to_string(), make_value(2.0)),
+            ]
+        );
+
+        // Delete.
+        db.delete("a".to_string()).await?;
+        assert_eq!(db.get("a".to_string()).await?, None);
+
+        // Clear.
+        db.clear().await?;
+        assert_eq!(db.iter().await?, vec![]);
+
+        let _ = std::fs::remove_dir_all(&db_folder);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+/*
+ *   Copyright (c) 2025 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Compare parsing an editor's line buffer (`Vec<String>`) by joining it into a [String]
+//! first, vs parsing it directly w/ a [LinesSlice].
+
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use nom_vec_input::ex_3::{LinesSlice, NEW_LINE, parse_markdown_blocks, parse_sentences};
+
+fn lines() -> Vec<String> {
+    let paragraph = "Some text. And a sentence that is\nsplit over two lines. Partial";
+    let mut lines = vec![];
+    for index in 0..200 {
+        lines.push(format!("## Heading {index}"));
+        lines.extend(paragraph.split(NEW_LINE).map(String::from));
+        lines.push(String::new());
+    }
+    lines
+}
+
+fn bench_parse_sentences(c: &mut Criterion) {
+    let lines = lines();
+    let mut group = c.benchmark_group("parse_sentences");
+    group.bench_function("joined_string", |b| {
+        b.iter(|| {
+            let text = black_box(&lines).join(NEW_LINE);
+            parse_sentences(text.as_str()).len()
+        })
+    });
+    group.bench_function("lines_slice", |b| {
+        b.iter(|| parse_sentences(LinesSlice::new(black_box(&lines))).len())
+    });
+    group.finish();
+}
+
+fn bench_parse_markdown_blocks(c: &mut Criterion) {
+    let lines = lines();
+    let mut group = c.benchmark_group("parse_markdown_blocks");
+    group.bench_function("joined_string", |b| {
+        b.iter(|| {
+            let text = black_box(&lines).join(NEW_LINE);
+            parse_markdown_blocks(text.as_str()).unwrap().1.len()
+        })
+    });
+    group.bench_function("lines_slice", |b| {
+        b.iter(|| {
+            parse_markdown_blocks(LinesSlice::new(black_box(&lines)))
+                .unwrap()
+                .1
+                .len()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse_sentences, bench_parse_markdown_blocks);
+criterion_main!(benches);
@@ -0,0 +1,830 @@
+/*
+ *   Copyright (c) 2025 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! This is different from [mod@crate::ex_1] and [mod@crate::ex_2], which iterate over the
+//! lines manually (or join them into a [String] first). Here [LinesSlice] is a zero-copy
+//! view over an editor's line buffer (`&[String]`), which behaves like the [&str] that you
+//! would get from `lines.join("\n")`, w/out allocating it.
+//!
+//! nom 8 merged the `InputTake`, `InputIter`, `InputLength`, and `Slice` traits from older
+//! versions into [nom::Input], so [LinesSlice] implements:
+//! 1. [nom::Input] - length, iteration over [char]s, and "taking" byte ranges.
+//! 2. [nom::Compare] - used by [nom::bytes::complete::tag] and friends.
+//! 3. [nom::FindSubstring] - used by [nom::bytes::complete::take_until].
+//! 4. [nom::Offset] - used by [nom::combinator::recognize].
+//!
+//! Since the same parser can run on a [&str] or a [LinesSlice], the parsers in this module
+//! are generic over the input type, eg: [parse_sentences] and [parse_markdown_blocks]. The
+//! benchmarks in `benches/lines_slice.rs` compare the two approaches.
+//!
+//! ```text
+//! lines: [ "# Title", "foo. bar" ]
+//!          │            │
+//!          ▼            ▼
+//! view:   "# Title" \n "foo. bar"   <- the "\n" is virtual, it is not in the lines
+//!                 ▲        ▲
+//!                 └────────┘ <- LinesSlice { line: 0, col: 5, len: 8, .. } is "le\nfoo. "
+//! ```
+
+use std::{fmt::Display, str::Chars};
+
+use nom::{
+    Compare, CompareResult, Err, FindSubstring, IResult, Input, Needed, Offset, Parser as _,
+    branch::alt,
+    bytes::complete::{tag, take_till, take_till1, take_until, take_while_m_n, take_while1},
+    character::complete::{char, digit1, line_ending, space0},
+    combinator::{map, opt, recognize, verify},
+    error::{Error, ErrorKind},
+    multi::{many0, many1, separated_list0},
+    sequence::{delimited, preceded, terminated},
+};
+
+pub const NEW_LINE: &str = "\n";
+
+/// A view over a range of `lines`, that is treated as if the lines were joined w/
+/// [NEW_LINE]. All the indices are byte indices into that (virtual) joined string, just
+/// like they are for a [&str].
+#[derive(Debug, Clone, Copy)]
+pub struct LinesSlice<'a> {
+    lines: &'a [String],
+    /// Index of the line that this slice starts in.
+    line: usize,
+    /// Byte index in `lines[line]` that this slice starts at. It can be the length of the
+    /// line, ie: the slice starts w/ the [NEW_LINE] at the end of the line.
+    col: usize,
+    /// Length of this slice in bytes, including the [NEW_LINE]s between lines.
+    len: usize,
+    /// Byte index of the start of this slice from the start of `lines`, for [Offset].
+    offset: usize,
+}
+
+impl<'a> LinesSlice<'a> {
+    pub fn new(lines: &'a [String]) -> Self {
+        let len = lines.iter().map(String::len).sum::<usize>() + lines.len().saturating_sub(1);
+        Self {
+            lines,
+            line: 0,
+            col: 0,
+            len,
+            offset: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The parts of the lines in this slice. Joining them w/ [NEW_LINE] produces the
+    /// slice's text. An empty slice has one empty segment.
+    pub fn segments(&self) -> Segments<'a> {
+        Segments {
+            lines: self.lines,
+            line: self.line,
+            col: self.col,
+            remaining: Some(self.len),
+        }
+    }
+
+    /// If this slice doesn't span multiple lines, then it can be borrowed as a [&str] w/out
+    /// allocating.
+    pub fn as_str(&self) -> Option<&'a str> {
+        let mut segments = self.segments();
+        match (segments.next(), segments.next()) {
+            (Some(it), None) => Some(it),
+            _ => None,
+        }
+    }
+
+    /// Move the start of the slice forward by `count` bytes, which must not be more than
+    /// the length of the slice.
+    fn advance(&self, count: usize) -> Self {
+        debug_assert!(count <= self.len);
+        let (mut line, mut col, mut left) = (self.line, self.col, count);
+        while left > 0 {
+            let rest = self.lines[line].len() - col;
+            if left <= rest {
+                col += left;
+                break;
+            }
+            // Skip the rest of the line and its new line.
+            left -= rest + 1;
+            line += 1;
+            col = 0;
+        }
+        Self {
+            lines: self.lines,
+            line,
+            col,
+            len: self.len - count,
+            offset: self.offset + count,
+        }
+    }
+}
+
+impl Display for LinesSlice<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, segment) in self.segments().enumerate() {
+            if index > 0 {
+                f.write_str(NEW_LINE)?;
+            }
+            f.write_str(segment)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares the text, not where the slices are in the lines.
+impl PartialEq for LinesSlice<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter_elements().eq(other.iter_elements())
+    }
+}
+
+impl PartialEq<&str> for LinesSlice<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self.len == other.len() && self.iter_elements().eq(other.chars())
+    }
+}
+
+/// Returned by [LinesSlice::segments].
+#[derive(Debug, Clone)]
+pub struct Segments<'a> {
+    lines: &'a [String],
+    line: usize,
+    col: usize,
+    /// [None] once the last segment has been returned.
+    remaining: Option<usize>,
+}
+
+impl<'a> Iterator for Segments<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let remaining = self.remaining?;
+        let text = self.lines.get(self.line).map_or("", |it| &it[self.col..]);
+        if remaining <= text.len() {
+            self.remaining = None;
+            return Some(&text[..remaining]);
+        }
+        self.remaining = Some(remaining - text.len() - NEW_LINE.len());
+        self.line += 1;
+        self.col = 0;
+        Some(text)
+    }
+}
+
+/// Returned by [Input::iter_elements]. The [char]s of each segment followed by a `'\n'`
+/// (except for the last one).
+#[derive(Debug, Clone)]
+pub struct LinesSliceChars<'a> {
+    segments: Segments<'a>,
+    chars: Chars<'a>,
+}
+
+impl Iterator for LinesSliceChars<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(it) = self.chars.next() {
+            return Some(it);
+        }
+        self.chars = self.segments.next()?.chars();
+        Some('\n')
+    }
+}
+
+/// Returned by [Input::iter_indices]. Like [str::char_indices], the indices are in bytes.
+#[derive(Debug, Clone)]
+pub struct LinesSliceCharIndices<'a> {
+    chars: LinesSliceChars<'a>,
+    index: usize,
+}
+
+impl Iterator for LinesSliceCharIndices<'_> {
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let it = self.chars.next()?;
+        let index = self.index;
+        self.index += it.len_utf8();
+        Some((index, it))
+    }
+}
+
+mod impl_nom_traits_for_lines_slice {
+    use super::*;
+
+    impl<'a> Input for LinesSlice<'a> {
+        type Item = char;
+        type Iter = LinesSliceChars<'a>;
+        type IterIndices = LinesSliceCharIndices<'a>;
+
+        fn input_len(&self) -> usize {
+            self.len
+        }
+
+        fn take(&self, index: usize) -> Self {
+            Self {
+                len: index,
+                ..*self
+            }
+        }
+
+        fn take_from(&self, index: usize) -> Self {
+            self.advance(index)
+        }
+
+        /// Returns `(suffix, prefix)`, like the impl for [&str].
+        fn take_split(&self, index: usize) -> (Self, Self) {
+            (self.take_from(index), self.take(index))
+        }
+
+        /// Searches each segment w/ [str::find], which is faster than [Input::iter_indices].
+        fn position<P>(&self, predicate: P) -> Option<usize>
+        where
+            P: Fn(Self::Item) -> bool,
+        {
+            let mut offset = 0;
+            for (index, segment) in self.segments().enumerate() {
+                if index > 0 {
+                    if predicate('\n') {
+                        return Some(offset);
+                    }
+                    offset += NEW_LINE.len();
+                }
+                if let Some(it) = segment.find(&predicate) {
+                    return Some(offset + it);
+                }
+                offset += segment.len();
+            }
+            None
+        }
+
+        fn iter_elements(&self) -> Self::Iter {
+            let mut segments = self.segments();
+            let chars = segments.next().unwrap_or_default().chars();
+            LinesSliceChars { segments, chars }
+        }
+
+        fn iter_indices(&self) -> Self::IterIndices {
+            LinesSliceCharIndices {
+                chars: self.iter_elements(),
+                index: 0,
+            }
+        }
+
+        fn slice_index(&self, count: usize) -> Result<usize, Needed> {
+            let mut indices = self.iter_indices().map(|(index, _)| index);
+            match indices.nth(count) {
+                Some(index) => Ok(index),
+                None if self.iter_elements().count() == count => Ok(self.len),
+                None => Err(Needed::Unknown),
+            }
+        }
+    }
+
+    impl Compare<&str> for LinesSlice<'_> {
+        /// Compares the bytes of each segment (and the [NEW_LINE]s between them) w/ `t`.
+        fn compare(&self, t: &str) -> CompareResult {
+            let mut t = t.as_bytes();
+            for (index, segment) in self.segments().enumerate() {
+                if index > 0 {
+                    match t.strip_prefix(NEW_LINE.as_bytes()) {
+                        Some(rest) => t = rest,
+                        None if t.is_empty() => return CompareResult::Ok,
+                        None => return CompareResult::Error,
+                    }
+                }
+                let length = segment.len().min(t.len());
+                if segment.as_bytes()[..length] != t[..length] {
+                    return CompareResult::Error;
+                }
+                t = &t[length..];
+                if t.is_empty() {
+                    return CompareResult::Ok;
+                }
+            }
+            CompareResult::Incomplete
+        }
+
+        fn compare_no_case(&self, t: &str) -> CompareResult {
+            let mut chars = self.iter_elements();
+            for expected in t.chars() {
+                match chars.next() {
+                    Some(it) if it.to_lowercase().eq(expected.to_lowercase()) => {}
+                    Some(_) => return CompareResult::Error,
+                    None => return CompareResult::Incomplete,
+                }
+            }
+            CompareResult::Ok
+        }
+    }
+
+    impl FindSubstring<&str> for LinesSlice<'_> {
+        /// A `substr` w/out a [NEW_LINE] can only be in one segment. Otherwise the first line
+        /// of `substr` has to be at the end of a segment, and the rest of its lines have to
+        /// be at the start of the segments that follow.
+        fn find_substring(&self, substr: &str) -> Option<usize> {
+            let mut offset = 0;
+            let mut segments = self.segments();
+            while let Some(segment) = segments.next() {
+                let mut parts = substr.split(NEW_LINE);
+                let first = parts.next().unwrap_or_default();
+                if parts.clone().next().is_none() {
+                    if let Some(index) = segment.find(first) {
+                        return Some(offset + index);
+                    }
+                } else if segment.ends_with(first) && is_prefix_of(parts, segments.clone()) {
+                    return Some(offset + segment.len() - first.len());
+                }
+                offset += segment.len() + NEW_LINE.len();
+            }
+            None
+        }
+    }
+
+    /// The last part only has to be at the start of its segment, the others have to match
+    /// their whole segment.
+    fn is_prefix_of<'a>(parts: impl Iterator<Item = &'a str>, mut segments: Segments<'_>) -> bool {
+        let mut parts = parts.peekable();
+        while let Some(part) = parts.next() {
+            let Some(segment) = segments.next() else {
+                return false;
+            };
+            let is_match = match parts.peek() {
+                Some(_) => segment == part,
+                None => segment.starts_with(part),
+            };
+            if !is_match {
+                return false;
+            }
+        }
+        true
+    }
+
+    impl Offset for LinesSlice<'_> {
+        fn offset(&self, second: &Self) -> usize {
+            second.offset - self.offset
+        }
+    }
+}
+
+/// All the bounds needed by the parsers in this module. Both [&str] and [LinesSlice]
+/// satisfy them.
+pub trait TextInput: Input<Item = char> + Compare<&'static str> + Offset + Clone {}
+
+impl<T> TextInput for T where T: Input<Item = char> + Compare<&'static str> + Offset + Clone {}
+
+/// Same as `Sentence` in [mod@crate::ex_normal], but generic over the input, so a
+/// [Sentence::Full] can span lines w/out being copied into a [String].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sentence<I> {
+    Full(I),
+    Partial(I),
+    Eol,
+}
+
+impl<I: Display> Sentence<I> {
+    /// Convert to an owned [Sentence], to compare the output for different inputs.
+    pub fn to_owned_sentence(&self) -> Sentence<String> {
+        match self {
+            Sentence::Full(it) => Sentence::Full(it.to_string()),
+            Sentence::Partial(it) => Sentence::Partial(it.to_string()),
+            Sentence::Eol => Sentence::Eol,
+        }
+    }
+}
+
+/// Generic version of `parse_sentences()` in [mod@crate::ex_normal].
+pub fn parse_sentences<I: TextInput>(input: I) -> Vec<Sentence<I>> {
+    match many0(parse_single_sentence).parse(input.clone()) {
+        Ok((remainder, mut sentences)) => {
+            if remainder.input_len() > 0 {
+                sentences.push(Sentence::Partial(remainder));
+            }
+            sentences
+        }
+        Err(_) if input.input_len() > 0 => vec![Sentence::Partial(input)],
+        Err(_) => vec![],
+    }
+}
+
+/// Generic version of `parse_single_sentence()` in [mod@crate::ex_normal]. Do not reorder,
+/// the ordering of these branches matters for parsing.
+fn parse_single_sentence<I: TextInput>(input: I) -> IResult<I, Sentence<I>> {
+    alt((
+        map(line_ending, |_| Sentence::Eol),
+        map(
+            recognize((take_till(|c| c == '.'), char('.'))),
+            Sentence::Full,
+        ),
+        map(
+            take_while1(|c: char| c.is_whitespace() && c != '\n'),
+            Sentence::Partial,
+        ),
+        map(
+            verify(take_till(|c| c == '.' || c == '\n'), |it: &I| {
+                it.input_len() > 0
+            }),
+            Sentence::Partial,
+        ),
+    ))
+    .parse(input)
+}
+
+/// The blocks of the `nom` crate's `md_parser` (`Block` in `md_parser/types.rs`), ported so
+/// that they are generic over the input, and can run directly on an editor's line buffer w/
+/// a [LinesSlice].
+///
+/// Unlike `md_parser`, the text of each block is not parsed any further into inline
+/// fragments (bold, links, etc). Those never span lines, so each one can be parsed from
+/// [LinesSlice::as_str] w/ the existing `&str` parsers. A [MarkdownBlock::CodeBlock] does
+/// span lines, and its text is borrowed from the lines w/out being joined.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarkdownBlock<I> {
+    /// `@title: Something` or `@title: "Something"`.
+    Title(I),
+    /// `@tags: [tag1, tag2]` or `@tags: ["tag1", "tag2"]`.
+    Tags(Vec<I>),
+    /// `# Heading`, w/ 1 to 6 `#`s.
+    Heading { level: usize, text: I },
+    /// Consecutive `- item` lines.
+    UnorderedList(Vec<I>),
+    /// Consecutive `1. item` lines.
+    OrderedList(Vec<I>),
+    /// A fenced code block. The `text` includes the new line at the end of its last line.
+    CodeBlock { language: Option<I>, text: I },
+    /// Any other line, which can be empty.
+    Text(I),
+}
+
+impl<I: Display> MarkdownBlock<I> {
+    /// Convert to an owned [MarkdownBlock], to compare the output for different inputs.
+    pub fn to_owned_block(&self) -> MarkdownBlock<String> {
+        let to_strings = |items: &[I]| items.iter().map(ToString::to_string).collect();
+        match self {
+            MarkdownBlock::Title(it) => MarkdownBlock::Title(it.to_string()),
+            MarkdownBlock::Tags(it) => MarkdownBlock::Tags(to_strings(it)),
+            MarkdownBlock::Heading { level, text } => MarkdownBlock::Heading {
+                level: *level,
+                text: text.to_string(),
+            },
+            MarkdownBlock::UnorderedList(it) => MarkdownBlock::UnorderedList(to_strings(it)),
+            MarkdownBlock::OrderedList(it) => MarkdownBlock::OrderedList(to_strings(it)),
+            MarkdownBlock::CodeBlock { language, text } => MarkdownBlock::CodeBlock {
+                language: language.as_ref().map(ToString::to_string),
+                text: text.to_string(),
+            },
+            MarkdownBlock::Text(it) => MarkdownBlock::Text(it.to_string()),
+        }
+    }
+}
+
+/// The same ordering as `root_parse_markdown()` in `md_parser`. Do not reorder, the ordering
+/// of these branches matters for parsing.
+pub fn parse_markdown_blocks<I: MarkdownInput>(input: I) -> IResult<I, Vec<MarkdownBlock<I>>> {
+    many0(alt((
+        map(parse_title, MarkdownBlock::Title),
+        map(parse_tags, MarkdownBlock::Tags),
+        parse_heading,
+        map(parse_unordered_list, MarkdownBlock::UnorderedList),
+        map(parse_ordered_list, MarkdownBlock::OrderedList),
+        parse_code_block,
+        map(parse_until_eol, MarkdownBlock::Text),
+    )))
+    .parse(input)
+}
+
+/// [TextInput] plus [nom::bytes::complete::take_until], to find the end of a code block.
+pub trait MarkdownInput: TextInput + FindSubstring<&'static str> {}
+
+impl<T> MarkdownInput for T where T: TextInput + FindSubstring<&'static str> {}
+
+pub mod markdown_constants {
+    pub const TITLE: &str = "@title:";
+    pub const TAGS: &str = "@tags:";
+    pub const HEADING_CHAR: char = '#';
+    pub const UNORDERED_LIST: &str = "- ";
+    pub const ORDERED_LIST: &str = ". ";
+    pub const CODE_BLOCK: &str = "```";
+    pub const QUOTE: char = '"';
+}
+use markdown_constants::*;
+
+/// eg: `@title: Something\n`. The quotes are optional.
+fn parse_title<I: MarkdownInput>(input: I) -> IResult<I, I> {
+    preceded(
+        (tag(TITLE), space0),
+        alt((terminated(parse_quoted, parse_eol), parse_until_eol)),
+    )
+    .parse(input)
+}
+
+/// eg: `@tags: [tag1, "tag2", tag3]\n`.
+fn parse_tags<I: MarkdownInput>(input: I) -> IResult<I, Vec<I>> {
+    let tag_item = alt((
+        parse_quoted,
+        take_till1(|c| c == ',' || c == ']' || c == '\n'),
+    ));
+    delimited(
+        (tag(TAGS), space0, char('[')),
+        separated_list0((char(','), space0), tag_item),
+        (char(']'), parse_eol),
+    )
+    .parse(input)
+}
+
+/// eg: `"Something"`. The output doesn't include the quotes.
+fn parse_quoted<I: MarkdownInput>(input: I) -> IResult<I, I> {
+    delimited(
+        char(QUOTE),
+        take_till1(|c| c == QUOTE || c == '\n'),
+        char(QUOTE),
+    )
+    .parse(input)
+}
+
+/// eg: `## Title\n`, w/ 1 to 6 `#`s.
+fn parse_heading<I: MarkdownInput>(input: I) -> IResult<I, MarkdownBlock<I>> {
+    let (input, level) = take_while_m_n(1, 6, |c: char| c == HEADING_CHAR).parse(input)?;
+    let (input, _) = (char(' '), space0).parse(input)?;
+    let (input, text) = parse_until_eol(input)?;
+    let level = level.input_len();
+    Ok((input, MarkdownBlock::Heading { level, text }))
+}
+
+/// eg: `- foo\n- bar\n`.
+fn parse_unordered_list<I: MarkdownInput>(input: I) -> IResult<I, Vec<I>> {
+    many1(preceded(tag(UNORDERED_LIST), parse_until_eol)).parse(input)
+}
+
+/// eg: `1. foo\n2. bar\n`.
+fn parse_ordered_list<I: MarkdownInput>(input: I) -> IResult<I, Vec<I>> {
+    many1(preceded((digit1, tag(ORDERED_LIST)), parse_until_eol)).parse(input)
+}
+
+/// eg: "```rs\nfn main() {}\n```\n". The language is optional.
+fn parse_code_block<I: MarkdownInput>(input: I) -> IResult<I, MarkdownBlock<I>> {
+    let (input, language) = delimited(
+        tag(CODE_BLOCK),
+        opt(take_till1(|c| c == '\n')),
+        tag(NEW_LINE),
+    )
+    .parse(input)?;
+    let (input, text) = terminated(take_until(CODE_BLOCK), tag(CODE_BLOCK)).parse(input)?;
+    let (input, _) = parse_eol(input)?;
+    Ok((input, MarkdownBlock::CodeBlock { language, text }))
+}
+
+/// Consumes the [NEW_LINE] (if there is one), but doesn't include it in the output. Fails
+/// at the end of the input so that [many0] stops.
+fn parse_until_eol<I: TextInput>(input: I) -> IResult<I, I> {
+    if input.input_len() == 0 {
+        return Err(Err::Error(Error::new(input, ErrorKind::Eof)));
+    }
+    terminated(take_till(|c| c == '\n'), opt(tag(NEW_LINE))).parse(input)
+}
+
+/// The [NEW_LINE] at the end of a line, which the last line doesn't have.
+fn parse_eol<I: TextInput>(input: I) -> IResult<I, ()> {
+    if input.input_len() == 0 {
+        return Ok((input, ()));
+    }
+    map(tag(NEW_LINE), |_| ()).parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::bytes::complete::take_until;
+
+    fn to_lines(text: &str) -> Vec<String> {
+        text.split(NEW_LINE).map(String::from).collect()
+    }
+
+    #[test]
+    fn test_lines_slice_is_the_joined_text() {
+        let lines = to_lines("ab\n\nc🦀d\n");
+        let slice = LinesSlice::new(&lines);
+        assert_eq!(slice.input_len(), "ab\n\nc🦀d\n".len());
+        assert_eq!(slice.to_string(), "ab\n\nc🦀d\n");
+        assert_eq!(
+            slice.iter_elements().collect::<String>(),
+            "ab\n\nc🦀d\n".to_string()
+        );
+        assert!(slice.iter_indices().eq("ab\n\nc🦀d\n".char_indices()));
+
+        // Every byte range (on a char boundary) is the same as the &str's.
+        let text = lines.join(NEW_LINE);
+        for start in 0..=text.len() {
+            for end in start..=text.len() {
+                if !text.is_char_boundary(start) || !text.is_char_boundary(end) {
+                    continue;
+                }
+                let it = slice.take_from(start).take(end - start);
+                assert_eq!(it.to_string(), &text[start..end], "{start}..{end}");
+                assert_eq!(slice.offset(&it), start);
+            }
+        }
+
+        assert_eq!(LinesSlice::new(&[]).to_string(), "");
+        assert!(LinesSlice::new(&[]).is_empty());
+        assert_eq!(slice.take(2).as_str(), Some("ab"));
+        assert_eq!(slice.take(3).as_str(), None);
+    }
+
+    #[test]
+    fn test_lines_slice_nom_traits() {
+        let lines = to_lines("foo\nbar baz\nqux");
+        let slice = LinesSlice::new(&lines);
+
+        assert_eq!(slice.compare("foo\nb"), CompareResult::Ok);
+        assert_eq!(slice.compare("FOO"), CompareResult::Error);
+        assert_eq!(slice.compare_no_case("FOO\nB"), CompareResult::Ok);
+        assert_eq!(slice.take(2).compare("foo"), CompareResult::Incomplete);
+
+        assert_eq!(slice.find_substring("baz"), Some(8));
+        assert_eq!(slice.find_substring("o\nbar"), Some(2));
+        assert_eq!(slice.find_substring("baz\nqux"), Some(8));
+        assert_eq!(slice.find_substring("\n"), Some(3));
+        assert_eq!(slice.find_substring("o\nqux"), None);
+        assert_eq!(slice.take(10).find_substring("baz"), None);
+
+        assert_eq!(slice.slice_index(5), Ok(5));
+        assert_eq!(slice.slice_index(slice.input_len()), Ok(slice.input_len()));
+        assert_eq!(slice.slice_index(100), Err(Needed::Unknown));
+
+        let (rest, output) = take_until::<_, _, nom::error::Error<_>>("baz")
+            .parse(slice)
+            .unwrap();
+        assert_eq!(output, "foo\nbar ");
+        assert_eq!(rest, "baz\nqux");
+
+        let (rest, output) = tag::<_, _, nom::error::Error<_>>("foo\nbar")
+            .parse(slice)
+            .unwrap();
+        assert_eq!(output, "foo\nbar");
+        assert_eq!(rest, " baz\nqux");
+    }
+
+    /// Run a parser on the joined [String] and on a [LinesSlice], and check that the output
+    /// is the same.
+    #[test]
+    fn test_parse_sentences_same_as_str() {
+        for text in [
+            "foo. \nbar ",
+            "\nfoo.\n\nbar\n",
+            "hello world.",
+            "first line.\nsecond part",
+            "a sentence\nover two lines. And 🦀.",
+            "",
+            "\n\n",
+        ] {
+            let lines = to_lines(text);
+            let expected = parse_sentences(text)
+                .iter()
+                .map(Sentence::to_owned_sentence)
+                .collect::<Vec<_>>();
+            let actual = parse_sentences(LinesSlice::new(&lines))
+                .iter()
+                .map(Sentence::to_owned_sentence)
+                .collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{text:?}");
+        }
+
+        let lines = to_lines("foo. \nbar ");
+        assert_eq!(
+            parse_sentences(LinesSlice::new(&lines))
+                .iter()
+                .map(Sentence::to_owned_sentence)
+                .collect::<Vec<_>>(),
+            vec![
+                Sentence::Full("foo.".to_string()),
+                Sentence::Partial(" ".to_string()),
+                Sentence::Eol,
+                Sentence::Partial("bar ".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_markdown_blocks_same_as_str() {
+        // Same as `MARKDOWN_INPUT` in the `nom` crate's `md_parser/parser.rs`, plus lists.
+        let text = [
+            "@title: Something",
+            "@tags: [tag1, \"tag2\", tag3]",
+            "# Foobar",
+            "",
+            "Foobar is a Python library for dealing with word pluralization.",
+            "",
+            "```bash",
+            "pip install foobar",
+            "```",
+            "## Installation",
+            "- one",
+            "- two",
+            "1. first",
+            "2. second",
+            "```",
+            "no language",
+            "",
+            "```",
+            "####### not a heading",
+        ]
+        .join(NEW_LINE);
+        let lines = to_lines(&text);
+
+        let (rest, blocks) = parse_markdown_blocks(LinesSlice::new(&lines)).unwrap();
+        assert!(rest.is_empty());
+        let actual = blocks
+            .iter()
+            .map(MarkdownBlock::to_owned_block)
+            .collect::<Vec<_>>();
+        let strings = |it: &[&str]| it.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            actual,
+            vec![
+                MarkdownBlock::Title("Something".to_string()),
+                MarkdownBlock::Tags(strings(&["tag1", "tag2", "tag3"])),
+                MarkdownBlock::Heading {
+                    level: 1,
+                    text: "Foobar".to_string()
+                },
+                MarkdownBlock::Text("".to_string()),
+                MarkdownBlock::Text(
+                    "Foobar is a Python library for dealing with word pluralization.".to_string()
+                ),
+                MarkdownBlock::Text("".to_string()),
+                MarkdownBlock::CodeBlock {
+                    language: Some("bash".to_string()),
+                    text: "pip install foobar\n".to_string()
+                },
+                MarkdownBlock::Heading {
+                    level: 2,
+                    text: "Installation".to_string()
+                },
+                MarkdownBlock::UnorderedList(strings(&["one", "two"])),
+                MarkdownBlock::OrderedList(strings(&["first", "second"])),
+                MarkdownBlock::CodeBlock {
+                    language: None,
+                    text: "no language\n\n".to_string()
+                },
+                MarkdownBlock::Text("####### not a heading".to_string()),
+            ]
+        );
+
+        let (_, expected) = parse_markdown_blocks(text.as_str()).unwrap();
+        let expected = expected
+            .iter()
+            .map(MarkdownBlock::to_owned_block)
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+
+        // The blocks that are in a single line borrow from it, and a code block spans lines
+        // w/out being joined.
+        let MarkdownBlock::Heading { text: title, .. } = &blocks[2] else {
+            panic!("expected heading: {:?}", blocks[2]);
+        };
+        assert_eq!(title.as_str(), Some("Foobar"));
+        let MarkdownBlock::CodeBlock { text: code, .. } = &blocks[10] else {
+            panic!("expected code block: {:?}", blocks[10]);
+        };
+        assert_eq!(code.as_str(), None);
+        assert_eq!(
+            code.segments().collect::<Vec<_>>(),
+            vec!["no language", "", ""]
+        );
+    }
+
+    #[test]
+    fn test_parse_markdown_blocks_unterminated_code_block() {
+        // Like `md_parser`, a code block w/out a closing fence is just text.
+        let lines = to_lines("```rs\nfn main() {}");
+        let (rest, blocks) = parse_markdown_blocks(LinesSlice::new(&lines)).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(
+            blocks
+                .iter()
+                .map(MarkdownBlock::to_owned_block)
+                .collect::<Vec<_>>(),
+            vec![
+                MarkdownBlock::Text("```rs".to_string()),
+                MarkdownBlock::Text("fn main() {}".to_string()),
+            ]
+        );
+    }
+}
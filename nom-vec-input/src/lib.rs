@@ -32,3 +32,5 @@ mod ex_2;
 
 #[allow(unused)]
 mod ex_normal;
+
+pub mod ex_3;
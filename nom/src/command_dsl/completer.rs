@@ -0,0 +1,121 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use crate::command_dsl::*;
+use nom::combinator::all_consuming;
+
+/// The candidates for the last word of a partially typed line, eg: for a REPL's tab
+/// completion.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Completions<'a> {
+    /// Byte index of the start of the last word in the input, which is what the candidates
+    /// replace.
+    pub start: usize,
+    pub candidates: Vec<&'a str>,
+}
+
+impl Grammar {
+    /// The words before the last one have to match the start of a command for it to offer
+    /// any candidates. Only keywords and choices can be completed, there are no candidates
+    /// for the other slots.
+    pub fn complete<'a>(&'a self, input: &str) -> Completions<'a> {
+        let start = input.trim_end_matches(|it: char| !it.is_whitespace()).len();
+        let (typed, partial) = input.split_at(start);
+        let partial = partial.to_lowercase();
+
+        let mut candidates = vec![];
+        for spec in &self.commands {
+            let Some(token) = next_token(spec, typed) else {
+                continue;
+            };
+            let words: Vec<&str> = match token {
+                Token::Keyword(keyword) => vec![keyword],
+                Token::Slot(Slot {
+                    kind: SlotKind::Choice(choices),
+                    ..
+                }) => choices.iter().map(String::as_str).collect(),
+                Token::Slot(_) => vec![],
+            };
+            for word in words {
+                if word.to_lowercase().starts_with(&partial) && !candidates.contains(&word) {
+                    candidates.push(word);
+                }
+            }
+        }
+
+        Completions { start, candidates }
+    }
+}
+
+/// The token after the `typed` words, if they all match the start of `spec`.
+fn next_token<'a>(spec: &'a CommandSpec, typed: &str) -> Option<&'a Token> {
+    let mut words = typed.split_whitespace();
+    for token in &spec.tokens {
+        let Some(word) = words.next() else {
+            return Some(token);
+        };
+        let is_text = matches!(
+            token,
+            Token::Slot(Slot {
+                kind: SlotKind::Text,
+                ..
+            })
+        );
+        if is_text || all_consuming(parse_token(token))(word).is_err() {
+            return None;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complete() {
+        let grammar = Grammar::try_new([
+            ("get", "get <key: name>"),
+            ("get_all", "getall"),
+            ("insert", "insert <key: name> <value: text>"),
+            ("color", "set color <color: red|green|blue>"),
+            ("size", "set size <size: number>"),
+        ])
+        .unwrap();
+        let complete = |input: &str| {
+            let it = grammar.complete(input);
+            (it.start, it.candidates)
+        };
+
+        assert_eq!(complete(""), (0, vec!["get", "getall", "insert", "set"]));
+        assert_eq!(complete("  G"), (2, vec!["get", "getall"]));
+        assert_eq!(complete("set "), (4, vec!["color", "size"]));
+        assert_eq!(complete("set c"), (4, vec!["color"]));
+        assert_eq!(complete("SET COLOR "), (10, vec!["red", "green", "blue"]));
+        assert_eq!(complete("set color g"), (10, vec!["green"]));
+
+        // Free form slots, and commands that are already complete.
+        assert_eq!(complete("get "), (4, vec![]));
+        assert_eq!(complete("insert key "), (11, vec![]));
+        assert_eq!(complete("insert key some text "), (21, vec![]));
+        assert_eq!(complete("getall "), (7, vec![]));
+
+        // The typed words don't match any command.
+        assert_eq!(complete("nope "), (5, vec![]));
+        assert_eq!(complete("set colour r"), (11, vec![]));
+    }
+}
@@ -0,0 +1,205 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use crate::command_dsl::*;
+use miette::{Diagnostic, SourceSpan};
+use thiserror::Error;
+
+/// Returned by [Grammar::parse]. It holds a copy of the input, so it can be rendered w/
+/// [miette] on its own, eg: `println!("{:?}", miette::Report::new(error))`.
+#[derive(Error, Diagnostic, Clone, Debug, PartialEq)]
+#[error("Could not parse the command, expected {expected}")]
+#[diagnostic(code(command_dsl::parse_error))]
+pub struct CommandError {
+    pub expected: String,
+    #[source_code]
+    pub source_code: String,
+    #[label("here")]
+    pub span: SourceSpan,
+    /// Eg: "did you mean `insert`?", when the word is close to one of the expected keywords
+    /// or choices.
+    #[help]
+    pub help: Option<String>,
+}
+
+impl CommandError {
+    /// Only the commands that got the furthest into `input` are used, since the others
+    /// don't have anything to do w/ what was typed.
+    pub(crate) fn new(input: &str, mismatches: &[Mismatch<'_>]) -> Self {
+        let rest = mismatches
+            .iter()
+            .map(|it| it.rest)
+            .min_by_key(|it| it.len())
+            .unwrap_or(input);
+        let expected_tokens = mismatches
+            .iter()
+            .filter(|it| it.rest.len() == rest.len())
+            .map(|it| it.expected);
+
+        let mut expected = vec![];
+        let mut candidates = vec![];
+        for token in expected_tokens {
+            let description = match token {
+                None => "the end of the line".to_string(),
+                Some(Token::Keyword(keyword)) => {
+                    candidates.push(keyword.as_str());
+                    format!("`{keyword}`")
+                }
+                Some(Token::Slot(Slot { name, kind })) => match kind {
+                    SlotKind::Name => format!("a name for <{name}>"),
+                    SlotKind::Number => format!("a number for <{name}>"),
+                    SlotKind::Text => format!("some text for <{name}>"),
+                    SlotKind::Choice(choices) => {
+                        candidates.extend(choices.iter().map(String::as_str));
+                        let choices = choices.iter().map(|it| format!("`{it}`")).collect();
+                        format!("{} for <{name}>", join_w_or(choices))
+                    }
+                },
+            };
+            if !expected.contains(&description) {
+                expected.push(description);
+            }
+        }
+
+        let found = parse_word(rest).map_or("", |(_, it)| it);
+        CommandError {
+            expected: join_w_or(expected),
+            source_code: input.to_string(),
+            span: (input.len() - rest.len(), found.len()).into(),
+            help: suggest(found, &candidates).map(|it| format!("did you mean `{it}`?")),
+        }
+    }
+}
+
+/// Eg: "`a`, `b`, or `c`".
+fn join_w_or(mut items: Vec<String>) -> String {
+    match items.len() {
+        0 | 1 => items.pop().unwrap_or_default(),
+        2 => items.join(" or "),
+        _ => {
+            let last = items.pop().unwrap_or_default();
+            format!("{}, or {last}", items.join(", "))
+        }
+    }
+}
+
+/// The closest candidate to `word` (ignoring case), if it is a close enough typo, or if
+/// `word` is the start of it.
+pub fn suggest<'a>(word: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    if word.is_empty() {
+        return None;
+    }
+    let word = word.to_lowercase();
+    let max_distance = (word.chars().count() / 3).max(1);
+    candidates
+        .iter()
+        .map(|it| (*it, edit_distance(&word, &it.to_lowercase())))
+        .filter(|(it, distance)| *distance <= max_distance || it.to_lowercase().starts_with(&word))
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(it, _)| it)
+}
+
+/// The Levenshtein distance between `a` and `b`, ie: the number of chars that have to be
+/// inserted, deleted, or replaced to turn one into the other.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let replace = previous[j] + usize::from(a_char != *b_char);
+            current.push(replace.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miette::{GraphicalReportHandler, GraphicalTheme};
+
+    fn error(input: &str) -> CommandError {
+        Grammar::try_new([
+            ("get", "get <key: name>"),
+            ("get_all", "getall"),
+            ("insert", "insert <key: name> <value: text>"),
+            ("color", "set color <color: red|green|blue>"),
+            ("size", "set size <size: number>"),
+        ])
+        .unwrap()
+        .parse(input)
+        .unwrap_err()
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("insert", "insert"), 0);
+        assert_eq!(edit_distance("insrt", "insert"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(suggest("INSRET", &["get", "insert"]), Some("insert"));
+        assert_eq!(suggest("ge", &["get", "getall"]), Some("get"));
+        assert_eq!(suggest("xyz", &["get", "insert"]), None);
+        assert_eq!(suggest("", &["get"]), None);
+    }
+
+    #[test]
+    fn test_error_points_at_the_problem() {
+        let it = error("insrt key value");
+        assert_eq!(
+            it.expected,
+            "`get`, `getall`, `insert`, or `set`".to_string()
+        );
+        assert_eq!(it.span, (0, 5).into());
+        assert_eq!(it.help.as_deref(), Some("did you mean `insert`?"));
+
+        let it = error("set color gren");
+        assert_eq!(
+            it.expected,
+            "`red`, `green`, or `blue` for <color>".to_string()
+        );
+        assert_eq!(it.span, (10, 4).into());
+        assert_eq!(it.help.as_deref(), Some("did you mean `green`?"));
+
+        let it = error("set colour red");
+        assert_eq!(it.expected, "`color` or `size`".to_string());
+        assert_eq!(it.help.as_deref(), Some("did you mean `color`?"));
+
+        let it = error("get");
+        assert_eq!(it.expected, "a name for <key>".to_string());
+        assert_eq!(it.span, (3, 0).into());
+        assert_eq!(it.help, None);
+
+        let it = error("getall now");
+        assert_eq!(it.expected, "the end of the line".to_string());
+        assert_eq!(it.span, (7, 3).into());
+    }
+
+    #[test]
+    fn test_error_renders_w_miette() {
+        let mut rendered = String::new();
+        GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+            .render_report(&mut rendered, &error("set color gren"))
+            .unwrap();
+        assert!(rendered.contains("command_dsl::parse_error"));
+        assert!(rendered.contains("did you mean `green`?"));
+        assert!(rendered.contains("set color gren"));
+    }
+}
@@ -0,0 +1,262 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use nom::{
+    branch::*, bytes::complete::*, character::complete::*, combinator::*, multi::*, sequence::*,
+    Err as NomErr, IResult,
+};
+use thiserror::Error;
+
+/// All the commands that can be parsed, in the order that they were declared.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grammar {
+    pub commands: Vec<CommandSpec>,
+}
+
+impl Grammar {
+    /// Parse the spec of each `(name, spec)` command, eg: `("get", "get <key: name>")`. The
+    /// grammar is usually a constant, so an error here is a bug in the spec.
+    pub fn try_new<'a>(
+        commands: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<Self, GrammarError> {
+        let commands = commands
+            .into_iter()
+            .map(|(name, spec)| CommandSpec::try_new(name, spec))
+            .collect::<Result<_, _>>()?;
+        Ok(Grammar { commands })
+    }
+}
+
+/// A single command in a [Grammar], eg: `get <key: name>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommandSpec {
+    pub name: String,
+    pub tokens: Vec<Token>,
+}
+
+impl CommandSpec {
+    pub fn try_new(name: &str, spec: &str) -> Result<Self, GrammarError> {
+        let tokens = match parse_spec(spec) {
+            Ok((_, tokens)) => tokens,
+            Err(NomErr::Error(error) | NomErr::Failure(error)) => {
+                return Err(GrammarError::InvalidSpec {
+                    command: name.to_string(),
+                    offset: spec.len() - error.input.len(),
+                })
+            }
+            Err(NomErr::Incomplete(_)) => unreachable!("Only complete parsers are used"),
+        };
+
+        let slots = tokens.iter().filter_map(|it| match it {
+            Token::Slot(slot) => Some(slot),
+            Token::Keyword(_) => None,
+        });
+        for (index, slot) in slots.clone().enumerate() {
+            if slots.clone().skip(index + 1).any(|it| it.name == slot.name) {
+                return Err(GrammarError::DuplicateSlot {
+                    command: name.to_string(),
+                    slot: slot.name.clone(),
+                });
+            }
+        }
+        let is_text = |it: &Token| {
+            matches!(
+                it,
+                Token::Slot(Slot {
+                    kind: SlotKind::Text,
+                    ..
+                })
+            )
+        };
+        if let Some(Token::Slot(slot)) = tokens.iter().rev().skip(1).find(|it| is_text(it)) {
+            return Err(GrammarError::TextNotLast {
+                command: name.to_string(),
+                slot: slot.name.clone(),
+            });
+        }
+
+        Ok(CommandSpec {
+            name: name.to_string(),
+            tokens,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token {
+    /// A word that has to be typed as is (ignoring case).
+    Keyword(String),
+    Slot(Slot),
+}
+
+/// A `<name: kind>` in a spec.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Slot {
+    pub name: String,
+    pub kind: SlotKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SlotKind {
+    /// A word that starts w/ a letter, followed by letters, digits, `_`, or `-`.
+    Name,
+    /// An integer, eg: `42` or `-7`.
+    Number,
+    /// One of the words (ignoring case), eg: `<language: rust|go>`.
+    Choice(Vec<String>),
+    /// The rest of the line, so it has to be the last token.
+    Text,
+}
+
+#[derive(Error, Clone, Debug, PartialEq)]
+pub enum GrammarError {
+    #[error("Could not parse the spec of the `{command}` command at byte {offset}")]
+    InvalidSpec { command: String, offset: usize },
+
+    #[error("The `{slot}` slot is used more than once in the `{command}` command")]
+    DuplicateSlot { command: String, slot: String },
+
+    #[error("The `{slot}` slot in the `{command}` command is text, so it has to be last")]
+    TextNotLast { command: String, slot: String },
+}
+
+/// Parse a whole spec, eg: `get <key: name>`, which must have at least one token.
+pub fn parse_spec(input: &str) -> IResult<&str, Vec<Token>> {
+    all_consuming(terminated(
+        many1(preceded(multispace0, alt((parse_slot, parse_keyword)))),
+        multispace0,
+    ))(input)
+}
+
+/// Any word that isn't a slot, eg: `hello,` or `i`.
+fn parse_keyword(input: &str) -> IResult<&str, Token> {
+    map(
+        take_till1(|it: char| it.is_whitespace() || it == '<' || it == '>'),
+        |it: &str| Token::Keyword(it.to_string()),
+    )(input)
+}
+
+/// Eg: `<age: number>` or `<language: rust|go>`. Once the `<` is matched, the slot has to be
+/// valid.
+fn parse_slot(input: &str) -> IResult<&str, Token> {
+    let (rem, (name, kind)) = delimited(
+        char('<'),
+        cut(separated_pair(
+            delimited(multispace0, parse_identifier, multispace0),
+            char(':'),
+            delimited(multispace0, parse_slot_kind, multispace0),
+        )),
+        cut(char('>')),
+    )(input)?;
+    let name = name.to_string();
+    Ok((rem, Token::Slot(Slot { name, kind })))
+}
+
+/// A single word is one of the kinds, and 2 or more words separated by `|` are a choice.
+fn parse_slot_kind(input: &str) -> IResult<&str, SlotKind> {
+    map_opt(
+        separated_list1(
+            delimited(multispace0, char('|'), multispace0),
+            parse_identifier,
+        ),
+        |words| match words.as_slice() {
+            ["name"] => Some(SlotKind::Name),
+            ["number"] => Some(SlotKind::Number),
+            ["text"] => Some(SlotKind::Text),
+            [_] => None,
+            _ => Some(SlotKind::Choice(
+                words.iter().map(|it| it.to_string()).collect(),
+            )),
+        },
+    )(input)
+}
+
+fn parse_identifier(input: &str) -> IResult<&str, &str> {
+    take_while1(|it: char| it.is_alphanumeric() || it == '_' || it == '-')(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spec() {
+        let keyword = |it: &str| Token::Keyword(it.to_string());
+        let slot = |name: &str, kind: SlotKind| {
+            Token::Slot(Slot {
+                name: name.to_string(),
+                kind,
+            })
+        };
+
+        assert_eq!(
+            parse_spec(" hello, <name:name> is <age : number>  <lang: rust | go>\t<rest: text> "),
+            Ok((
+                "",
+                vec![
+                    keyword("hello,"),
+                    slot("name", SlotKind::Name),
+                    keyword("is"),
+                    slot("age", SlotKind::Number),
+                    slot(
+                        "lang",
+                        SlotKind::Choice(vec!["rust".to_string(), "go".to_string()])
+                    ),
+                    slot("rest", SlotKind::Text),
+                ]
+            ))
+        );
+
+        assert!(parse_spec("").is_err());
+        assert!(parse_spec("get <key: color>").is_err());
+        assert!(parse_spec("get <key: name").is_err());
+        assert!(parse_spec("get <key>").is_err());
+        assert!(parse_spec("get key>").is_err());
+    }
+
+    #[test]
+    fn test_grammar_errors() {
+        assert_eq!(
+            Grammar::try_new([("ok", "get <key: name>"), ("get", "get <key: nope>")]),
+            Err(GrammarError::InvalidSpec {
+                command: "get".to_string(),
+                offset: 10
+            })
+        );
+        assert_eq!(
+            Grammar::try_new([("insert", "insert <key: name> <key: text>")]),
+            Err(GrammarError::DuplicateSlot {
+                command: "insert".to_string(),
+                slot: "key".to_string()
+            })
+        );
+        assert_eq!(
+            Grammar::try_new([("say", "say <message: text> now")]),
+            Err(GrammarError::TextNotLast {
+                command: "say".to_string(),
+                slot: "message".to_string()
+            })
+        );
+        assert_eq!(
+            Grammar::try_new([("say", "say <message: text>")])
+                .unwrap()
+                .commands
+                .len(),
+            1
+        );
+    }
+}
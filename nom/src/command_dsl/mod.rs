@@ -0,0 +1,42 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! A small command language, which is the grown up version of [crate::parse_natural_lang].
+//! Instead of hand writing a parser for each sentence, the [Grammar] is declared once, w/ a
+//! spec for each command, eg:
+//! ```text
+//! insert <key: name> <value: text>
+//! hello my name is <name: name> and i am <age: number> years old and i like <language: rust|go>
+//! ```
+//! - Words are keywords, and are matched ignoring case.
+//! - `<slot: kind>` captures a value, where `kind` is `name`, `number`, `text` (the rest of the
+//!   line), or a choice between words separated by `|`.
+//!
+//! [Grammar::parse] matches an input line against all the commands, and returns a [Command]
+//! that holds the values of the slots. When nothing matches, the [CommandError] points at the
+//! word that is wrong, w/ a "did you mean" suggestion when there is a close match. And
+//! [Grammar::complete] returns the candidates for the last (partially typed) word, for a REPL.
+
+pub mod completer;
+pub mod error;
+pub mod grammar;
+pub mod parser;
+
+pub use completer::*;
+pub use error::*;
+pub use grammar::*;
+pub use parser::*;
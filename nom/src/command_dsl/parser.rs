@@ -0,0 +1,265 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use crate::command_dsl::*;
+use nom::{bytes::complete::*, character::complete::*, combinator::*, sequence::*, IResult};
+
+/// A line of input that matched one of the commands in a [Grammar].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Command<'a> {
+    /// The name of the [CommandSpec] that matched.
+    pub name: &'a str,
+    /// The slot names and their values, in the order that they are in the spec.
+    pub args: Vec<(&'a str, Value<'a>)>,
+}
+
+impl<'a> Command<'a> {
+    pub fn get(&self, slot: &str) -> Option<Value<'a>> {
+        self.args
+            .iter()
+            .find(|(name, _)| *name == slot)
+            .map(|(_, value)| *value)
+    }
+
+    /// The value of a [SlotKind::Name], [SlotKind::Choice], or [SlotKind::Text] slot.
+    pub fn str(&self, slot: &str) -> Option<&'a str> {
+        match self.get(slot)? {
+            Value::Name(it) | Value::Choice(it) | Value::Text(it) => Some(it),
+            Value::Number(_) => None,
+        }
+    }
+
+    pub fn number(&self, slot: &str) -> Option<i64> {
+        match self.get(slot)? {
+            Value::Number(it) => Some(it),
+            _ => None,
+        }
+    }
+}
+
+/// The value of a slot. Names and text borrow from the input, and a choice borrows the word
+/// from the [Grammar] (so it doesn't depend on the case of the input).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Value<'a> {
+    Name(&'a str),
+    Number(i64),
+    Choice(&'a str),
+    Text(&'a str),
+}
+
+/// Where a command stopped matching, and what it expected there.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Mismatch<'a> {
+    /// The rest of the input, after the whitespace, where the match failed.
+    pub rest: &'a str,
+    /// [None] means that the end of the input was expected.
+    pub expected: Option<&'a Token>,
+}
+
+impl Grammar {
+    /// Match `input` against each of the commands (in order), and return the first one that
+    /// matches all of it. If none do, the error is about the command that got the furthest.
+    pub fn parse<'a>(&'a self, input: &'a str) -> Result<Command<'a>, CommandError> {
+        let mut mismatches = vec![];
+        for spec in &self.commands {
+            match match_command(spec, input) {
+                Ok(command) => return Ok(command),
+                Err(mismatch) => mismatches.push(mismatch),
+            }
+        }
+        Err(CommandError::new(input, &mismatches))
+    }
+}
+
+pub(crate) fn match_command<'a>(
+    spec: &'a CommandSpec,
+    input: &'a str,
+) -> Result<Command<'a>, Mismatch<'a>> {
+    let mut args = vec![];
+    let mut rest = input;
+    for token in &spec.tokens {
+        rest = rest.trim_start();
+        match parse_token(token)(rest) {
+            Ok((rem, value)) => {
+                if let (Token::Slot(slot), Some(value)) = (token, value) {
+                    args.push((slot.name.as_str(), value));
+                }
+                rest = rem;
+            }
+            Err(_) => {
+                return Err(Mismatch {
+                    rest,
+                    expected: Some(token),
+                })
+            }
+        }
+    }
+
+    rest = rest.trim_start();
+    if !rest.is_empty() {
+        return Err(Mismatch {
+            rest,
+            expected: None,
+        });
+    }
+    Ok(Command {
+        name: &spec.name,
+        args,
+    })
+}
+
+/// Returns a parser for a single word that must match `token`, or the rest of the line for a
+/// [SlotKind::Text]. Keywords produce [None].
+pub fn parse_token<'a>(
+    token: &'a Token,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Option<Value<'a>>> {
+    move |input| match token {
+        Token::Slot(Slot {
+            kind: SlotKind::Text,
+            ..
+        }) => map(
+            verify(rest, |it: &str| !it.trim().is_empty()),
+            |it: &str| Some(Value::Text(it.trim_end())),
+        )(input),
+        Token::Keyword(keyword) => map(
+            verify(parse_word, |it: &str| it.eq_ignore_ascii_case(keyword)),
+            |_| None,
+        )(input),
+        Token::Slot(Slot {
+            kind: SlotKind::Name,
+            ..
+        }) => map(all_consuming_word(parse_name), |it| Some(Value::Name(it)))(input),
+        Token::Slot(Slot {
+            kind: SlotKind::Number,
+            ..
+        }) => map(
+            all_consuming_word(map_res(
+                recognize(pair(opt(char('-')), digit1)),
+                str::parse::<i64>,
+            )),
+            |it| Some(Value::Number(it)),
+        )(input),
+        Token::Slot(Slot {
+            kind: SlotKind::Choice(choices),
+            ..
+        }) => map_opt(parse_word, |word: &str| {
+            choices
+                .iter()
+                .find(|it| it.eq_ignore_ascii_case(word))
+                .map(|it| Some(Value::Choice(it.as_str())))
+        })(input),
+    }
+}
+
+/// A word is everything up to the next whitespace.
+pub fn parse_word(input: &str) -> IResult<&str, &str> {
+    take_till1(char::is_whitespace)(input)
+}
+
+/// Eg: `tommaso`, `my_key`, or `key-2`.
+fn parse_name(input: &str) -> IResult<&str, &str> {
+    recognize(pair(
+        satisfy(char::is_alphabetic),
+        take_while(|it: char| it.is_alphanumeric() || it == '_' || it == '-'),
+    ))(input)
+}
+
+/// Run `parser` on the next word, which it has to consume completely.
+fn all_consuming_word<'a, O>(
+    parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O> {
+    let mut parser = all_consuming(parser);
+    move |input| {
+        let (rem, word) = parse_word(input)?;
+        let (_, output) = parser(word)?;
+        Ok((rem, output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grammar() -> Grammar {
+        Grammar::try_new([
+            ("get_all", "getall"),
+            ("get", "get <key: name>"),
+            ("insert", "insert <key: name> <value: text>"),
+            ("sleep", "sleep <seconds: number>"),
+            ("color", "set color <color: red|green|blue>"),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        let grammar = grammar();
+
+        let it = grammar.parse("  GetAll ").unwrap();
+        assert_eq!(it.name, "get_all");
+        assert!(it.args.is_empty());
+
+        let it = grammar.parse("get my_key").unwrap();
+        assert_eq!(it.name, "get");
+        assert_eq!(it.get("key"), Some(Value::Name("my_key")));
+
+        let it = grammar
+            .parse("insert key-2   some value w/ spaces  ")
+            .unwrap();
+        assert_eq!(it.str("key"), Some("key-2"));
+        assert_eq!(it.str("value"), Some("some value w/ spaces"));
+
+        let it = grammar.parse("sleep -10").unwrap();
+        assert_eq!(it.number("seconds"), Some(-10));
+        assert_eq!(it.str("seconds"), None);
+
+        let it = grammar.parse("set color GREEN").unwrap();
+        assert_eq!(it.get("color"), Some(Value::Choice("green")));
+        assert_eq!(it.get("nope"), None);
+    }
+
+    #[test]
+    fn test_parse_commands_errors() {
+        let grammar = grammar();
+        assert!(grammar.parse("").is_err());
+        assert!(grammar.parse("get").is_err());
+        assert!(grammar.parse("get 2key").is_err());
+        assert!(grammar.parse("get key extra").is_err());
+        assert!(grammar.parse("insert key").is_err());
+        assert!(grammar.parse("sleep 10s").is_err());
+        assert!(grammar.parse("sleep 99999999999999999999").is_err());
+        assert!(grammar.parse("set color pink").is_err());
+    }
+
+    /// The sentence from [crate::parse_natural_lang], in a single order.
+    #[test]
+    fn test_parse_natural_lang_sentence() {
+        let grammar = Grammar::try_new([(
+            "sentence",
+            "hello, my name is <name: name> and i am <age: number> years old \
+             and i like <language: rust|python|javascript>",
+        )])
+        .unwrap();
+
+        let it = grammar
+            .parse("Hello, my name is Tommaso and i am 32 years old and I like Rust")
+            .unwrap();
+        assert_eq!(it.str("name"), Some("Tommaso"));
+        assert_eq!(it.number("age"), Some(32));
+        assert_eq!(it.str("language"), Some("rust"));
+    }
+}
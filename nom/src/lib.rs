@@ -15,6 +15,7 @@
  *   limitations under the License.
  */
 
+pub mod command_dsl;
 pub mod convert_vec_string_for_parsing;
 pub mod parse_hex_with_error_handling;
 pub mod parse_natural_lang;
@@ -24,3 +25,6 @@ pub mod parser_simple_hex;
 
 pub mod md_parser;
 pub use md_parser::*;
+
+pub mod streaming;
+pub mod style_sheet;
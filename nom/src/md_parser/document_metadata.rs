@@ -0,0 +1,143 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use crate::*;
+use chrono::NaiveDate;
+use constants::*;
+
+/// Typed accessors for the metadata in a parsed [Document]. The metadata can come from the
+/// [Block::Title] & [Block::Tags] blocks, or from any [Block::Metadata] block. When a key shows
+/// up more than once, the first one wins, both w/in a single block and across blocks.
+pub trait DocumentMetadataExt<'a> {
+    /// The value of `key` from the first [Block::Metadata] block that has it.
+    fn metadata(&self, key: &str) -> Option<&MetadataValue<'a>>;
+
+    fn title(&self) -> Option<&str>;
+
+    /// Empty if there are no tags.
+    fn tags(&self) -> Vec<&str>;
+
+    /// The `date` key, which has to be formatted as [DATE_FORMAT], eg: `2023-06-01`. Returns
+    /// [None] if it is missing or invalid.
+    fn date(&self) -> Option<NaiveDate>;
+}
+
+impl<'a> DocumentMetadataExt<'a> for [Block<'a>] {
+    fn metadata(&self, key: &str) -> Option<&MetadataValue<'a>> {
+        self.iter().find_map(|block| match block {
+            Block::Metadata(metadata) => metadata.map.get(key),
+            _ => None,
+        })
+    }
+
+    fn title(&self) -> Option<&str> {
+        let title_block = self.iter().find_map(|block| match block {
+            Block::Title(title) => Some(*title),
+            _ => None,
+        });
+        title_block.or_else(|| match self.metadata(TITLE_KEY)? {
+            MetadataValue::Text(it) => Some(it.as_ref()),
+            MetadataValue::List(_) => None,
+        })
+    }
+
+    fn tags(&self) -> Vec<&str> {
+        let tags_block = self.iter().find_map(|block| match block {
+            Block::Tags(tags) => Some(tags.clone()),
+            _ => None,
+        });
+        tags_block.unwrap_or_else(|| match self.metadata(TAGS_KEY) {
+            Some(MetadataValue::List(it)) => it.iter().map(|it| it.as_ref()).collect(),
+            Some(MetadataValue::Text(it)) => it.split(COMMA).map(str::trim).collect(),
+            None => vec![],
+        })
+    }
+
+    fn date(&self) -> Option<NaiveDate> {
+        match self.metadata(DATE_KEY)? {
+            MetadataValue::Text(it) => NaiveDate::parse_from_str(it, DATE_FORMAT).ok(),
+            MetadataValue::List(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accessors_w_at_keys() {
+        let (_, document) = root_parse_markdown(
+            "@title: Something\n@tags: [a, b]\n@authors: [Foo, Bar]\n@date: 2023-06-01\n# Hi\n",
+        )
+        .unwrap();
+        assert_eq!(document.title(), Some("Something"));
+        assert_eq!(document.tags(), vec!["a", "b"]);
+        assert_eq!(document.date(), NaiveDate::from_ymd_opt(2023, 6, 1));
+        assert_eq!(
+            document.metadata("authors"),
+            Some(&MetadataValue::List(vec!["Foo".into(), "Bar".into()]))
+        );
+        assert_eq!(document.metadata("nope"), None);
+    }
+
+    #[test]
+    fn test_accessors_w_yaml_front_matter() {
+        let (_, document) = root_parse_markdown(
+            "---\ntitle: \"Some: title\"\ntags:\n  - a\n  - b\ndate: 2023-02-30\n---\n# Hi\n",
+        )
+        .unwrap();
+        assert_eq!(document.title(), Some("Some: title"));
+        assert_eq!(document.tags(), vec!["a", "b"]);
+        // Not a valid date.
+        assert_eq!(document.date(), None);
+    }
+
+    #[test]
+    fn test_accessors_w_json_front_matter() {
+        let (_, document) = root_parse_markdown(
+            ";;;\n{\"title\": \"Hi \\\"there\\\"\", \"tags\": \"a, b\", \"date\": \"2024-02-29\"}\n;;;\n",
+        )
+        .unwrap();
+        assert_eq!(document.title(), Some("Hi \"there\""));
+        assert_eq!(document.tags(), vec!["a", "b"]);
+        assert_eq!(document.date(), NaiveDate::from_ymd_opt(2024, 2, 29));
+    }
+
+    #[test]
+    fn test_accessors_w_duplicate_keys() {
+        // W/in one block.
+        let (_, document) =
+            root_parse_markdown("@date: 2023-06-01\n@date: 2024-01-01\n# Hi\n").unwrap();
+        assert_eq!(document.date(), NaiveDate::from_ymd_opt(2023, 6, 1));
+
+        // Across blocks.
+        let (_, document) = root_parse_markdown(
+            "---\ndate: 2023-06-01\ndate: 2024-01-01\n---\n@date: 2025-01-01\n",
+        )
+        .unwrap();
+        assert_eq!(document.date(), NaiveDate::from_ymd_opt(2023, 6, 1));
+    }
+
+    #[test]
+    fn test_accessors_wo_metadata() {
+        let (_, document) = root_parse_markdown("# Hi\n").unwrap();
+        assert_eq!(document.title(), None);
+        assert_eq!(document.tags(), Vec::<&str>::new());
+        assert_eq!(document.date(), None);
+    }
+}
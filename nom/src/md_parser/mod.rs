@@ -18,18 +18,40 @@
 //! The main entry point (function) for this Markdown parsing module is [parser#parse_markdown]. It
 //! takes a string slice and returns a vector of [Block]s.
 
+pub mod document_metadata;
 pub mod parser;
+pub mod parser_incremental;
+pub mod parser_with_recovery;
+pub mod renderer;
+pub mod renderer_ansi;
+pub mod renderer_markdown;
+pub mod renderer_plain_text;
+pub mod source_map;
+#[cfg(feature = "syntax-highlighting")]
+pub mod syntax_highlighter;
 pub mod translator;
 pub mod types;
 
+pub use document_metadata::*;
 pub use parser::*;
+pub use parser_incremental::*;
+pub use parser_with_recovery::*;
+pub use renderer::*;
+pub use renderer_ansi::*;
+pub use renderer_markdown::*;
+pub use renderer_plain_text::*;
+pub use source_map::*;
+#[cfg(feature = "syntax-highlighting")]
+pub use syntax_highlighter::*;
 pub use translator::*;
 pub use types::*;
 
 pub(crate) mod parser_impl_block;
 pub(crate) mod parser_impl_element;
 pub(crate) mod parser_impl_metadata;
+pub(crate) mod parser_impl_span;
 
 pub(crate) use parser_impl_block::*;
 pub(crate) use parser_impl_element::*;
 pub(crate) use parser_impl_metadata::*;
+pub(crate) use parser_impl_span::*;
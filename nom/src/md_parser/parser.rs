@@ -28,22 +28,44 @@ use nom::{branch::*, combinator::*, multi::*, IResult};
 /// following variants:
 /// 1. heading (which contains a [Level] & [Fragments]),
 /// 2. ordered & unordered list (which itself contains a [Vec] of [Fragments],
-/// 3. code block (which contains string slices of the language & code),
-/// 4. line (which contains a [Fragments]).
+/// 3. nested list (which contains a [List] of items, that may have their own sub lists),
+/// 4. code block (which contains string slices of the language & code),
+/// 5. block quote (which contains a [Vec] of [Fragments]),
+/// 6. horizontal rule,
+/// 7. table (which contains a [Table] of [Fragments] for each cell),
+/// 8. line (which contains a [Fragments]),
+/// 9. metadata (which contains a map of `@key: value` lines, or the YAML / JSON front matter
+///    that the document starts with).
+///
+/// Parsing stops at the first line that can't be parsed. Use
+/// [root_parse_markdown_with_recovery] to parse the whole input, w/ the position of each block
+/// & a list of diagnostics.
+pub fn root_parse_markdown(input: &str) -> IResult<&str, Document<'_>> {
+    let (input, front_matter) = opt(parse_front_matter)(input)?;
+    let (input, mut blocks) = many0(parse_block)(input)?;
+    if let Some(front_matter) = front_matter {
+        blocks.insert(0, Block::Metadata(front_matter));
+    }
+    Ok((input, blocks))
+}
+
+/// Parse a single [Block] of Markdown. Each of these parsers end up scanning until EOL.
 #[rustfmt::skip]
-pub fn root_parse_markdown(input: &str) -> IResult<&str, Document> {
-    many0(
-        /* Each of these parsers end up scanning until EOL. */
-        alt((
-            map(parse_title,                         Block::Title),
-            map(parse_tags,                          Block::Tags),
-            map(parse_block_heading,                 Block::Heading),
-            map(parse_block_unordered_list,          Block::UnorderedList),
-            map(parse_block_ordered_list,            Block::OrderedList),
-            map(parse_block_code,                    Block::CodeBlock),
-            map(parse_block_markdown_text_until_eol, Block::Text),
-        )),
-    )(input)
+pub fn parse_block(input: &str) -> IResult<&str, Block<'_>> {
+    alt((
+        map(parse_title,                         Block::Title),
+        map(parse_tags,                          Block::Tags),
+        map(parse_block_metadata,                Block::Metadata),
+        map(parse_block_heading,                 Block::Heading),
+        map(parse_block_horizontal_rule,         |_| Block::HorizontalRule),
+        map(parse_block_nested_list,             Block::NestedList),
+        map(parse_block_unordered_list,          Block::UnorderedList),
+        map(parse_block_ordered_list,            Block::OrderedList),
+        map(parse_block_code,                    Block::CodeBlock),
+        map(parse_block_quote,                   Block::BlockQuote),
+        map(parse_block_table,                   Block::Table),
+        map(parse_block_markdown_text_until_eol, Block::Text),
+    ))(input)
 }
 
 #[cfg(test)]
@@ -81,6 +103,46 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_parse_markdown_quote_rule_table_nested_list() {
+        let it = root_parse_markdown(raw_strings::MARKDOWN_INPUT_2);
+        assert_eq!(
+            it,
+            Ok((
+                "",
+                vec![
+                    Block::BlockQuote(vec![vec![Fragment::Plain("Quote")]]),
+                    Block::HorizontalRule,
+                    Block::NestedList(List {
+                        kind: ListKind::Ordered,
+                        items: vec![ListItem::from((
+                            vec![Fragment::Plain("one")],
+                            Some(List {
+                                kind: ListKind::Unordered,
+                                items: vec![ListItem::from((vec![Fragment::Plain("two")], None))],
+                            }),
+                        ))],
+                    }),
+                    Block::UnorderedList(vec![vec![Fragment::Plain("three")]]),
+                    Block::Table(Table {
+                        header: vec![vec![Fragment::Plain("a")], vec![Fragment::Plain("b")]],
+                        alignments: vec![Alignment::Center, Alignment::None],
+                        rows: vec![vec![vec![Fragment::Plain("1")], vec![Fragment::Plain("2")]]],
+                    }),
+                    Block::Text(vec![]),
+                    Block::Text(vec![Fragment::Plain("| not | a table |")]),
+                ]
+            ))
+        );
+        assert_eq!(
+            translate(it.unwrap().1),
+            "<blockquote><p>Quote</p></blockquote><hr /><ol><li>one<ul><li>two</li></ul></li></ol>\
+            <ul><li>three</li></ul><table><thead><tr><th align=\"center\">a</th><th>b</th></tr>\
+            </thead><tbody><tr><td align=\"center\">1</td><td>2</td></tr></tbody></table>\
+            <p>| not | a table |</p>"
+        );
+    }
+
     #[rustfmt::skip]
     mod raw_strings {
         /// Valid Markdown content.
@@ -105,6 +167,20 @@ foobar.pluralize('goose') # returns 'geese'
 foobar.singularize('phenomena') # returns 'phenomenon'
 ```"#;
 
+        /// Valid Markdown content w/ a block quote, rule, nested list, and table.
+        pub const MARKDOWN_INPUT_2: &str =
+r#"> Quote
+***
+1. one
+   - two
+- three
+| a | b |
+|:-:|---|
+| 1 | 2 |
+
+| not | a table |
+"#;
+
         /// Code block content.
         pub const CODE_BLOCK: &str =
 r#"import foobar
@@ -26,7 +26,7 @@ pub mod no_rustfmt_block {
     };
 
 /// Parse a single line of markdown text [MarkdownLineOfText].
-pub fn parse_block_markdown_text_until_eol(input: &str) -> IResult<&str, Fragments> {
+pub fn parse_block_markdown_text_until_eol(input: &str) -> IResult<&str, Fragments<'_>> {
     terminated(
         /* output */ many0(parse_element_markdown_inline),
         /* ends with (discarded) */ tag(NEW_LINE),
@@ -46,7 +46,7 @@ pub fn parse_heading_tag(input: &str) -> IResult<&str, Level> {
 
 /// This matches the heading tag and text until EOL. Outputs a tuple of [HeadingLevel] and
 /// [MarkdownLineOfText].
-pub fn parse_block_heading(input: &str) -> IResult<&str, (Level, Fragments)> {
+pub fn parse_block_heading(input: &str) -> IResult<&str, (Level, Fragments<'_>)> {
     tuple(
         (parse_heading_tag, parse_block_markdown_text_until_eol)
     )(input)
@@ -60,14 +60,14 @@ pub fn parse_unordered_list_tag(input: &str) -> IResult<&str, &str> {
     )(input)
 }
 
-pub fn parse_unordered_list_element(input: &str) -> IResult<&str, Fragments> {
+pub fn parse_unordered_list_element(input: &str) -> IResult<&str, Fragments<'_>> {
     preceded(
         /* prefix (discarded) */ parse_unordered_list_tag,
         /* output */ parse_block_markdown_text_until_eol,
     )(input)
 }
 
-pub fn parse_block_unordered_list(input: &str) -> IResult<&str, Vec<Fragments>> {
+pub fn parse_block_unordered_list(input: &str) -> IResult<&str, Vec<Fragments<'_>>> {
     many1(
         parse_unordered_list_element
     )(input)
@@ -84,20 +84,20 @@ pub fn parse_ordered_list_tag(input: &str) -> IResult<&str, &str> {
     )(input)
 }
 
-pub fn parse_ordered_list_element(input: &str) -> IResult<&str, Fragments> {
+pub fn parse_ordered_list_element(input: &str) -> IResult<&str, Fragments<'_>> {
     preceded(
         /* prefix (discarded) */ parse_ordered_list_tag,
         /* output */ parse_block_markdown_text_until_eol,
     )(input)
 }
 
-pub fn parse_block_ordered_list(input: &str) -> IResult<&str, Vec<Fragments>> {
+pub fn parse_block_ordered_list(input: &str) -> IResult<&str, Vec<Fragments<'_>>> {
     many1(
         parse_ordered_list_element
     )(input)
 }
 
-pub fn parse_block_code(input: &str) -> IResult<&str, CodeBlock> {
+pub fn parse_block_code(input: &str) -> IResult<&str, CodeBlock<'_>> {
     let (input, output) = tuple(
         (parse_code_block_lang, parse_code_block_body)
     )(input)?;
@@ -107,7 +107,7 @@ pub fn parse_block_code(input: &str) -> IResult<&str, CodeBlock> {
 pub fn parse_code_block_body(input: &str) -> IResult<&str, &str> {
     delimited(
         /* start */ tag(NEW_LINE),
-        /* output */ is_not(CODE_BLOCK),
+        /* output */ take_until(CODE_BLOCK),
         /* end */ tag(CODE_BLOCK),
     )(input)
 }
@@ -124,6 +124,180 @@ pub fn parse_code_block_lang(input: &str) -> IResult<&str, &str> {
     ))(input)
 }
 
+/// Matches exactly `indent` spaces, followed by `- ` or `1. `. Outputs the [ListKind].
+pub fn parse_nested_list_tag(input: &str, indent: usize) -> IResult<&str, ListKind> {
+    preceded(
+        /* prefix (discarded) */ pair(count(char(' '), indent), not(char(' '))),
+        /* output */ alt((
+            map(parse_unordered_list_tag, |_| ListKind::Unordered),
+            map(parse_ordered_list_tag,   |_| ListKind::Ordered),
+        )),
+    )(input)
+}
+
+/// Parse a single list item of the given `kind` at `indent`, along w/ its sub list (if any).
+pub fn parse_nested_list_item(input: &str, indent: usize, kind: ListKind) -> IResult<&str, ListItem<'_>> {
+    let (input, _) = verify(
+        /* output */ |it| parse_nested_list_tag(it, indent),
+        /* must match */ |it: &ListKind| *it == kind,
+    )(input)?;
+    let (input, content) = parse_block_markdown_text_until_eol(input)?;
+    let (input, children) = opt(|it| parse_nested_sub_list(it, indent))(input)?;
+    Ok((input, ListItem::from((content, children))))
+}
+
+/// A sub list has to be indented further than its parent item (which is at `parent_indent`).
+pub fn parse_nested_sub_list(input: &str, parent_indent: usize) -> IResult<&str, List<'_>> {
+    let (_, spaces) = take_while(|it| it == ' ')(input)?;
+    let (_, indent) = verify(success(spaces.len()), |it| *it > parent_indent)(input)?;
+    parse_list_at_indent(input, indent)
+}
+
+/// All the items in a list have the same [ListKind] & `indent`. An item w/ a different kind or
+/// a smaller indent ends the list.
+pub fn parse_list_at_indent(input: &str, indent: usize) -> IResult<&str, List<'_>> {
+    let (_, kind) = peek(|it| parse_nested_list_tag(it, indent))(input)?;
+    let (input, items) = many1(
+        |it| parse_nested_list_item(it, indent, kind)
+    )(input)?;
+    Ok((input, List { kind, items }))
+}
+
+/// Only matches lists that have at least one sub list. Flat lists are left for
+/// [parse_block_unordered_list] & [parse_block_ordered_list] to handle.
+pub fn parse_block_nested_list(input: &str) -> IResult<&str, List<'_>> {
+    verify(
+        /* output */ |it| parse_list_at_indent(it, 0),
+        /* must have a sub list */ |list: &List| list.items.iter().any(|it| it.children.is_some()),
+    )(input)
+}
+
+/// Matches `> ` (the space is optional). Outputs the `>` char.
+pub fn parse_block_quote_tag(input: &str) -> IResult<&str, &str> {
+    terminated(
+        /* output `>` */ tag(BLOCK_QUOTE),
+        /* ends with (discarded) */ opt(tag(SPACE)),
+    )(input)
+}
+
+/// Matches `> ` (the space is optional). Outputs the text until EOL.
+pub fn parse_block_quote_line(input: &str) -> IResult<&str, Fragments<'_>> {
+    preceded(
+        /* prefix (discarded) */ parse_block_quote_tag,
+        /* output */ parse_block_markdown_text_until_eol,
+    )(input)
+}
+
+pub fn parse_block_quote(input: &str) -> IResult<&str, Lines<'_>> {
+    many1(
+        parse_block_quote_line
+    )(input)
+}
+
+/// Matches 3 or more of the same `-`, `*`, or `_` char, which may be separated by spaces, on a
+/// line by themselves. Outputs the matched chars.
+pub fn parse_block_horizontal_rule(input: &str) -> IResult<&str, &str> {
+    terminated(
+        /* output */ alt((
+            parse_horizontal_rule_chars(HORIZONTAL_RULE_CHARS[0]),
+            parse_horizontal_rule_chars(HORIZONTAL_RULE_CHARS[1]),
+            parse_horizontal_rule_chars(HORIZONTAL_RULE_CHARS[2]),
+        )),
+        /* ends with (discarded) */ tag(NEW_LINE),
+    )(input)
+}
+
+fn parse_horizontal_rule_chars<'a>(it: char) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str> {
+    recognize(
+        pair(
+            /* at least 3 */ count(terminated(char(it), space0), 3),
+            /* and then some more */ many0(terminated(char(it), space0)),
+        )
+    )
+}
+
+/// Matches `| cell | cell |\n`. Outputs the trimmed cells.
+pub fn parse_table_row(input: &str) -> IResult<&str, Vec<&str>> {
+    delimited(
+        /* start */ tag(TABLE_PIPE),
+        /* output */ many1(
+            map(parse_table_row_cell, |it: &str| it.trim())
+        ),
+        /* end */ parse_table_row_end,
+    )(input)
+}
+
+/// Matches a cell that ends w/ `|`, eg: ` cell |`. Outputs the cell as is, ie: w/ its spaces.
+pub fn parse_table_row_cell(input: &str) -> IResult<&str, &str> {
+    terminated(
+        /* output */ take_till(|it| it == '|' || it == '\n'),
+        /* ends with (discarded) */ tag(TABLE_PIPE),
+    )(input)
+}
+
+/// Matches the spaces after the last `|` of a row, and the EOL.
+pub fn parse_table_row_end(input: &str) -> IResult<&str, &str> {
+    recognize(
+        pair(space0, tag(NEW_LINE))
+    )(input)
+}
+
+/// Matches `|:---|:--:|---:|\n`. Outputs an [Alignment] for each column.
+pub fn parse_table_delimiter_row(input: &str) -> IResult<&str, Vec<Alignment>> {
+    map_opt(
+        parse_table_row,
+        |cells| cells
+            .iter()
+            .map(|cell| parse_table_alignment(cell).ok().map(|(_, it)| it))
+            .collect::<Option<Vec<_>>>(),
+    )(input)
+}
+
+/// Matches `---`, `:--`, `:-:`, or `--:`.
+pub fn parse_table_alignment(input: &str) -> IResult<&str, Alignment> {
+    map(
+        all_consuming(tuple((
+            opt(char(TABLE_ALIGN_COLON)),
+            take_while1(|it| it == TABLE_DASH),
+            opt(char(TABLE_ALIGN_COLON)),
+        ))),
+        |(left, _, right)| match (left, right) {
+            (Some(_), Some(_)) => Alignment::Center,
+            (Some(_), None)    => Alignment::Left,
+            (None, Some(_))    => Alignment::Right,
+            (None, None)       => Alignment::None,
+        },
+    )(input)
+}
+
+/// A cell that can't be parsed as Markdown (eg: it has an unmatched `*`) is kept as plain text.
+pub fn parse_table_cell(input: &str) -> Fragments<'_> {
+    match all_consuming(many0(parse_element_markdown_inline))(input) {
+        Ok((_, output)) => output,
+        Err(_) => vec![Fragment::Plain(input)],
+    }
+}
+
+/// A table has a header row, followed by a delimiter row w/ the same number of columns, followed
+/// by any number of body rows.
+pub fn parse_block_table(input: &str) -> IResult<&str, Table<'_>> {
+    let (input, header) = parse_table_row(input)?;
+    let (input, alignments) = verify(
+        parse_table_delimiter_row,
+        |it: &Vec<Alignment>| it.len() == header.len(),
+    )(input)?;
+    let (input, rows) = many0(parse_table_row)(input)?;
+
+    Ok((input, Table {
+        header: header.into_iter().map(parse_table_cell).collect(),
+        alignments,
+        rows: rows
+            .into_iter()
+            .map(|row| row.into_iter().map(parse_table_cell).collect())
+            .collect(),
+    }))
+}
+
 }
 pub use no_rustfmt_block::*;
 
@@ -148,7 +322,7 @@ mod tests {
                 "",
                 vec![
                     Fragment::Plain("here is some plaintext "),
-                    Fragment::Italic("but what if we italicize?"),
+                    Fragment::Italic(vec![Fragment::Plain("but what if we italicize?")]),
                 ]
             ))
         );
@@ -156,9 +330,9 @@ mod tests {
             parse_block_markdown_text_until_eol("here is some plaintext *but what if we italicize?* I guess it doesn't **matter** in my `code`\n"),
             Ok(("", vec![
                 Fragment::Plain("here is some plaintext "),
-                Fragment::Italic("but what if we italicize?"),
+                Fragment::Italic(vec![Fragment::Plain("but what if we italicize?")]),
                 Fragment::Plain(" I guess it doesn't "),
-                Fragment::Bold("matter"),
+                Fragment::Bold(vec![Fragment::Plain("matter")]),
                 Fragment::Plain(" in my "),
                 Fragment::InlineCode("code"),
             ]))
@@ -171,7 +345,7 @@ mod tests {
                 "",
                 vec![
                     Fragment::Plain("here is some plaintext "),
-                    Fragment::Italic("but what if we italicize?"),
+                    Fragment::Italic(vec![Fragment::Plain("but what if we italicize?")]),
                 ]
             ))
         );
@@ -186,6 +360,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_block_markdown_text_escapes_and_line_breaks() {
+        assert_eq!(
+            parse_block_markdown_text_until_eol("\\*literal\\* and <https://r3bl.com>  \n"),
+            Ok((
+                "",
+                vec![
+                    Fragment::Escaped("*"),
+                    Fragment::Plain("literal"),
+                    Fragment::Escaped("*"),
+                    Fragment::Plain(" and "),
+                    Fragment::Autolink("https://r3bl.com"),
+                    Fragment::LineBreak("  "),
+                ]
+            ))
+        );
+        assert_eq!(
+            parse_block_markdown_text_until_eol("ends w/ a backslash\\\n"),
+            Ok((
+                "",
+                vec![
+                    Fragment::Plain("ends w/ a backslash"),
+                    Fragment::LineBreak("\\"),
+                ]
+            ))
+        );
+        assert_eq!(
+            parse_block_markdown_text_until_eol("**a *b* c**\n"),
+            Ok((
+                "",
+                vec![Fragment::Bold(vec![
+                    Fragment::Plain("a "),
+                    Fragment::Italic(vec![Fragment::Plain("b")]),
+                    Fragment::Plain(" c"),
+                ])]
+            ))
+        );
+    }
+
     #[test]
     fn test_parse_header_tag() {
         assert_eq!(parse_heading_tag("# "), Ok(("", 1.into())));
@@ -509,6 +722,259 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_codeblock_w_backticks_in_body() {
+        assert_eq!(
+            parse_block_code("```rs\nlet x = `y`;\n```"),
+            Ok(("", CodeBlock::from(("rs", "let x = `y`;\n"))))
+        );
+    }
+
+    #[test]
+    fn test_parse_nested_list_tag() {
+        assert_eq!(
+            parse_nested_list_tag("- ", 0),
+            Ok(("", ListKind::Unordered))
+        );
+        assert_eq!(
+            parse_nested_list_tag("  1. foo", 2),
+            Ok(("foo", ListKind::Ordered))
+        );
+        assert_eq!(
+            parse_nested_list_tag("   - foo", 2),
+            Err(NomErr::Error(Error {
+                input: " - foo",
+                code: ErrorKind::Not
+            }))
+        );
+        assert_eq!(
+            parse_nested_list_tag(" - foo", 2),
+            Err(NomErr::Error(Error {
+                input: "- foo",
+                code: ErrorKind::Char
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_nested_list() {
+        assert_eq!(
+            parse_block_nested_list(raw_strings::NESTED_LIST),
+            Ok((
+                "",
+                List {
+                    kind: ListKind::Unordered,
+                    items: vec![
+                        ListItem::from((
+                            vec![Fragment::Plain("item 1")],
+                            Some(List {
+                                kind: ListKind::Unordered,
+                                items: vec![ListItem::from((
+                                    vec![Fragment::Plain("item 1.1")],
+                                    Some(List {
+                                        kind: ListKind::Ordered,
+                                        items: vec![
+                                            ListItem::from((
+                                                vec![Fragment::Plain("item 1.1.1")],
+                                                None
+                                            )),
+                                            ListItem::from((
+                                                vec![Fragment::Plain("item 1.1.2")],
+                                                None
+                                            )),
+                                        ],
+                                    }),
+                                ))],
+                            }),
+                        )),
+                        ListItem::from((vec![Fragment::Plain("item 2")], None)),
+                    ],
+                }
+            ))
+        );
+        // Flat lists are left for the other list parsers.
+        assert_eq!(
+            parse_block_nested_list(raw_strings::UNORDERED_LIST_ELEMENT),
+            Err(NomErr::Error(Error {
+                input: raw_strings::UNORDERED_LIST_ELEMENT,
+                code: ErrorKind::Verify
+            }))
+        );
+        // An item w/ a different list kind at the same indent ends the list.
+        assert_eq!(
+            parse_block_nested_list("- foo\n  - bar\n1. baz\n"),
+            Ok((
+                "1. baz\n",
+                List {
+                    kind: ListKind::Unordered,
+                    items: vec![ListItem::from((
+                        vec![Fragment::Plain("foo")],
+                        Some(List {
+                            kind: ListKind::Unordered,
+                            items: vec![ListItem::from((vec![Fragment::Plain("bar")], None))],
+                        }),
+                    ))],
+                }
+            ))
+        );
+        assert_eq!(
+            parse_block_nested_list("  - foo\n"),
+            Err(NomErr::Error(Error {
+                input: "  - foo\n",
+                code: ErrorKind::Not
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_block_quote() {
+        assert_eq!(
+            parse_block_quote("> this is a quote\n"),
+            Ok(("", vec![vec![Fragment::Plain("this is a quote")]]))
+        );
+        assert_eq!(
+            parse_block_quote(raw_strings::BLOCK_QUOTE),
+            Ok((
+                "not a quote\n",
+                vec![
+                    vec![Fragment::Plain("this is a quote")],
+                    vec![],
+                    vec![
+                        Fragment::Plain("with "),
+                        Fragment::Bold(vec![Fragment::Plain("bold")])
+                    ],
+                ]
+            ))
+        );
+        assert_eq!(
+            parse_block_quote("> no EOL"),
+            Err(NomErr::Error(Error {
+                input: "",
+                code: ErrorKind::Tag
+            }))
+        );
+        assert_eq!(
+            parse_block_quote("not a quote\n"),
+            Err(NomErr::Error(Error {
+                input: "not a quote\n",
+                code: ErrorKind::Tag
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_horizontal_rule() {
+        assert_eq!(parse_block_horizontal_rule("---\n"), Ok(("", "---")));
+        assert_eq!(parse_block_horizontal_rule("*****\n"), Ok(("", "*****")));
+        assert_eq!(
+            parse_block_horizontal_rule("_ _ _ \nfoo"),
+            Ok(("foo", "_ _ _ "))
+        );
+        assert_eq!(
+            parse_block_horizontal_rule("--\n"),
+            Err(NomErr::Error(Error {
+                input: "--\n",
+                code: ErrorKind::Char
+            }))
+        );
+        assert_eq!(
+            parse_block_horizontal_rule("-*-\n"),
+            Err(NomErr::Error(Error {
+                input: "-*-\n",
+                code: ErrorKind::Char
+            }))
+        );
+        assert_eq!(
+            parse_block_horizontal_rule("--- foo\n"),
+            Err(NomErr::Error(Error {
+                input: "foo\n",
+                code: ErrorKind::Tag
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_table_row() {
+        assert_eq!(parse_table_row("| a | b |\n"), Ok(("", vec!["a", "b"])));
+        assert_eq!(
+            parse_table_row("|a||  c  |  \n"),
+            Ok(("", vec!["a", "", "c"]))
+        );
+        assert_eq!(
+            parse_table_row("| a | b"),
+            Err(NomErr::Error(Error {
+                input: "b",
+                code: ErrorKind::Tag
+            }))
+        );
+        assert_eq!(
+            parse_table_row("a | b |\n"),
+            Err(NomErr::Error(Error {
+                input: "a | b |\n",
+                code: ErrorKind::Tag
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_table_delimiter_row() {
+        assert_eq!(
+            parse_table_delimiter_row("|---|:--|:-:|--:|\n"),
+            Ok((
+                "",
+                vec![
+                    Alignment::None,
+                    Alignment::Left,
+                    Alignment::Center,
+                    Alignment::Right
+                ]
+            ))
+        );
+        assert_eq!(
+            parse_table_delimiter_row("| a |\n"),
+            Err(NomErr::Error(Error {
+                input: "| a |\n",
+                code: ErrorKind::MapOpt
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_table() {
+        assert_eq!(
+            parse_block_table(raw_strings::TABLE),
+            Ok((
+                "",
+                Table {
+                    header: vec![vec![Fragment::Plain("Name")], vec![Fragment::Plain("Age")]],
+                    alignments: vec![Alignment::Left, Alignment::Right],
+                    rows: vec![
+                        vec![
+                            vec![Fragment::InlineCode("foo")],
+                            vec![Fragment::Plain("42")]
+                        ],
+                        vec![vec![Fragment::Plain("*bar")], vec![]],
+                    ],
+                }
+            ))
+        );
+        // The delimiter row must have the same number of columns as the header row.
+        assert_eq!(
+            parse_block_table("| a | b |\n|---|\n"),
+            Err(NomErr::Error(Error {
+                input: "|---|\n",
+                code: ErrorKind::Verify
+            }))
+        );
+        assert_eq!(
+            parse_block_table("| a | b |\n"),
+            Err(NomErr::Error(Error {
+                input: "",
+                code: ErrorKind::Tag
+            }))
+        );
+    }
+
     #[rustfmt::skip]
     mod raw_strings {
         pub const UNORDERED_LIST_ELEMENT: &str =
@@ -518,6 +984,28 @@ r#"- this is an element
         pub const ORDERED_LIST_ELEMENT: &str =
 r#"1. this is an element
 1. here is another
+"#;
+
+        pub const NESTED_LIST: &str =
+r#"- item 1
+  - item 1.1
+    1. item 1.1.1
+    2. item 1.1.2
+- item 2
+"#;
+
+        pub const BLOCK_QUOTE: &str =
+r#"> this is a quote
+>
+>with **bold**
+not a quote
+"#;
+
+        pub const TABLE: &str =
+r#"| Name  | Age |
+|:------|----:|
+| `foo` | 42  |
+| *bar  |     |
 "#;
 
         pub const CODE_BLOCK_1_INPUT: &str =
@@ -25,42 +25,106 @@ pub mod no_rustfmt_block {
         sequence::*, IResult,
     };
 
+    /// Matches the content of an emphasis span that ends w/ `end`. Inline code & backslash
+    /// escapes are skipped over, so that an `end` inside of them doesn't end the span early. The
+    /// content can't span multiple lines.
+    pub fn parse_emphasis_content<'a>(end: &'static str) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str> {
+        recognize(
+            many1(
+                alt((
+                    recognize(parse_element_code),
+                    recognize(parse_element_escape),
+                    recognize(preceded(not(alt((tag(end), tag(NEW_LINE)))), anychar)),
+                ))
+            )
+        )
+    }
+
+    /// Same as [parse_emphasis_content], except that a nested bold span (eg: `**` inside of `*`)
+    /// is skipped over as well.
+    pub fn parse_italic_content<'a>(end: &'static str) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str> {
+        recognize(
+            many1(
+                alt((
+                    recognize(parse_element_bold),
+                    recognize(parse_element_code),
+                    recognize(parse_element_escape),
+                    recognize(preceded(not(alt((tag(end), tag(NEW_LINE)))), anychar)),
+                ))
+            )
+        )
+    }
+
     pub fn parse_element_bold_italic(input: &str) -> IResult<&str, &str> {
         alt((
-            delimited(/* start */ tag(BITALIC_1), /* output */ is_not(BITALIC_1), /* end */ tag(BITALIC_1)),
-            delimited(/* start */ tag(BITALIC_2), /* output */ is_not(BITALIC_2), /* end */ tag(BITALIC_2)),
+            delimited(/* start */ tag(BITALIC_1), /* output */ parse_emphasis_content(BITALIC_1), /* end */ tag(BITALIC_1)),
+            delimited(/* start */ tag(BITALIC_2), /* output */ parse_emphasis_content(BITALIC_2), /* end */ tag(BITALIC_2)),
         ))(input)
     }
 
     pub fn parse_element_bold(input: &str) -> IResult<&str, &str> {
         alt((
-            delimited(/* start */ tag(BOLD_1), /* output */ is_not(BOLD_1), /* end */ tag(BOLD_1)),
-            delimited(/* start */ tag(BOLD_2), /* output */ is_not(BOLD_2), /* end */ tag(BOLD_2)),
+            delimited(/* start */ tag(BOLD_1), /* output */ parse_emphasis_content(BOLD_1), /* end */ tag(BOLD_1)),
+            delimited(/* start */ tag(BOLD_2), /* output */ parse_emphasis_content(BOLD_2), /* end */ tag(BOLD_2)),
         ))(input)
     }
 
     pub fn parse_element_italic(input: &str) -> IResult<&str, &str> {
         alt((
-            delimited(/* start */ tag(ITALIC_1), /* output */ is_not(ITALIC_1), /* end */ tag(ITALIC_1)),
-            delimited(/* start */ tag(ITALIC_2), /* output */ is_not(ITALIC_2), /* end */ tag(ITALIC_2)),
+            delimited(/* start */ tag(ITALIC_1), /* output */ parse_italic_content(ITALIC_1), /* end */ tag(ITALIC_1)),
+            delimited(/* start */ tag(ITALIC_2), /* output */ parse_italic_content(ITALIC_2), /* end */ tag(ITALIC_2)),
         ))(input)
     }
 
+    pub fn parse_element_strikethrough(input: &str) -> IResult<&str, &str> {
+        delimited(/* start */ tag(STRIKETHROUGH), /* output */ parse_emphasis_content(STRIKETHROUGH), /* end */ tag(STRIKETHROUGH))(input)
+    }
+
     pub fn parse_element_code(input: &str) -> IResult<&str, &str> {
-        delimited(/* start */ tag(BACKTICK), /* output */ is_not(BACKTICK), /* end */ tag(BACKTICK))(input)
+        delimited(/* start */ tag(BACKTICK), /* output */ is_not("`\n"), /* end */ tag(BACKTICK))(input)
     }
 
     pub fn parse_element_link(input: &str) -> IResult<&str, (&str, &str)> {
         pair(
-            delimited(/* start */ tag(LEFT_BRACKET), /* output */ is_not(RIGHT_BRACKET), /* end */ tag(RIGHT_BRACKET)),
-            delimited(/* start */ tag(LEFT_PAREN), /* output */ is_not(RIGHT_PAREN), /* end */ tag(RIGHT_PAREN)),
+            delimited(/* start */ tag(LEFT_BRACKET), /* output */ is_not("]\n"), /* end */ tag(RIGHT_BRACKET)),
+            delimited(/* start */ tag(LEFT_PAREN), /* output */ is_not(")\n"), /* end */ tag(RIGHT_PAREN)),
         )(input)
     }
 
     pub fn parse_element_image(input: &str) -> IResult<&str, (&str, &str)> {
         pair(
-            delimited(/* start */ tag(LEFT_IMG), /* output */ is_not(RIGHT_IMG), /* end */ tag(RIGHT_IMG)),
-            delimited(/* start */ tag(LEFT_PAREN), /* output */ is_not(RIGHT_PAREN), /* end */ tag(RIGHT_PAREN)),
+            delimited(/* start */ tag(LEFT_IMG), /* output */ is_not("]\n"), /* end */ tag(RIGHT_IMG)),
+            delimited(/* start */ tag(LEFT_PAREN), /* output */ is_not(")\n"), /* end */ tag(RIGHT_PAREN)),
+        )(input)
+    }
+
+    /// Matches `<scheme:rest>`, eg: `<https://r3bl.com>` or `<mailto:foo@bar.com>`. Outputs the
+    /// URL w/out the angle brackets.
+    pub fn parse_element_autolink(input: &str) -> IResult<&str, &str> {
+        delimited(
+            /* start */ tag(LEFT_ANGLE),
+            /* output */ recognize(tuple((alpha1, char(':'), is_not(" <>\n")))),
+            /* end */ tag(RIGHT_ANGLE),
+        )(input)
+    }
+
+    /// Matches a backslash followed by an ASCII punctuation char, eg: `\*`. Outputs the char.
+    pub fn parse_element_escape(input: &str) -> IResult<&str, &str> {
+        preceded(
+            /* prefix - discarded */ tag(BACKSLASH),
+            /* output */ recognize(satisfy(|it| it.is_ascii_punctuation())),
+        )(input)
+    }
+
+    /// Matches two or more spaces, or a single backslash, right before the end of the line. The
+    /// EOL itself is not consumed. Outputs the matched chars.
+    pub fn parse_element_hard_line_break(input: &str) -> IResult<&str, &str> {
+        terminated(
+            /* output */ alt((
+                recognize(pair(tag(HARD_LINE_BREAK_SPACES), space0)),
+                tag(BACKSLASH),
+            )),
+            /* ends with (not consumed) */ peek(tag(NEW_LINE)),
         )(input)
     }
 
@@ -68,45 +132,103 @@ pub mod no_rustfmt_block {
     /// special tags, but since we have no tools available to match and consume in the negative case
     /// (without regex) we need to match against our (start) tags, then consume one char; we repeat
     /// this until we run into one of our special characters (start tags) then we return this slice.
+    ///
+    /// The start tags of strikethrough, autolink, escape & hard line break show up in plain text
+    /// all the time, so only when one of them is found is its whole element tried, and if that
+    /// doesn't match then the start char is plain text. A run of spaces is consumed in one go,
+    /// since trying a hard line break at each of its spaces would be quadratic.
     pub fn parse_element_plaintext(input: &str) -> IResult<&str, &str> {
         recognize(
             many1(
-                preceded(
-                    /* prefix - discarded */
-                    not(
-                        /* starts with special characters */
-                        alt((
-                            tag(BITALIC_1),
-                            tag(BITALIC_2),
-                            tag(BOLD_1),
-                            tag(BOLD_2),
-                            tag(ITALIC_1),
-                            tag(ITALIC_2),
-                            tag(BACKTICK),
-                            tag(LEFT_BRACKET),
-                            tag(LEFT_IMG),
-                            tag(NEW_LINE),
-                        ))
+                alt((
+                    /* spaces that don't end the line, ie: that aren't a hard line break */
+                    recognize(terminated(space1, not(tag(NEW_LINE)))),
+                    /* a start tag that doesn't start its element */
+                    recognize(
+                        preceded(
+                            /* prefix - discarded */
+                            tuple((
+                                peek(
+                                    alt((
+                                        tag(STRIKETHROUGH),
+                                        tag(LEFT_ANGLE),
+                                        tag(BACKSLASH),
+                                        tag(HARD_LINE_BREAK_SPACES),
+                                    ))
+                                ),
+                                not(
+                                    alt((
+                                        recognize(parse_element_strikethrough),
+                                        recognize(parse_element_autolink),
+                                        recognize(parse_element_escape),
+                                        recognize(parse_element_hard_line_break),
+                                    ))
+                                ),
+                            )),
+                            /* output - keep char */
+                            anychar,
+                        )
+                    ),
+                    /* any other char that doesn't start a special element */
+                    recognize(
+                        preceded(
+                            /* prefix - discarded */
+                            not(
+                                /* starts with special characters */
+                                alt((
+                                    tag(BITALIC_1),
+                                    tag(BITALIC_2),
+                                    tag(BOLD_1),
+                                    tag(BOLD_2),
+                                    tag(ITALIC_1),
+                                    tag(ITALIC_2),
+                                    tag(BACKTICK),
+                                    tag(LEFT_BRACKET),
+                                    tag(LEFT_IMG),
+                                    tag(NEW_LINE),
+                                    tag(STRIKETHROUGH),
+                                    tag(LEFT_ANGLE),
+                                    tag(BACKSLASH),
+                                    tag(HARD_LINE_BREAK_SPACES),
+                                ))
+                            ),
+                            /* output - keep char */
+                            anychar,
+                        )
                     ),
-                    /* output - keep char */
-                    anychar,
-                )
+                ))
             )
         )(input)
     }
 
-    /// Parse a single chunk of markdown text [MarkdownInlineElement] in a single line.
-    pub fn parse_element_markdown_inline(input: &str) -> IResult<&str, Fragment> {
+    /// Parse a single chunk of markdown text [MarkdownInlineElement] in a single line. The content
+    /// of emphasis elements (bold, italic, etc) is parsed recursively into [Fragments].
+    pub fn parse_element_markdown_inline(input: &str) -> IResult<&str, Fragment<'_>> {
         alt((
-            map(parse_element_italic,       Fragment::Italic),
-            map(parse_element_bold,         Fragment::Bold),
-            map(parse_element_bold_italic,  Fragment::BoldItalic),
-            map(parse_element_code,         Fragment::InlineCode),
-            map(parse_element_image,        Fragment::Image),
-            map(parse_element_link,         Fragment::Link),
-            map(parse_element_plaintext,    Fragment::Plain),
+            map(map_parser(parse_element_bold_italic,   parse_element_markdown_inline_nested), Fragment::BoldItalic),
+            map(map_parser(parse_element_bold,          parse_element_markdown_inline_nested), Fragment::Bold),
+            map(map_parser(parse_element_italic,        parse_element_markdown_inline_nested), Fragment::Italic),
+            map(map_parser(parse_element_strikethrough, parse_element_markdown_inline_nested), Fragment::Strikethrough),
+            map(parse_element_code,             Fragment::InlineCode),
+            map(parse_element_image,            Fragment::Image),
+            map(parse_element_link,             Fragment::Link),
+            map(parse_element_autolink,         Fragment::Autolink),
+            map(parse_element_escape,           Fragment::Escaped),
+            map(parse_element_hard_line_break,  Fragment::LineBreak),
+            map(parse_element_plaintext,        Fragment::Plain),
         ))(input)
     }
+
+    /// Parse the content of an emphasis element. Unlike a top level line, any special char that
+    /// doesn't start a nested element (eg: a lone `*` inside of `**`) is kept as plain text.
+    pub fn parse_element_markdown_inline_nested(input: &str) -> IResult<&str, Fragments<'_>> {
+        many0(
+            alt((
+                parse_element_markdown_inline,
+                map(recognize(anychar), Fragment::Plain),
+            ))
+        )(input)
+    }
 }
 pub use no_rustfmt_block::*;
 
@@ -352,6 +474,13 @@ mod tests {
             parse_element_link("[title](https://www.example.com)"),
             Ok(("", ("title", "https://www.example.com")))
         );
+        assert_eq!(
+            parse_element_link("[title](https://\n)"),
+            Err(NomErr::Error(Error {
+                input: "\n)",
+                code: ErrorKind::Tag
+            }))
+        );
         assert_eq!(
             parse_element_code(""),
             Err(NomErr::Error(Error {
@@ -480,17 +609,202 @@ mod tests {
                 code: ErrorKind::Eof
             }))
         );
+        assert_eq!(
+            parse_element_plaintext("a ~ <b \\c  d~~e"),
+            Ok(("", "a ~ <b \\c  d~~e"))
+        );
+        assert_eq!(
+            parse_element_plaintext("a  ~~strike~~"),
+            Ok(("~~strike~~", "a  "))
+        );
+        assert_eq!(
+            parse_element_plaintext("a \n"),
+            Ok(("\n", "a "))
+        );
+        assert_eq!(
+            parse_element_plaintext("a   \n"),
+            Ok(("   \n", "a"))
+        );
+    }
+
+    /// Each space used to re-run the hard line break parser over the rest of the run, which made
+    /// a long run of spaces quadratic.
+    #[test]
+    fn test_parse_element_plaintext_long_run_of_spaces() {
+        let spaces = " ".repeat(100_000);
+
+        let input = format!("a{spaces}b\n");
+        let expected = format!("a{spaces}b");
+        assert_eq!(
+            parse_element_plaintext(&input),
+            Ok(("\n", expected.as_str()))
+        );
+
+        let input = format!("a{spaces}\n");
+        assert_eq!(
+            parse_element_plaintext(&input),
+            Ok((&input[1..], "a"))
+        );
+    }
+
+    #[test]
+    fn test_parse_element_strikethrough() {
+        assert_eq!(
+            parse_element_strikethrough("~~here is strike~~"),
+            Ok(("", "here is strike"))
+        );
+        assert_eq!(
+            parse_element_strikethrough("~~strike `~~` code~~ after"),
+            Ok((" after", "strike `~~` code"))
+        );
+        assert_eq!(
+            parse_element_strikethrough("~~here is strike"),
+            Err(NomErr::Error(Error {
+                input: "",
+                code: ErrorKind::Tag
+            }))
+        );
+        assert_eq!(
+            parse_element_strikethrough("~~strike\n~~"),
+            Err(NomErr::Error(Error {
+                input: "\n~~",
+                code: ErrorKind::Tag
+            }))
+        );
+        assert_eq!(
+            parse_element_strikethrough("~here is strike~"),
+            Err(NomErr::Error(Error {
+                input: "~here is strike~",
+                code: ErrorKind::Tag
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_element_autolink() {
+        assert_eq!(
+            parse_element_autolink("<https://r3bl.com>"),
+            Ok(("", "https://r3bl.com"))
+        );
+        assert_eq!(
+            parse_element_autolink("<mailto:foo@bar.com> after"),
+            Ok((" after", "mailto:foo@bar.com"))
+        );
+        assert_eq!(
+            parse_element_autolink("<not a link>"),
+            Err(NomErr::Error(Error {
+                input: " a link>",
+                code: ErrorKind::Char
+            }))
+        );
+        assert_eq!(
+            parse_element_autolink("<https://r3bl.com"),
+            Err(NomErr::Error(Error {
+                input: "",
+                code: ErrorKind::Tag
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_element_escape() {
+        assert_eq!(parse_element_escape("\\*literal"), Ok(("literal", "*")));
+        assert_eq!(parse_element_escape("\\\\"), Ok(("", "\\")));
+        assert_eq!(
+            parse_element_escape("\\a"),
+            Err(NomErr::Error(Error {
+                input: "a",
+                code: ErrorKind::Satisfy
+            }))
+        );
+        assert_eq!(
+            parse_element_escape("*"),
+            Err(NomErr::Error(Error {
+                input: "*",
+                code: ErrorKind::Tag
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_element_hard_line_break() {
+        assert_eq!(parse_element_hard_line_break("  \n"), Ok(("\n", "  ")));
+        assert_eq!(parse_element_hard_line_break("    \n"), Ok(("\n", "    ")));
+        assert_eq!(parse_element_hard_line_break("\\\n"), Ok(("\n", "\\")));
+        assert_eq!(
+            parse_element_hard_line_break(" \n"),
+            Err(NomErr::Error(Error {
+                input: " \n",
+                code: ErrorKind::Tag
+            }))
+        );
+        assert_eq!(
+            parse_element_hard_line_break("  foo\n"),
+            Err(NomErr::Error(Error {
+                input: "foo\n",
+                code: ErrorKind::Tag
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_element_nested_emphasis() {
+        assert_eq!(
+            parse_element_markdown_inline("**bold with `code` inside**"),
+            Ok((
+                "",
+                Fragment::Bold(vec![
+                    Fragment::Plain("bold with "),
+                    Fragment::InlineCode("code"),
+                    Fragment::Plain(" inside"),
+                ])
+            ))
+        );
+        assert_eq!(
+            parse_element_markdown_inline("*italic **bold** ~~strike~~*"),
+            Ok((
+                "",
+                Fragment::Italic(vec![
+                    Fragment::Plain("italic "),
+                    Fragment::Bold(vec![Fragment::Plain("bold")]),
+                    Fragment::Plain(" "),
+                    Fragment::Strikethrough(vec![Fragment::Plain("strike")]),
+                ])
+            ))
+        );
+        assert_eq!(
+            parse_element_markdown_inline("**a \\** b**"),
+            Ok((
+                "",
+                Fragment::Bold(vec![
+                    Fragment::Plain("a "),
+                    Fragment::Escaped("*"),
+                    Fragment::Plain("*"),
+                    Fragment::Plain(" b"),
+                ])
+            ))
+        );
+        assert_eq!(
+            parse_element_markdown_inline("***bold italic***"),
+            Ok((
+                "",
+                Fragment::BoldItalic(vec![Fragment::Plain("bold italic")])
+            ))
+        );
     }
 
     #[test]
     fn test_parse_element_markdown_inline() {
         assert_eq!(
             parse_element_markdown_inline("*here is italic*"),
-            Ok(("", Fragment::Italic("here is italic")))
+            Ok((
+                "",
+                Fragment::Italic(vec![Fragment::Plain("here is italic")])
+            ))
         );
         assert_eq!(
             parse_element_markdown_inline("**here is bold**"),
-            Ok(("", Fragment::Bold("here is bold")))
+            Ok(("", Fragment::Bold(vec![Fragment::Plain("here is bold")])))
         );
         assert_eq!(
             parse_element_markdown_inline("`here is code`"),
@@ -504,6 +818,25 @@ mod tests {
             parse_element_markdown_inline("![alt text](image.jpg)"),
             Ok(("", (Fragment::Image(("alt text", "image.jpg")))))
         );
+        assert_eq!(
+            parse_element_markdown_inline("~~here is strike~~"),
+            Ok((
+                "",
+                Fragment::Strikethrough(vec![Fragment::Plain("here is strike")])
+            ))
+        );
+        assert_eq!(
+            parse_element_markdown_inline("<https://r3bl.com>"),
+            Ok(("", Fragment::Autolink("https://r3bl.com")))
+        );
+        assert_eq!(
+            parse_element_markdown_inline("\\*not italic\\*"),
+            Ok(("not italic\\*", Fragment::Escaped("*")))
+        );
+        assert_eq!(
+            parse_element_markdown_inline("a ~ b < c \\ d ~~ e\n"),
+            Ok(("\n", Fragment::Plain("a ~ b < c \\ d ~~ e")))
+        );
         assert_eq!(
             parse_element_markdown_inline("here is plaintext!"),
             Ok(("", Fragment::Plain("here is plaintext!")))
@@ -21,9 +21,10 @@ pub mod no_rustfmt_block {
     use crate::*;
     use constants::*;
     use nom::{
-        branch::*, bytes::complete::*, character::complete::*, combinator::*, multi::*, sequence::*,
-        IResult,
+        branch::*, bytes::complete::*, character::complete::*, combinator::*, multi::*,
+        number::complete::recognize_float, sequence::*, IResult,
     };
+    use std::borrow::Cow;
 
     /// Parse input: `@title: "Something"\n` or `@title: Something\n`.
     pub fn parse_title(input: &str) -> IResult<&str, &str> {
@@ -123,6 +124,283 @@ pub mod no_rustfmt_block {
             Ok((input, output_trimmed_unquoted))
         }
     }
+
+    /// Parse input: `@key: value\n` lines, eg: `@authors: [Foo, Bar]\n` or `@date: 2023-01-01\n`.
+    /// `@title` & `@tags` lines are left for [parse_title] & [parse_tags] to handle.
+    pub fn parse_block_metadata(input: &str) -> IResult<&str, Metadata<'_>> {
+        map(
+            many1(parse_at_key_value),
+            |entries| Metadata { syntax: MetadataSyntax::AtKey, map: collect_first_wins(entries) },
+        )(input)
+    }
+
+    /// When a key shows up more than once in the same block, the first one wins (just like it
+    /// does across blocks, see [DocumentMetadataExt]).
+    fn collect_first_wins<'a>(
+        entries: impl IntoIterator<Item = (Cow<'a, str>, MetadataValue<'a>)>,
+    ) -> MetadataMap<'a> {
+        let mut map = MetadataMap::new();
+        for (key, value) in entries {
+            map.entry(key).or_insert(value);
+        }
+        map
+    }
+
+    /// Parse input: `@key: value\n`.
+    pub fn parse_at_key_value(input: &str) -> IResult<&str, (Cow<'_, str>, MetadataValue<'_>)> {
+        let (input, key) = verify(
+            /* prefix (discarded) */ preceded(tag(AT), parse_metadata_key),
+            /* must not be */ |it: &str| it != TITLE_KEY && it != TAGS_KEY,
+        )(input)?;
+        let (input, value) = delimited(
+            /* start */ pair(tag(COLON), space0),
+            /* output */ parse_metadata_value,
+            /* end */ tag(NEW_LINE),
+        )(input)?;
+        Ok((input, (Cow::Borrowed(key), value)))
+    }
+
+    /// Parse input: `authors`, `created-at`, or `draft_2`.
+    pub fn parse_metadata_key(input: &str) -> IResult<&str, &str> {
+        recognize(
+            pair(
+                alpha1,
+                many0(alt((alphanumeric1, tag("_"), tag("-")))),
+            )
+        )(input)
+    }
+
+    /// Parse the rest of the line as a list (`[a, "b"]`), a quoted string (`"a"` or `'a'`), or
+    /// else as plain text, which is trimmed.
+    pub fn parse_metadata_value(input: &str) -> IResult<&str, MetadataValue<'_>> {
+        map_parser(
+            not_line_ending,
+            alt((
+                all_consuming(map(terminated(parse_metadata_list, space0), MetadataValue::List)),
+                all_consuming(map(terminated(parse_metadata_quoted, space0), MetadataValue::Text)),
+                map(rest, |it: &str| MetadataValue::Text(Cow::Borrowed(it.trim()))),
+            )),
+        )(input)
+    }
+
+    /// Parse input: `[a, "b, c", d]`.
+    pub fn parse_metadata_list(input: &str) -> IResult<&str, Vec<Cow<'_, str>>> {
+        delimited(
+            /* start */ pair(tag(LEFT_BRACKET), space0),
+            /* output */ separated_list0(
+                /* separator */ tuple((space0, tag(COMMA), space0)),
+                alt((
+                    parse_metadata_quoted,
+                    map(is_not(",]\n"), |it: &str| Cow::Borrowed(it.trim())),
+                )),
+            ),
+            /* end */ pair(space0, tag(RIGHT_BRACKET)),
+        )(input)
+    }
+
+    /// Parse input: `"a \"b\""` (w/ JSON escapes), or `'a "b"'` (w/out any escapes).
+    pub fn parse_metadata_quoted(input: &str) -> IResult<&str, Cow<'_, str>> {
+        alt((
+            parse_json_string,
+            map(
+                delimited(
+                    /* start */ tag(SINGLE_QUOTE),
+                    /* output */ take_till(|it| it == '\'' || it == '\n'),
+                    /* end */ tag(SINGLE_QUOTE),
+                ),
+                Cow::Borrowed,
+            ),
+        ))(input)
+    }
+
+    /// Parse input: `"a \"b\" \u00e9"`. Only allocates if there are escapes to replace.
+    pub fn parse_json_string(input: &str) -> IResult<&str, Cow<'_, str>> {
+        map(
+            delimited(
+                /* start */ tag(QUOTE),
+                /* output */ recognize(many0(alt((
+                    is_not("\"\\\n"),
+                    recognize(pair(tag(BACKSLASH), anychar)),
+                )))),
+                /* end */ tag(QUOTE),
+            ),
+            unescape_json,
+        )(input)
+    }
+
+    fn unescape_json(input: &str) -> Cow<'_, str> {
+        if !input.contains(BACKSLASH) {
+            return Cow::Borrowed(input);
+        }
+        let mut acc = String::with_capacity(input.len());
+        let mut chars = input.chars();
+        while let Some(it) = chars.next() {
+            if it != '\\' {
+                acc.push(it);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => acc.push('\n'),
+                Some('t') => acc.push('\t'),
+                Some('r') => acc.push('\r'),
+                Some('b') => acc.push('\u{8}'),
+                Some('f') => acc.push('\u{c}'),
+                Some('u') => {
+                    let hex = chars.by_ref().take(4).collect::<String>();
+                    let it = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                    acc.push(it.unwrap_or(char::REPLACEMENT_CHARACTER));
+                }
+                // Eg: `\"`, `\\`, and `\/`.
+                Some(it) => acc.push(it),
+                None => {}
+            }
+        }
+        Cow::Owned(acc)
+    }
+
+    /// Front matter has to be at the very start of a document, so [root_parse_markdown] runs
+    /// this before any other parser.
+    pub fn parse_front_matter(input: &str) -> IResult<&str, Metadata<'_>> {
+        alt((
+            parse_front_matter_yaml,
+            parse_front_matter_json,
+        ))(input)
+    }
+
+    /// Parse input:
+    /// ```text
+    /// ---
+    /// title: Something
+    /// authors: [Foo, "Bar"]
+    /// # Comments & blank lines are skipped.
+    /// tags:
+    ///   - tag1
+    ///   - tag2
+    /// ---
+    /// ```
+    /// Nested maps are not supported. There has to be at least one key, since `---\n---\n` is
+    /// just as likely to be two horizontal rules.
+    pub fn parse_front_matter_yaml(input: &str) -> IResult<&str, Metadata<'_>> {
+        let (input, entries) = delimited(
+            /* start */ parse_front_matter_delimiter(FRONT_MATTER_YAML),
+            /* output */ many0(alt((
+                map(parse_yaml_blank_or_comment_line, |_| None),
+                map(parse_yaml_entry,                 Some),
+            ))),
+            /* end */ parse_front_matter_delimiter(FRONT_MATTER_YAML),
+        )(input)?;
+        let map = collect_first_wins(entries.into_iter().flatten());
+        let (_, map) = verify(success(map), |it: &MetadataMap<'_>| !it.is_empty())(input)?;
+        Ok((input, Metadata { syntax: MetadataSyntax::Yaml, map }))
+    }
+
+    /// Parse input: `key: value\n`, or `key:\n` followed by `  - item\n` lines.
+    pub fn parse_yaml_entry(input: &str) -> IResult<&str, (Cow<'_, str>, MetadataValue<'_>)> {
+        let (input, key) = terminated(parse_metadata_key, tag(COLON))(input)?;
+        let (input, value) = alt((
+            map(
+                preceded(pair(space0, tag(NEW_LINE)), many1(parse_yaml_list_item)),
+                MetadataValue::List,
+            ),
+            delimited(space0, parse_metadata_value, tag(NEW_LINE)),
+        ))(input)?;
+        Ok((input, (Cow::Borrowed(key), value)))
+    }
+
+    /// Parse input: `  - item\n`.
+    pub fn parse_yaml_list_item(input: &str) -> IResult<&str, Cow<'_, str>> {
+        delimited(
+            /* start */ tuple((space0, tag(UNORDERED_LIST), space1)),
+            /* output */ map_parser(
+                not_line_ending,
+                alt((
+                    all_consuming(terminated(parse_metadata_quoted, space0)),
+                    map(rest, |it: &str| Cow::Borrowed(it.trim())),
+                )),
+            ),
+            /* end */ tag(NEW_LINE),
+        )(input)
+    }
+
+    pub fn parse_yaml_blank_or_comment_line(input: &str) -> IResult<&str, &str> {
+        recognize(
+            tuple((
+                space0,
+                opt(pair(tag(YAML_COMMENT), not_line_ending)),
+                tag(NEW_LINE),
+            ))
+        )(input)
+    }
+
+    /// Parse input:
+    /// ```text
+    /// ;;;
+    /// { "title": "Something", "authors": ["Foo", "Bar"], "draft": false }
+    /// ;;;
+    /// ```
+    /// Nested objects are not supported.
+    pub fn parse_front_matter_json(input: &str) -> IResult<&str, Metadata<'_>> {
+        let (input, entries) = delimited(
+            /* start */ parse_front_matter_delimiter(FRONT_MATTER_JSON),
+            /* output */ delimited(multispace0, parse_json_object, multispace0),
+            /* end */ parse_front_matter_delimiter(FRONT_MATTER_JSON),
+        )(input)?;
+        Ok((input, Metadata { syntax: MetadataSyntax::Json, map: collect_first_wins(entries) }))
+    }
+
+    /// Parse input: `{ "key": value, ... }`.
+    pub fn parse_json_object(input: &str) -> IResult<&str, Vec<(Cow<'_, str>, MetadataValue<'_>)>> {
+        delimited(
+            /* start */ pair(tag(LEFT_BRACE), multispace0),
+            /* output */ separated_list0(
+                /* separator */ tuple((multispace0, tag(COMMA), multispace0)),
+                separated_pair(
+                    parse_json_string,
+                    tuple((multispace0, tag(COLON), multispace0)),
+                    parse_json_value,
+                ),
+            ),
+            /* end */ pair(multispace0, tag(RIGHT_BRACE)),
+        )(input)
+    }
+
+    /// Parse input: a scalar, or an array of scalars, eg: `["Foo", 42]`.
+    pub fn parse_json_value(input: &str) -> IResult<&str, MetadataValue<'_>> {
+        alt((
+            map(parse_json_scalar, MetadataValue::Text),
+            map(
+                delimited(
+                    /* start */ pair(tag(LEFT_BRACKET), multispace0),
+                    /* output */ separated_list0(
+                        /* separator */ tuple((multispace0, tag(COMMA), multispace0)),
+                        parse_json_scalar,
+                    ),
+                    /* end */ pair(multispace0, tag(RIGHT_BRACKET)),
+                ),
+                MetadataValue::List,
+            ),
+        ))(input)
+    }
+
+    /// Parse input: a string, number, `true`, `false`, or `null`.
+    pub fn parse_json_scalar(input: &str) -> IResult<&str, Cow<'_, str>> {
+        alt((
+            parse_json_string,
+            map(recognize_float, Cow::Borrowed),
+            map(alt((tag("true"), tag("false"), tag("null"))), Cow::Borrowed),
+        ))(input)
+    }
+
+    /// The delimiter has to be on a line by itself. The closing one may be at the end of input.
+    fn parse_front_matter_delimiter<'a>(delimiter: &'static str) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str> {
+        recognize(
+            tuple((
+                tag(delimiter),
+                space0,
+                alt((tag(NEW_LINE), eof)),
+            ))
+        )
+    }
 }
 pub use no_rustfmt_block::*;
 
@@ -130,6 +408,8 @@ pub use no_rustfmt_block::*;
 mod tests {
     use super::*;
     use crate::parser_impl_metadata::tests::raw_strings::*;
+    use crate::*;
+    use nom::{error::Error, error::ErrorKind, Err as NomErr};
 
     #[test]
     fn test_parse_metadata_tags() {
@@ -149,6 +429,133 @@ mod tests {
         assert_eq!(output, Ok(("", "Some title")));
     }
 
+    #[test]
+    fn test_parse_block_metadata() {
+        assert_eq!(
+            parse_block_metadata(
+                "@authors: [Foo, \"Bar, Baz\"]\n@date: 2023-06-01  \n@draft:\n# Hi\n"
+            ),
+            Ok((
+                "# Hi\n",
+                Metadata {
+                    syntax: MetadataSyntax::AtKey,
+                    map: [
+                        (
+                            "authors".into(),
+                            MetadataValue::List(vec!["Foo".into(), "Bar, Baz".into()])
+                        ),
+                        ("date".into(), MetadataValue::Text("2023-06-01".into())),
+                        ("draft".into(), MetadataValue::Text("".into())),
+                    ]
+                    .into_iter()
+                    .collect(),
+                }
+            ))
+        );
+        // The first duplicate key wins.
+        let (_, it) = parse_block_metadata("@date: 2023-06-01\n@date: 2024-01-01\n").unwrap();
+        assert_eq!(
+            it.map.get("date"),
+            Some(&MetadataValue::Text("2023-06-01".into()))
+        );
+        let (_, it) = parse_front_matter("---\ndate: a\ndate: b\n---\n").unwrap();
+        assert_eq!(it.map.get("date"), Some(&MetadataValue::Text("a".into())));
+        let (_, it) = parse_front_matter(";;;\n{\"date\": \"a\", \"date\": \"b\"}\n;;;\n").unwrap();
+        assert_eq!(it.map.get("date"), Some(&MetadataValue::Text("a".into())));
+
+        // `@title` & `@tags` have their own blocks.
+        assert_eq!(
+            parse_block_metadata(TITLE_STRING_1),
+            Err(NomErr::Error(Error {
+                input: TITLE_STRING_1,
+                code: ErrorKind::Verify
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_metadata_value() {
+        assert_eq!(
+            parse_metadata_value("[a, 'b', \"c\"] \n"),
+            Ok((
+                "\n",
+                MetadataValue::List(vec!["a".into(), "b".into(), "c".into()])
+            ))
+        );
+        assert_eq!(
+            parse_metadata_value("[]"),
+            Ok(("", MetadataValue::List(vec![])))
+        );
+        assert_eq!(
+            parse_metadata_value("\"a\\\"b\\u00e9\""),
+            Ok(("", MetadataValue::Text("a\"bé".into())))
+        );
+        // Not a list, since there is more text after it.
+        assert_eq!(
+            parse_metadata_value("[a] b"),
+            Ok(("", MetadataValue::Text("[a] b".into())))
+        );
+    }
+
+    #[test]
+    fn test_parse_front_matter_yaml() {
+        let it = parse_front_matter(YAML_FRONT_MATTER);
+        assert_eq!(
+            it,
+            Ok((
+                "# Hi\n",
+                Metadata {
+                    syntax: MetadataSyntax::Yaml,
+                    map: [
+                        ("title".into(), MetadataValue::Text("Something".into())),
+                        (
+                            "authors".into(),
+                            MetadataValue::List(vec!["Foo".into(), "Bar".into()])
+                        ),
+                        (
+                            "tags".into(),
+                            MetadataValue::List(vec!["tag1".into(), "tag 2".into()])
+                        ),
+                        ("draft".into(), MetadataValue::Text("false".into())),
+                    ]
+                    .into_iter()
+                    .collect(),
+                }
+            ))
+        );
+        // Two horizontal rules are not front matter.
+        assert!(parse_front_matter("---\n---\n").is_err());
+        // Neither is a front matter that is never closed.
+        assert!(parse_front_matter("---\ntitle: foo\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_front_matter_json() {
+        let it = parse_front_matter(JSON_FRONT_MATTER);
+        assert_eq!(
+            it,
+            Ok((
+                "",
+                Metadata {
+                    syntax: MetadataSyntax::Json,
+                    map: [
+                        ("title".into(), MetadataValue::Text("Some \"title\"".into())),
+                        (
+                            "authors".into(),
+                            MetadataValue::List(vec!["Foo".into(), "Bar".into()])
+                        ),
+                        ("count".into(), MetadataValue::Text("-4.2e1".into())),
+                        ("draft".into(), MetadataValue::Text("true".into())),
+                    ]
+                    .into_iter()
+                    .collect(),
+                }
+            ))
+        );
+        // Nested objects are not supported.
+        assert!(parse_front_matter(";;;\n{\"a\": {\"b\": 1}}\n;;;\n").is_err());
+    }
+
     #[rustfmt::skip]
     mod raw_strings {
 pub const TITLE_STRING_1: &str =
@@ -163,5 +570,27 @@ r#"@tags: [tag 1 ,    tag 2, tag3]
 pub const TAG_STRING_2: &str =
 r#"@tags: ["tag 1 ", "  tag 2", "tag 3"]
 "#;
+pub const YAML_FRONT_MATTER: &str =
+r#"---
+title: Something
+authors: [Foo, "Bar"]
+
+# A comment.
+tags:
+  - tag1
+  - "tag 2"
+draft: false
+---
+# Hi
+"#;
+pub const JSON_FRONT_MATTER: &str =
+r#";;;
+{
+  "title": "Some \"title\"",
+  "authors": ["Foo", "Bar"],
+  "count": -4.2e1,
+  "draft": true
+}
+;;;"#;
     }
 }
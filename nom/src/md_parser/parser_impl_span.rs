@@ -0,0 +1,393 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Finds the [SpanTree]s of the nodes in a parsed [Block]. The source of the block is walked
+//! again w/ the same parsers that produced it, and each position is counted from how much
+//! input those parsers consume. So the spans don't depend on the string slices in the block,
+//! or on the width of any delimiter.
+
+use crate::*;
+use constants::*;
+use nom::{
+    bytes::complete::tag,
+    character::complete::anychar,
+    combinator::{all_consuming, consumed, recognize},
+    multi::many0,
+    IResult, Offset,
+};
+
+/// How the inline parsers were run to get the [Fragments] of a line.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Inline {
+    /// [parse_block_markdown_text_until_eol], which stops at the first char that isn't part of
+    /// a [Fragment].
+    Strict,
+    /// [parse_element_markdown_inline_nested], which keeps any such char as plain text.
+    Lenient,
+    /// Same as [Inline::Lenient], and then adjacent [Fragment::Plain]s are joined, like the
+    /// recovering parser does for a line that it can't parse.
+    Recovered,
+}
+
+/// Returns the children of the [Spanned] `block`, which was parsed from the source of
+/// `source_map` at `offset`. Pass `is_recovered` if the block was made by the recovering
+/// parser (since it parses a line differently).
+pub fn parse_block_span_trees(
+    source_map: &SourceMap<'_>,
+    offset: usize,
+    block: &Block<'_>,
+    is_recovered: bool,
+) -> Vec<SpanTree> {
+    SpanWalker { source_map }.block(offset, block, is_recovered)
+}
+
+struct SpanWalker<'m, 'a> {
+    source_map: &'m SourceMap<'a>,
+}
+
+impl<'a> SpanWalker<'_, 'a> {
+    fn node(&self, start: usize, len: usize, children: Vec<SpanTree>) -> SpanTree {
+        SpanTree::new(self.source_map.span(start..start + len), children)
+    }
+
+    fn block(&self, start: usize, block: &Block<'_>, is_recovered: bool) -> Vec<SpanTree> {
+        let input = &self.source_map.source()[start..];
+        match block {
+            Block::Text(_) if is_recovered => {
+                let line = input.split(NEW_LINE).next().unwrap_or_default();
+                self.fragments(start, line, Inline::Recovered).0
+            }
+            Block::Text(_) => self.fragments(start, input, Inline::Strict).0,
+            Block::Heading(_) => match parse_heading_tag(input) {
+                Ok((rest, _)) => {
+                    let content_start = start + input.len() - rest.len();
+                    self.fragments(content_start, rest, Inline::Strict).0
+                }
+                Err(_) => vec![],
+            },
+            Block::UnorderedList(lines) => {
+                self.lines(start, input, lines.len(), parse_unordered_list_tag)
+            }
+            Block::OrderedList(lines) => {
+                self.lines(start, input, lines.len(), parse_ordered_list_tag)
+            }
+            Block::BlockQuote(lines) => {
+                self.lines(start, input, lines.len(), parse_block_quote_tag)
+            }
+            Block::NestedList(list) => self.list(start, input, list, 0).0,
+            Block::Table(table) => self.table(start, input, table),
+            Block::HorizontalRule
+            | Block::CodeBlock(_)
+            | Block::Title(_)
+            | Block::Tags(_)
+            | Block::Metadata(_) => vec![],
+        }
+    }
+
+    /// Returns a node for each fragment at the start of `input` (which is at `start` in the
+    /// source), along w/ the rest of `input`.
+    fn fragments(&self, start: usize, input: &'a str, inline: Inline) -> (Vec<SpanTree>, &'a str) {
+        let mut nodes: Vec<SpanTree> = vec![];
+        let mut is_last_plain = false;
+        let mut rest = input;
+        loop {
+            let fragment_start = start + input.len() - rest.len();
+            let (next, whole, children, is_plain) =
+                match consumed(parse_element_markdown_inline)(rest) {
+                    Ok((next, (whole, fragment))) => {
+                        let children = self.emphasis_children(fragment_start, whole, &fragment);
+                        let is_plain = matches!(fragment, Fragment::Plain(_));
+                        (next, whole, children, is_plain)
+                    }
+                    Err(_) if inline != Inline::Strict && !rest.is_empty() => {
+                        match recognize(anychar::<_, nom::error::Error<_>>)(rest) {
+                            Ok((next, whole)) => (next, whole, vec![], true),
+                            Err(_) => break,
+                        }
+                    }
+                    Err(_) => break,
+                };
+            match nodes.last_mut() {
+                Some(last) if inline == Inline::Recovered && is_plain && is_last_plain => {
+                    last.span = self
+                        .source_map
+                        .span(last.span.start..fragment_start + whole.len());
+                }
+                _ => nodes.push(self.node(fragment_start, whole.len(), children)),
+            }
+            is_last_plain = is_plain;
+            rest = next;
+        }
+        (nodes, rest)
+    }
+
+    /// The emphasis fragments (eg: [Fragment::Bold]) are parsed again, to find out where their
+    /// content starts in `whole` (which is at `start` in the source).
+    fn emphasis_children(
+        &self,
+        start: usize,
+        whole: &'a str,
+        fragment: &Fragment<'_>,
+    ) -> Vec<SpanTree> {
+        let content = match fragment {
+            Fragment::BoldItalic(_) => parse_element_bold_italic(whole),
+            Fragment::Bold(_) => parse_element_bold(whole),
+            Fragment::Italic(_) => parse_element_italic(whole),
+            Fragment::Strikethrough(_) => parse_element_strikethrough(whole),
+            _ => return vec![],
+        };
+        match content {
+            Ok((_, content)) => {
+                self.fragments(start + whole.offset(content), content, Inline::Lenient)
+                    .0
+            }
+            Err(_) => vec![],
+        }
+    }
+
+    /// Returns a node for the line of text at the start of `input` (which is at `start` in the
+    /// source), w/out its new line, along w/ the rest of `input` after the new line.
+    fn line(&self, start: usize, input: &'a str) -> (SpanTree, &'a str) {
+        let (children, rest) = self.fragments(start, input, Inline::Strict);
+        let node = self.node(start, input.len() - rest.len(), children);
+        let rest = tag::<_, _, nom::error::Error<_>>(NEW_LINE)(rest).map_or(rest, |(it, _)| it);
+        (node, rest)
+    }
+
+    /// A node for each of the `count` lines that start w/ a tag that `parse_tag` matches.
+    fn lines<O>(
+        &self,
+        start: usize,
+        input: &'a str,
+        count: usize,
+        parse_tag: fn(&'a str) -> IResult<&'a str, O>,
+    ) -> Vec<SpanTree> {
+        let mut nodes = vec![];
+        let mut rest = input;
+        for _ in 0..count {
+            let Ok((content, _)) = parse_tag(rest) else {
+                break;
+            };
+            let (node, next) = self.line(start + input.len() - content.len(), content);
+            nodes.push(node);
+            rest = next;
+        }
+        nodes
+    }
+
+    /// Returns a node for each item of `list` (which is at `indent`), along w/ the rest of
+    /// `input` after the list.
+    fn list(
+        &self,
+        start: usize,
+        input: &'a str,
+        list: &List<'_>,
+        indent: usize,
+    ) -> (Vec<SpanTree>, &'a str) {
+        let mut nodes = vec![];
+        let mut rest = input;
+        for item in &list.items {
+            // The item starts at its tag, after the indent.
+            let item_start = start + input.len() - rest.trim_start_matches(' ').len();
+            let Ok((content, _)) = parse_nested_list_tag(rest, indent) else {
+                break;
+            };
+            let (content_node, next) = self.line(start + input.len() - content.len(), content);
+            rest = next;
+            let mut children = vec![content_node];
+            if let Some(sub_list) = &item.children {
+                let sub_indent = rest.len() - rest.trim_start_matches(' ').len();
+                let rest_start = start + input.len() - rest.len();
+                let (items, next) = self.list(rest_start, rest, sub_list, sub_indent);
+                if let (Some(first), Some(last)) = (items.first(), items.last()) {
+                    let (sub_list_start, len) =
+                        (first.span.start, last.span.end - first.span.start);
+                    children.push(self.node(sub_list_start, len, items));
+                }
+                rest = next;
+            }
+            let len = children.last().map_or(0, |it| it.span.end - item_start);
+            nodes.push(self.node(item_start, len, children));
+        }
+        (nodes, rest)
+    }
+
+    /// A node for the header row & each body row of `table`. The delimiter row is skipped.
+    fn table(&self, start: usize, input: &'a str, table: &Table<'_>) -> Vec<SpanTree> {
+        let mut nodes = vec![];
+        let Some((header, rest)) = self.table_row(start, input, table.header.len()) else {
+            return nodes;
+        };
+        nodes.push(header);
+        let Ok((mut rest, _)) = parse_table_delimiter_row(rest) else {
+            return nodes;
+        };
+        for row in &table.rows {
+            let row_start = start + input.len() - rest.len();
+            let Some((node, next)) = self.table_row(row_start, rest, row.len()) else {
+                break;
+            };
+            nodes.push(node);
+            rest = next;
+        }
+        nodes
+    }
+
+    /// Returns a node for the row at the start of `input` (which is at `start` in the source)
+    /// w/ `count` cells, along w/ the rest of `input` after the row.
+    fn table_row(&self, start: usize, input: &'a str, count: usize) -> Option<(SpanTree, &'a str)> {
+        let (mut rest, _) = tag::<_, _, nom::error::Error<_>>(TABLE_PIPE)(input).ok()?;
+        let mut cells = vec![];
+        for _ in 0..count {
+            let cell_start = start + input.len() - rest.len();
+            let (next, cell) = parse_table_row_cell(rest).ok()?;
+            let trimmed = cell.trim();
+            let trimmed_start = cell_start + cell.len() - cell.trim_start().len();
+            let children = match all_consuming(many0(parse_element_markdown_inline))(trimmed) {
+                Ok(_) => self.fragments(trimmed_start, trimmed, Inline::Strict).0,
+                // Kept as a single plain text fragment, see [parse_table_cell].
+                Err(_) => vec![self.node(trimmed_start, trimmed.len(), vec![])],
+            };
+            cells.push(self.node(trimmed_start, trimmed.len(), children));
+            rest = next;
+        }
+        let row_end = start + input.len() - rest.len();
+        let (rest, _) = parse_table_row_end(rest).ok()?;
+        Some((self.node(start, row_end - start, cells), rest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The source text of each of the `trees`.
+    fn texts<'s>(source: &'s str, trees: &[SpanTree]) -> Vec<&'s str> {
+        trees.iter().map(|it| &source[it.span.range()]).collect()
+    }
+
+    /// The inputs are made from the delimiter constants, so if the width of any of them
+    /// changes, the spans must still cover the whole delimiter.
+    #[test]
+    fn test_fragment_spans_include_delimiters() {
+        for delimiter in [
+            BITALIC_1,
+            BITALIC_2,
+            BOLD_1,
+            BOLD_2,
+            ITALIC_1,
+            ITALIC_2,
+            STRIKETHROUGH,
+        ] {
+            let element = format!("{delimiter}b `c`{delimiter}");
+            let source = format!("a {element} d\n");
+            let it = root_parse_markdown_with_recovery(&source);
+            let children = &it.blocks[0].children;
+            assert_eq!(texts(&source, children), vec!["a ", &element, " d"]);
+            assert_eq!(texts(&source, &children[1].children), vec!["b ", "`c`"]);
+        }
+
+        let elements = [
+            format!("{BACKTICK}code{BACKTICK}"),
+            format!("{LEFT_BRACKET}text{RIGHT_BRACKET}{LEFT_PAREN}url{RIGHT_PAREN}"),
+            format!("{LEFT_IMG}alt{RIGHT_IMG}{LEFT_PAREN}url{RIGHT_PAREN}"),
+            format!("{LEFT_ANGLE}https:url{RIGHT_ANGLE}"),
+            format!("{BACKSLASH}*"),
+        ];
+        for element in elements {
+            let source = format!("a {element} d{HARD_LINE_BREAK_SPACES}\n");
+            let it = root_parse_markdown_with_recovery(&source);
+            assert_eq!(
+                texts(&source, &it.blocks[0].children),
+                vec!["a ", &element, " d", HARD_LINE_BREAK_SPACES]
+            );
+        }
+    }
+
+    #[test]
+    fn test_nested_fragment_spans() {
+        let source = "a **b _c_**\n";
+        let it = root_parse_markdown_with_recovery(source);
+        let bold = &it.blocks[0].children[1];
+        assert_eq!(texts(source, &bold.children), vec!["b ", "_c_"]);
+        let italic = &bold.children[1];
+        assert_eq!((italic.span.line, italic.span.column), (1, 7));
+        assert_eq!(texts(source, &italic.children), vec!["c"]);
+    }
+
+    #[test]
+    fn test_line_spans() {
+        let source = "# h *i*\n- one\n- **two**\n1. three\n> four\n>five\n";
+        let it = root_parse_markdown_with_recovery(source);
+        assert_eq!(texts(source, &it.blocks[0].children), vec!["h ", "*i*"]);
+
+        let lines = &it.blocks[1].children;
+        assert_eq!(texts(source, lines), vec!["one", "**two**"]);
+        assert_eq!(texts(source, &lines[1].children), vec!["**two**"]);
+        assert_eq!((lines[1].span.line, lines[1].span.column), (3, 3));
+
+        assert_eq!(texts(source, &it.blocks[2].children), vec!["three"]);
+        assert_eq!(texts(source, &it.blocks[3].children), vec!["four", "five"]);
+    }
+
+    #[test]
+    fn test_nested_list_spans() {
+        let source = "- one\n  1. two\n  2. *three*\n- four\n";
+        let it = root_parse_markdown_with_recovery(source);
+        let items = &it.blocks[0].children;
+        assert_eq!(
+            texts(source, items),
+            vec!["- one\n  1. two\n  2. *three*", "- four"]
+        );
+        assert_eq!(
+            texts(source, &items[0].children),
+            vec!["one", "1. two\n  2. *three*"]
+        );
+        let sub_items = &items[0].children[1].children;
+        assert_eq!(texts(source, sub_items), vec!["1. two", "2. *three*"]);
+        assert_eq!(texts(source, &sub_items[1].children), vec!["*three*"]);
+        assert_eq!(
+            texts(source, &sub_items[1].children[0].children),
+            vec!["*three*"]
+        );
+        assert_eq!(items[1].children.len(), 1);
+    }
+
+    #[test]
+    fn test_table_spans() {
+        let source = "| a | *b* |\n|---|---|\n|  c  | d *e |  \n";
+        let it = root_parse_markdown_with_recovery(source);
+        let rows = &it.blocks[0].children;
+        assert_eq!(texts(source, rows), vec!["| a | *b* |", "|  c  | d *e |"]);
+        assert_eq!(texts(source, &rows[0].children), vec!["a", "*b*"]);
+        assert_eq!(texts(source, &rows[0].children[1].children), vec!["*b*"]);
+        // A cell that can't be parsed is a single plain text fragment.
+        assert_eq!(texts(source, &rows[1].children), vec!["c", "d *e"]);
+        assert_eq!(texts(source, &rows[1].children[1].children), vec!["d *e"]);
+    }
+
+    #[test]
+    fn test_recovered_line_spans() {
+        let source = "foo *bar `baz`\n**qux";
+        let it = root_parse_markdown_with_recovery(source);
+        assert_eq!(
+            texts(source, &it.blocks[0].children),
+            vec!["foo *bar ", "`baz`"]
+        );
+        assert_eq!(texts(source, &it.blocks[1].children), vec!["**qux"]);
+    }
+}
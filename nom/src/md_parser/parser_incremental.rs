@@ -0,0 +1,463 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Incremental re-parsing for editors, which change a small part of a (potentially large)
+//! buffer on each keystroke. Instead of parsing the whole buffer again, only the blocks around
+//! an [Edit] are parsed, and the rest are reused from the previous [ParsedDocument].
+
+use crate::*;
+use std::{borrow::Cow, ops::Range};
+
+/// The bytes in `range` of the old source are replaced by `text`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Edit<'a> {
+    pub range: Range<usize>,
+    pub text: &'a str,
+}
+
+impl<'a> Edit<'a> {
+    pub fn new(range: Range<usize>, text: &'a str) -> Self {
+        Edit { range, text }
+    }
+
+    /// Returns the new source. Panics if `range` is out of bounds, or not on a char boundary.
+    pub fn apply(&self, old_source: &str) -> String {
+        let mut new_source = old_source.to_string();
+        new_source.replace_range(self.range.clone(), self.text);
+        new_source
+    }
+}
+
+/// Returns the same [ParsedDocument] as [root_parse_markdown_with_recovery] would for
+/// `new_source`, which is `old_source` w/ `edit` applied to it. `old_document` must be the
+/// result of parsing `old_source` w/ [root_parse_markdown_with_recovery] (or w/ this function).
+///
+/// Parsing starts at the block before the one that contains the edit (since a block parser
+/// looks ahead at the next line to find out where it ends), and stops as soon as a block ends
+/// after the edit at the same place that an old block started. The old blocks before & after
+/// the parsed ones are re-sliced into `new_source`. If the edit touches the front matter (or
+/// `edit` doesn't match the sources) then the whole document is parsed again.
+pub fn root_parse_markdown_incremental<'a>(
+    old_document: &ParsedDocument<'_>,
+    old_source: &str,
+    new_source: &'a str,
+    edit: &Edit<'_>,
+) -> ParsedDocument<'a> {
+    try_parse_incremental(old_document, old_source, new_source, edit)
+        .unwrap_or_else(|| root_parse_markdown_with_recovery(new_source))
+}
+
+fn try_parse_incremental<'a>(
+    old_document: &ParsedDocument<'_>,
+    old_source: &str,
+    new_source: &'a str,
+    edit: &Edit<'_>,
+) -> Option<ParsedDocument<'a>> {
+    let Range { start, end } = edit.range;
+    old_source.get(start..end)?;
+    if new_source.len() != old_source.len() - (end - start) + edit.text.len() {
+        return None;
+    }
+    let new_edit_end = start + edit.text.len();
+    let to_new_offset = |old_offset: usize| old_offset - end + new_edit_end;
+
+    // The front matter can only be reused if the edit comes after it, and the new source starts
+    // w/ the same front matter (eg: typing a closing `---` turns the start into front matter).
+    let old_front_matter_end = match old_document.blocks.first() {
+        Some(Spanned {
+            span,
+            value: Block::Metadata(Metadata { syntax, .. }),
+            ..
+        }) if *syntax != MetadataSyntax::AtKey => Some(span.end),
+        _ => None,
+    };
+    let new_front_matter_end = parse_front_matter(new_source)
+        .ok()
+        .map(|(rest, _)| new_source.len() - rest.len());
+    if old_front_matter_end != new_front_matter_end
+        || old_front_matter_end.is_some_and(|it| start < it)
+    {
+        return None;
+    }
+    let first_index = usize::from(old_front_matter_end.is_some());
+
+    // The block that contains the edit, or the last one for an edit at the end of the source.
+    let edited_index = old_document
+        .blocks
+        .iter()
+        .position(|it| start < it.span.end)
+        .unwrap_or(old_document.blocks.len().saturating_sub(1));
+    let restart_index = edited_index.saturating_sub(1).max(first_index);
+    let restart_offset = match old_document.blocks.get(restart_index) {
+        Some(it) => it.span.start,
+        None => old_source.len(),
+    };
+
+    let old_map = SourceMap::new(old_source);
+    let new_map = SourceMap::new(new_source);
+    let mut new_document = ParsedDocument {
+        blocks: vec![],
+        diagnostics: vec![],
+    };
+
+    // Everything before the restart offset is unchanged.
+    let same = Rebase {
+        old_map: &old_map,
+        new_map: &new_map,
+        to_new_offset: &|it| it,
+    };
+    for block in &old_document.blocks[..restart_index] {
+        new_document.blocks.push(same.spanned_block(block)?);
+    }
+    new_document.diagnostics.extend(
+        old_document
+            .diagnostics
+            .iter()
+            .filter(|it| it.span().start < restart_offset)
+            .cloned(),
+    );
+
+    let mut offset = restart_offset;
+    while offset < new_source.len() {
+        offset = parse_next_block(&new_map, offset, &mut new_document);
+        if offset < new_edit_end {
+            continue;
+        }
+
+        // Once a block ends where an old one starts, the rest of the old blocks are the same.
+        let old_offset = offset - new_edit_end + end;
+        let Ok(resync_index) = old_document
+            .blocks
+            .binary_search_by_key(&old_offset, |it| it.span.start)
+        else {
+            continue;
+        };
+        let shifted = Rebase {
+            old_map: &old_map,
+            new_map: &new_map,
+            to_new_offset: &to_new_offset,
+        };
+        for block in &old_document.blocks[resync_index..] {
+            new_document.blocks.push(shifted.spanned_block(block)?);
+        }
+        for diagnostic in &old_document.diagnostics {
+            if diagnostic.span().start >= old_offset {
+                let mut diagnostic = diagnostic.clone();
+                *diagnostic.span_mut() = shifted.span(diagnostic.span());
+                new_document.diagnostics.push(diagnostic);
+            }
+        }
+        break;
+    }
+
+    Some(new_document)
+}
+
+/// Moves a [Block] parsed from the old source into the new source, ie: each of its slices is
+/// replaced w/ the slice at the (shifted) same offset in the new source. Returns [None] if a
+/// slice is not from the old source.
+struct Rebase<'r, 'o, 'a> {
+    old_map: &'r SourceMap<'o>,
+    new_map: &'r SourceMap<'a>,
+    to_new_offset: &'r dyn Fn(usize) -> usize,
+}
+
+impl<'a> Rebase<'_, '_, 'a> {
+    fn span(&self, span: Span) -> Span {
+        let start = (self.to_new_offset)(span.start);
+        self.new_map.span(start..start + span.len())
+    }
+
+    fn str(&self, slice: &str) -> Option<&'a str> {
+        let start = (self.to_new_offset)(self.old_map.offset_of(slice)?);
+        self.new_map.source().get(start..start + slice.len())
+    }
+
+    /// Unescaped (owned) metadata isn't in the source, so it is copied.
+    fn cow(&self, text: &str) -> Cow<'a, str> {
+        match self.str(text) {
+            Some(it) => Cow::Borrowed(it),
+            None => Cow::Owned(text.to_string()),
+        }
+    }
+
+    fn spanned_block(&self, block: &Spanned<Block<'_>>) -> Option<Spanned<Block<'a>>> {
+        Some(
+            Spanned::new(self.span(block.span), self.block(block)?)
+                .with_children(self.span_trees(&block.children)),
+        )
+    }
+
+    fn span_trees(&self, trees: &[SpanTree]) -> Vec<SpanTree> {
+        trees
+            .iter()
+            .map(|it| SpanTree::new(self.span(it.span), self.span_trees(&it.children)))
+            .collect()
+    }
+
+    fn block(&self, block: &Block<'_>) -> Option<Block<'a>> {
+        Some(match block {
+            Block::Heading((level, text)) => Block::Heading((*level, self.fragments(text)?)),
+            Block::OrderedList(lines) => Block::OrderedList(self.lines(lines)?),
+            Block::UnorderedList(lines) => Block::UnorderedList(self.lines(lines)?),
+            Block::NestedList(list) => Block::NestedList(self.list(list)?),
+            Block::BlockQuote(lines) => Block::BlockQuote(self.lines(lines)?),
+            Block::HorizontalRule => Block::HorizontalRule,
+            Block::Table(table) => Block::Table(Table {
+                header: self.lines(&table.header)?,
+                alignments: table.alignments.clone(),
+                rows: table
+                    .rows
+                    .iter()
+                    .map(|row| self.lines(row))
+                    .collect::<Option<_>>()?,
+            }),
+            Block::Text(text) => Block::Text(self.fragments(text)?),
+            Block::CodeBlock(CodeBlock { language, text }) => Block::CodeBlock(CodeBlock {
+                // The parser uses a constant when there's no language.
+                language: match self.str(language) {
+                    Some(it) => it,
+                    None if *language == constants::UNKNOWN_LANGUAGE => constants::UNKNOWN_LANGUAGE,
+                    None => return None,
+                },
+                text: self.str(text)?,
+            }),
+            Block::Title(title) => Block::Title(self.str(title)?),
+            Block::Tags(tags) => {
+                Block::Tags(tags.iter().map(|it| self.str(it)).collect::<Option<_>>()?)
+            }
+            Block::Metadata(metadata) => Block::Metadata(Metadata {
+                syntax: metadata.syntax,
+                map: metadata
+                    .map
+                    .iter()
+                    .map(|(key, value)| (self.cow(key), self.metadata_value(value)))
+                    .collect(),
+            }),
+        })
+    }
+
+    fn metadata_value(&self, value: &MetadataValue<'_>) -> MetadataValue<'a> {
+        match value {
+            MetadataValue::Text(it) => MetadataValue::Text(self.cow(it)),
+            MetadataValue::List(items) => {
+                MetadataValue::List(items.iter().map(|it| self.cow(it)).collect())
+            }
+        }
+    }
+
+    fn list(&self, list: &List<'_>) -> Option<List<'a>> {
+        Some(List {
+            kind: list.kind,
+            items: list
+                .items
+                .iter()
+                .map(|item| {
+                    Some(ListItem {
+                        content: self.fragments(&item.content)?,
+                        children: match &item.children {
+                            Some(children) => Some(self.list(children)?),
+                            None => None,
+                        },
+                    })
+                })
+                .collect::<Option<_>>()?,
+        })
+    }
+
+    fn lines(&self, lines: &[Fragments<'_>]) -> Option<Lines<'a>> {
+        lines.iter().map(|line| self.fragments(line)).collect()
+    }
+
+    fn fragments(&self, fragments: &[Fragment<'_>]) -> Option<Fragments<'a>> {
+        fragments.iter().map(|it| self.fragment(it)).collect()
+    }
+
+    fn fragment(&self, fragment: &Fragment<'_>) -> Option<Fragment<'a>> {
+        Some(match fragment {
+            Fragment::Link((text, url)) => Fragment::Link((self.str(text)?, self.str(url)?)),
+            Fragment::Image((text, url)) => Fragment::Image((self.str(text)?, self.str(url)?)),
+            Fragment::Autolink(url) => Fragment::Autolink(self.str(url)?),
+            Fragment::InlineCode(code) => Fragment::InlineCode(self.str(code)?),
+            Fragment::Bold(text) => Fragment::Bold(self.fragments(text)?),
+            Fragment::BoldItalic(text) => Fragment::BoldItalic(self.fragments(text)?),
+            Fragment::Italic(text) => Fragment::Italic(self.fragments(text)?),
+            Fragment::Strikethrough(text) => Fragment::Strikethrough(self.fragments(text)?),
+            Fragment::Escaped(text) => Fragment::Escaped(self.str(text)?),
+            Fragment::LineBreak(text) => Fragment::LineBreak(self.str(text)?),
+            Fragment::Plain(text) => Fragment::Plain(self.str(text)?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_same_as_full_parse(old_source: &str, edit: Edit<'_>) {
+        let old_document = root_parse_markdown_with_recovery(old_source);
+        let new_source = edit.apply(old_source);
+        assert_eq!(
+            root_parse_markdown_incremental(&old_document, old_source, &new_source, &edit),
+            root_parse_markdown_with_recovery(&new_source),
+            "old: {old_source:?}, edit: {edit:?}"
+        );
+    }
+
+    #[test]
+    fn test_edit_apply() {
+        assert_eq!(Edit::new(2..4, "xyz").apply("abcdef"), "abxyzef");
+        assert_eq!(Edit::new(6..6, "!").apply("abcdef"), "abcdef!");
+    }
+
+    #[test]
+    fn test_incremental_reuses_blocks_after_edit() {
+        let old_source = "# one\n- a\n- b\n\ntext *here*\n```rs\nlet a = 1;\n```\n> quote\n";
+        let edit = Edit::new(2..5, "uno");
+        let new_source = edit.apply(old_source);
+        let old_document = root_parse_markdown_with_recovery(old_source);
+        let new_document =
+            root_parse_markdown_incremental(&old_document, old_source, &new_source, &edit);
+        assert_eq!(new_document, root_parse_markdown_with_recovery(&new_source));
+
+        // The reused blocks point into the new source.
+        let Block::BlockQuote(lines) = &new_document.blocks.last().unwrap().value else {
+            panic!("expected a block quote");
+        };
+        let Fragment::Plain(quote) = lines[0][0] else {
+            panic!("expected plain text");
+        };
+        assert!(SourceMap::new(&new_source).offset_of(quote).is_some());
+    }
+
+    #[test]
+    fn test_incremental_merges_and_splits_blocks() {
+        // Extends the list above.
+        assert_same_as_full_parse("- a\n\nb\n", Edit::new(4..4, "- c\n"));
+        // Splits a table.
+        assert_same_as_full_parse("| a |\n|---|\n| 1 |\n| 2 |\n", Edit::new(17..17, "x\n"));
+        // Closes a code fence.
+        assert_same_as_full_parse("```\ncode\n# not a heading\n", Edit::new(25..25, "```\n"));
+        // Opens a code fence.
+        assert_same_as_full_parse("# a\ntext\n```\n", Edit::new(4..4, "```\n"));
+        // Deletes everything.
+        assert_same_as_full_parse("# a\ntext\n", Edit::new(0..9, ""));
+        // Types into an empty document.
+        assert_same_as_full_parse("", Edit::new(0..0, "# a"));
+    }
+
+    #[test]
+    fn test_incremental_w_front_matter_and_diagnostics() {
+        // Turns the start of the document into YAML front matter.
+        assert_same_as_full_parse("---\ntitle: a\n# b\n", Edit::new(13..13, "---\n"));
+        // Edits inside of the front matter.
+        assert_same_as_full_parse("---\ntitle: a\n---\n# b\n", Edit::new(11..12, "xyz"));
+        // Edits after the front matter.
+        assert_same_as_full_parse("---\ntitle: a\n---\n# b\nc\n", Edit::new(19..20, "d"));
+        // Diagnostics before & after the edit move w/ their blocks.
+        assert_same_as_full_parse("a **b\n\nc\n\nd [e\n", Edit::new(7..8, "hello\nworld"));
+        // Fixes the missing new line at the end.
+        assert_same_as_full_parse("a\n# b", Edit::new(5..5, "\n"));
+    }
+
+    /// Random edits are applied one after another, and after each one the incremental parse
+    /// has to match a full parse of the new source.
+    #[test]
+    fn test_incremental_matches_full_parse_for_random_edits() {
+        const SNIPPETS: &[&str] = &[
+            "# heading\n",
+            "## h2 *it*\n",
+            "@title: Hi\n",
+            "@tags: a, b\n",
+            "@date: 2023-06-01\n",
+            "---\n",
+            ";;;\n",
+            "title: x\n",
+            "{\"a\": 1}\n",
+            "- item\n",
+            "  - sub\n",
+            "1. one\n",
+            "   2. two\n",
+            "> quote **b**\n",
+            "| a | b |\n",
+            "|:--|--:|\n",
+            "```rs\n",
+            "```\n",
+            "let a = 1;\n",
+            "text w/ `code` and [link](url)\n",
+            "**unclosed\n",
+            "~~strike~~ <https://r3bl.com>\n",
+            "é\n",
+            "\n",
+            "-",
+            "*",
+            "`",
+            "|",
+            "#",
+            " ",
+            "x",
+        ];
+
+        // Deterministic xorshift, so that failures can be reproduced.
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = |bound: usize| -> usize {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % bound as u64) as usize
+        };
+
+        // Each document borrows its source, so the sources are leaked to chain the edits.
+        let leak = |it: String| -> &'static str { Box::leak(it.into_boxed_str()) };
+
+        for _ in 0..100 {
+            let mut source = leak(
+                (0..next(12))
+                    .map(|_| SNIPPETS[next(SNIPPETS.len())])
+                    .collect(),
+            );
+            let mut document = root_parse_markdown_with_recovery(source);
+
+            for _ in 0..20 {
+                let mut start = next(source.len() + 1);
+                while !source.is_char_boundary(start) {
+                    start -= 1;
+                }
+                let mut end = (start + next(12)).min(source.len());
+                while !source.is_char_boundary(end) {
+                    end += 1;
+                }
+                let text = (0..next(3))
+                    .map(|_| SNIPPETS[next(SNIPPETS.len())])
+                    .collect::<String>();
+                let edit = Edit::new(start..end, &text);
+
+                let new_source = leak(edit.apply(source));
+                let incremental =
+                    root_parse_markdown_incremental(&document, source, new_source, &edit);
+                assert_eq!(
+                    incremental,
+                    root_parse_markdown_with_recovery(new_source),
+                    "old: {source:?}, edit: {edit:?}"
+                );
+
+                document = incremental;
+                source = new_source;
+            }
+        }
+    }
+}
@@ -0,0 +1,393 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use crate::*;
+use constants::*;
+use miette::Diagnostic;
+use nom::{bytes::complete::tag, Err as NomErr};
+use thiserror::Error;
+
+/// The output of [root_parse_markdown_with_recovery]. Each top level block carries its [Span]
+/// (including its trailing new line), and the spans of the nodes inside of it (eg: the lines of
+/// a list, or the [Fragment]s of a line), via [Spanned]. See [SpanTree] for which nodes those
+/// are.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParsedDocument<'a> {
+    pub blocks: Vec<Spanned<Block<'a>>>,
+    pub diagnostics: Vec<ParseDiagnostic>,
+}
+
+impl<'a> ParsedDocument<'a> {
+    /// Drop the spans, eg: to pass the blocks to [translate].
+    pub fn into_document(self) -> Document<'a> {
+        self.blocks.into_iter().map(|it| it.value).collect()
+    }
+}
+
+/// The problems that [root_parse_markdown_with_recovery] recovers from. These can be rendered
+/// w/ [miette], eg: `miette::Report::new(diagnostic).with_source_code(input.to_string())`.
+#[derive(Error, Diagnostic, Clone, Debug, PartialEq)]
+pub enum ParseDiagnostic {
+    #[error("Code block is never closed")]
+    #[diagnostic(
+        code(md_parser::unterminated_code_block),
+        help("Add a line w/ ``` to close it, until then it runs to the end of the document")
+    )]
+    UnterminatedCodeBlock {
+        #[label("this code block")]
+        span: Span,
+    },
+
+    #[error("Could not parse the inline element that starts w/ `{found}`")]
+    #[diagnostic(
+        code(md_parser::invalid_inline_element),
+        help("Close the element, or escape `{found}` w/ a backslash; until then it is kept as plain text")
+    )]
+    InvalidInlineElement {
+        found: char,
+        #[label("this element")]
+        span: Span,
+    },
+
+    #[error("Last line is missing its new line")]
+    #[diagnostic(
+        code(md_parser::missing_new_line),
+        severity(Warning),
+        help("Add a new line to the end of the document, until then this line is plain text")
+    )]
+    MissingNewLine {
+        #[label("this line")]
+        span: Span,
+    },
+}
+
+impl ParseDiagnostic {
+    pub fn span(&self) -> Span {
+        match self {
+            ParseDiagnostic::UnterminatedCodeBlock { span }
+            | ParseDiagnostic::InvalidInlineElement { span, .. }
+            | ParseDiagnostic::MissingNewLine { span } => *span,
+        }
+    }
+
+    pub(crate) fn span_mut(&mut self) -> &mut Span {
+        match self {
+            ParseDiagnostic::UnterminatedCodeBlock { span }
+            | ParseDiagnostic::InvalidInlineElement { span, .. }
+            | ParseDiagnostic::MissingNewLine { span } => span,
+        }
+    }
+}
+
+/// Unlike [root_parse_markdown], this parses the entire `input`, no matter what. Whenever a
+/// line can't be parsed, the parser recovers by turning it into a [Block::Text] (or a
+/// [Block::CodeBlock] that runs to the end of `input` for an unterminated code fence), records
+/// a [ParseDiagnostic], and moves on to the next line.
+pub fn root_parse_markdown_with_recovery(input: &str) -> ParsedDocument<'_> {
+    let source_map = SourceMap::new(input);
+    let mut document = ParsedDocument {
+        blocks: vec![],
+        diagnostics: vec![],
+    };
+
+    let mut offset = 0;
+    if let Ok((rest, front_matter)) = parse_front_matter(input) {
+        offset = input.len() - rest.len();
+        let span = source_map.span(0..offset);
+        document
+            .blocks
+            .push(Spanned::new(span, Block::Metadata(front_matter)));
+    }
+    while offset < input.len() {
+        offset = parse_next_block(&source_map, offset, &mut document);
+    }
+
+    document
+}
+
+/// Parse the block that starts at `offset` in the source of `source_map`, and add it (and any
+/// diagnostics) to `document`. Returns the offset where the next block starts.
+pub(crate) fn parse_next_block<'a>(
+    source_map: &SourceMap<'a>,
+    offset: usize,
+    document: &mut ParsedDocument<'a>,
+) -> usize {
+    let input = source_map.source();
+    let remainder = &input[offset..];
+    let (rest, block, is_recovered) = match parse_block(remainder) {
+        Ok((rest, block)) => (rest, block, false),
+        Err(_) => {
+            let (rest, block) = recover_block(remainder, source_map, &mut document.diagnostics);
+            (rest, block, true)
+        }
+    };
+    let end = input.len() - rest.len();
+    let children = parse_block_span_trees(source_map, offset, &block, is_recovered);
+    document
+        .blocks
+        .push(Spanned::new(source_map.span(offset..end), block).with_children(children));
+    end
+}
+
+/// Parse the block that [parse_block] failed on, this always consumes at least one line.
+fn recover_block<'a>(
+    input: &'a str,
+    source_map: &SourceMap<'a>,
+    diagnostics: &mut Vec<ParseDiagnostic>,
+) -> (&'a str, Block<'a>) {
+    // Code fence w/out a closing fence.
+    if let Ok((body, language)) = parse_code_block_lang(input) {
+        if let Ok((text, _)) = tag::<_, _, nom::error::Error<_>>(NEW_LINE)(body) {
+            if let Some(span) = source_map.span_of(&input[..input.len() - text.len()]) {
+                diagnostics.push(ParseDiagnostic::UnterminatedCodeBlock { span });
+            }
+            return ("", Block::CodeBlock(CodeBlock::from((language, text))));
+        }
+    }
+
+    let (line, rest, has_new_line) = match input.find(NEW_LINE) {
+        Some(index) => (&input[..index], &input[index + 1..], true),
+        None => (input, "", false),
+    };
+
+    // The last line doesn't have to end w/ a new line, but every block parser expects one. So
+    // parse a copy of it w/ a new line, to find out what went wrong.
+    let line_with_new_line;
+    let strict_input = if has_new_line {
+        input
+    } else {
+        line_with_new_line = format!("{line}{NEW_LINE}");
+        &line_with_new_line
+    };
+    match parse_block(strict_input) {
+        Ok((_, Block::Text(_))) => {}
+        Ok(_) => {
+            if let Some(span) = source_map.span_of(line) {
+                diagnostics.push(ParseDiagnostic::MissingNewLine { span });
+            }
+        }
+        Err(_) => {
+            if let Err(NomErr::Error(error)) = parse_block_markdown_text_until_eol(strict_input) {
+                let offset = strict_input.len() - error.input.len();
+                let found = line[offset..].chars().next();
+                let start = source_map.offset_of(line).map(|it| it + offset);
+                if let (Some(found), Some(start)) = (found, start) {
+                    let span = source_map.span(start..start + found.len_utf8());
+                    diagnostics.push(ParseDiagnostic::InvalidInlineElement { found, span });
+                }
+            }
+        }
+    }
+
+    let fragments = parse_element_markdown_inline_nested(line)
+        .map(|(_, fragments)| fragments)
+        .unwrap_or_default();
+    (
+        rest,
+        Block::Text(merge_plain_fragments(source_map, fragments)),
+    )
+}
+
+/// Join adjacent [Fragment::Plain]s that are next to each other in the source into one, since
+/// the lenient inline parser produces one for each char it can't parse.
+fn merge_plain_fragments<'a>(
+    source_map: &SourceMap<'a>,
+    fragments: Fragments<'a>,
+) -> Fragments<'a> {
+    let mut merged: Fragments<'a> = vec![];
+    for fragment in fragments {
+        if let (Some(Fragment::Plain(last)), Fragment::Plain(it)) = (merged.last_mut(), &fragment) {
+            if let (Some(last_start), Some(it_start)) =
+                (source_map.offset_of(last), source_map.offset_of(it))
+            {
+                if last_start + last.len() == it_start {
+                    *last = &source_map.source()[last_start..it_start + it.len()];
+                    continue;
+                }
+            }
+        }
+        merged.push(fragment);
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_valid_markdown_w_spans() {
+        let input = "# Heading\n\n```rs\nlet x = `y`;\n```\n- one\n- two\n";
+        let it = root_parse_markdown_with_recovery(input);
+        assert_eq!(it.diagnostics, vec![]);
+        let spans = it
+            .blocks
+            .iter()
+            .map(|it| (&input[it.span.range()], it.span.line, it.span.column))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            spans,
+            vec![
+                ("# Heading\n", 1, 1),
+                ("\n", 2, 1),
+                ("```rs\nlet x = `y`;\n```", 3, 1),
+                ("\n", 5, 4),
+                ("- one\n- two\n", 6, 1),
+            ]
+        );
+        assert_eq!(it.into_document(), root_parse_markdown(input).unwrap().1);
+    }
+
+    #[test]
+    fn test_parse_front_matter_w_spans() {
+        let input = "---\ntitle: Hi\n---\n---\n";
+        let it = root_parse_markdown_with_recovery(input);
+        assert_eq!(it.diagnostics, vec![]);
+        let spans = it
+            .blocks
+            .iter()
+            .map(|it| &input[it.span.range()])
+            .collect::<Vec<_>>();
+        assert_eq!(spans, vec!["---\ntitle: Hi\n---\n", "---\n"]);
+        assert_eq!(it.into_document(), root_parse_markdown(input).unwrap().1);
+    }
+
+    #[test]
+    fn test_recover_from_invalid_inline_element() {
+        let input = "foo *bar\n[link](oops\nbaz\n";
+        let it = root_parse_markdown_with_recovery(input);
+        assert_eq!(
+            it.diagnostics,
+            vec![
+                ParseDiagnostic::InvalidInlineElement {
+                    found: '*',
+                    span: Span {
+                        start: 4,
+                        end: 5,
+                        line: 1,
+                        column: 5
+                    }
+                },
+                ParseDiagnostic::InvalidInlineElement {
+                    found: '[',
+                    span: Span {
+                        start: 9,
+                        end: 10,
+                        line: 2,
+                        column: 1
+                    }
+                },
+            ]
+        );
+        assert_eq!(
+            it.into_document(),
+            vec![
+                Block::Text(vec![Fragment::Plain("foo *bar")]),
+                Block::Text(vec![Fragment::Plain("[link](oops")]),
+                Block::Text(vec![Fragment::Plain("baz")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recover_from_unterminated_code_block() {
+        let input = "text\n```bash\necho hi\n";
+        let it = root_parse_markdown_with_recovery(input);
+        assert_eq!(
+            it.diagnostics,
+            vec![ParseDiagnostic::UnterminatedCodeBlock {
+                span: Span {
+                    start: 5,
+                    end: 13,
+                    line: 2,
+                    column: 1
+                }
+            }]
+        );
+        assert_eq!(it.blocks[1].span.range(), 5..input.len());
+        assert_eq!(
+            it.into_document(),
+            vec![
+                Block::Text(vec![Fragment::Plain("text")]),
+                Block::CodeBlock(CodeBlock::from(("bash", "echo hi\n"))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recover_last_line_wo_new_line() {
+        // Plain text doesn't need the new line.
+        let it = root_parse_markdown_with_recovery("foo\n**bar**");
+        assert_eq!(it.diagnostics, vec![]);
+        assert_eq!(
+            it.into_document(),
+            vec![
+                Block::Text(vec![Fragment::Plain("foo")]),
+                Block::Text(vec![Fragment::Bold(vec![Fragment::Plain("bar")])]),
+            ]
+        );
+
+        // But other blocks do.
+        let it = root_parse_markdown_with_recovery("foo\n# bar");
+        assert_eq!(
+            it.diagnostics,
+            vec![ParseDiagnostic::MissingNewLine {
+                span: Span {
+                    start: 4,
+                    end: 9,
+                    line: 2,
+                    column: 1
+                }
+            }]
+        );
+        assert_eq!(
+            it.blocks[1].value,
+            Block::Text(vec![Fragment::Plain("# bar")])
+        );
+
+        // Invalid inline elements are still reported.
+        let it = root_parse_markdown_with_recovery("`foo");
+        assert_eq!(
+            it.diagnostics,
+            vec![ParseDiagnostic::InvalidInlineElement {
+                found: '`',
+                span: Span {
+                    start: 0,
+                    end: 1,
+                    line: 1,
+                    column: 1
+                }
+            }]
+        );
+    }
+
+    #[test]
+    fn test_render_diagnostic_w_miette() {
+        let input = "foo *bar\n";
+        let diagnostic = root_parse_markdown_with_recovery(input)
+            .diagnostics
+            .remove(0);
+        let report = miette::Report::new(diagnostic).with_source_code(input.to_string());
+        let mut output = String::new();
+        miette::NarratableReportHandler::new()
+            .render_report(&mut output, report.as_ref())
+            .unwrap();
+        assert!(output.contains("Could not parse the inline element that starts w/ `*`"));
+        assert!(output.contains("this element"));
+        assert!(output.contains("md_parser::invalid_inline_element"));
+    }
+}
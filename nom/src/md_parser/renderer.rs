@@ -0,0 +1,218 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use crate::*;
+
+/// A visitor that turns a [Document] into a [String]. The provided `render_*` methods walk the
+/// [Block]s & [Fragment]s, and call the required methods (one for each variant) w/ the children
+/// already rendered. So a renderer only has to decide how to wrap each node, eg:
+/// [HtmlRenderer], [AnsiRenderer], [PlainTextRenderer], and [MarkdownRenderer].
+pub trait Renderer {
+    // Blocks.
+
+    fn title(&self, title: &str) -> String;
+
+    fn tags(&self, tags: &[&str]) -> String;
+
+    fn metadata(&self, metadata: &Metadata<'_>) -> String;
+
+    fn heading(&self, level: Level, text: String) -> String;
+
+    /// Both flat lists ([Block::OrderedList], [Block::UnorderedList]) & [Block::NestedList] end
+    /// up here. The `items` come from [Renderer::list_item]. `depth` is 0 for a top level list.
+    fn list(&self, kind: ListKind, depth: usize, items: Vec<String>) -> String;
+
+    /// `index` is the 0 based position of the item in its list, and `children` is the rendered
+    /// sub list (which is empty if there isn't one).
+    fn list_item(
+        &self,
+        kind: ListKind,
+        depth: usize,
+        index: usize,
+        text: String,
+        children: String,
+    ) -> String;
+
+    fn block_quote(&self, lines: Vec<String>) -> String;
+
+    fn horizontal_rule(&self) -> String;
+
+    fn table(
+        &self,
+        alignments: &[Alignment],
+        header: Vec<String>,
+        rows: Vec<Vec<String>>,
+    ) -> String;
+
+    fn code_block(&self, code_block: &CodeBlock<'_>) -> String;
+
+    /// A single line of text ([Block::Text]), which may be empty.
+    fn text(&self, text: String) -> String;
+
+    // Fragments.
+
+    fn plain(&self, text: &str) -> String;
+
+    fn escaped(&self, text: &str) -> String;
+
+    fn inline_code(&self, code: &str) -> String;
+
+    fn link(&self, text: &str, url: &str) -> String;
+
+    fn image(&self, text: &str, url: &str) -> String;
+
+    fn autolink(&self, url: &str) -> String;
+
+    fn line_break(&self, matched: &str) -> String;
+
+    fn bold(&self, text: String) -> String;
+
+    fn italic(&self, text: String) -> String;
+
+    fn bold_italic(&self, text: String) -> String;
+
+    fn strikethrough(&self, text: String) -> String;
+
+    // Traversal.
+
+    fn render_document(&self, document: &[Block<'_>]) -> String {
+        document
+            .iter()
+            .map(|block| self.render_block(block))
+            .collect()
+    }
+
+    fn render_block(&self, block: &Block<'_>) -> String {
+        match block {
+            Block::Title(title) => self.title(title),
+            Block::Tags(tags) => self.tags(tags),
+            Block::Metadata(metadata) => self.metadata(metadata),
+            Block::Heading((level, text)) => self.heading(*level, self.render_fragments(text)),
+            Block::OrderedList(lines) => self.render_flat_list(ListKind::Ordered, lines),
+            Block::UnorderedList(lines) => self.render_flat_list(ListKind::Unordered, lines),
+            Block::NestedList(list) => self.render_list(list, 0),
+            Block::BlockQuote(lines) => self.block_quote(
+                lines
+                    .iter()
+                    .map(|line| self.render_fragments(line))
+                    .collect(),
+            ),
+            Block::HorizontalRule => self.horizontal_rule(),
+            Block::Table(table) => self.render_table(table),
+            Block::CodeBlock(code_block) => self.code_block(code_block),
+            Block::Text(text) => self.text(self.render_fragments(text)),
+        }
+    }
+
+    fn render_flat_list(&self, kind: ListKind, lines: &[Fragments<'_>]) -> String {
+        let items = lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                self.list_item(kind, 0, index, self.render_fragments(line), String::new())
+            })
+            .collect();
+        self.list(kind, 0, items)
+    }
+
+    fn render_list(&self, list: &List<'_>, depth: usize) -> String {
+        let items = list
+            .items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let children = match &item.children {
+                    Some(children) => self.render_list(children, depth + 1),
+                    None => String::new(),
+                };
+                let text = self.render_fragments(&item.content);
+                self.list_item(list.kind, depth, index, text, children)
+            })
+            .collect();
+        self.list(list.kind, depth, items)
+    }
+
+    fn render_table(&self, table: &Table<'_>) -> String {
+        let render_row = |row: &[Fragments<'_>]| -> Vec<String> {
+            row.iter().map(|cell| self.render_fragments(cell)).collect()
+        };
+        self.table(
+            &table.alignments,
+            render_row(&table.header),
+            table.rows.iter().map(|row| render_row(row)).collect(),
+        )
+    }
+
+    fn render_fragments(&self, fragments: &[Fragment<'_>]) -> String {
+        fragments
+            .iter()
+            .map(|fragment| self.render_fragment(fragment))
+            .collect()
+    }
+
+    fn render_fragment(&self, fragment: &Fragment<'_>) -> String {
+        match fragment {
+            Fragment::Plain(text) => self.plain(text),
+            Fragment::Escaped(text) => self.escaped(text),
+            Fragment::InlineCode(code) => self.inline_code(code),
+            Fragment::Link((text, url)) => self.link(text, url),
+            Fragment::Image((text, url)) => self.image(text, url),
+            Fragment::Autolink(url) => self.autolink(url),
+            Fragment::LineBreak(matched) => self.line_break(matched),
+            Fragment::Bold(text) => self.bold(self.render_fragments(text)),
+            Fragment::Italic(text) => self.italic(self.render_fragments(text)),
+            Fragment::BoldItalic(text) => self.bold_italic(self.render_fragments(text)),
+            Fragment::Strikethrough(text) => self.strikethrough(self.render_fragments(text)),
+        }
+    }
+}
+
+/// Pads the cells of each column in a table (the header & the body rows) to the same width, so
+/// they line up in monospaced output. `width` returns the number of columns a cell takes up on
+/// screen, which can be less than its length (eg: when it contains ANSI escape sequences).
+pub fn align_table_cells(
+    alignments: &[Alignment],
+    rows: Vec<Vec<String>>,
+    width: impl Fn(&str) -> usize,
+) -> Vec<Vec<String>> {
+    let mut column_widths: Vec<usize> = vec![];
+    for row in &rows {
+        for (index, cell) in row.iter().enumerate() {
+            match column_widths.get_mut(index) {
+                Some(it) => *it = (*it).max(width(cell)),
+                None => column_widths.push(width(cell)),
+            }
+        }
+    }
+
+    rows.into_iter()
+        .map(|row| {
+            row.into_iter()
+                .enumerate()
+                .map(|(index, cell)| {
+                    let padding = column_widths[index] - width(&cell);
+                    let (before, after) = match alignments.get(index) {
+                        Some(Alignment::Right) => (padding, 0),
+                        Some(Alignment::Center) => (padding / 2, padding - padding / 2),
+                        _ => (0, padding),
+                    };
+                    format!("{}{cell}{}", " ".repeat(before), " ".repeat(after))
+                })
+                .collect()
+        })
+        .collect()
+}
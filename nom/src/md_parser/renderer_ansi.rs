@@ -0,0 +1,281 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use crate::*;
+
+/// Renders a [Document] w/ ANSI escape sequences, to be displayed in a terminal. Each style is
+/// turned off w/ its own reset sequence (instead of a full reset), so that styles can be nested,
+/// eg: italic text inside of bold text.
+///
+/// Some reset sequences turn off more than the style that they close, eg: [NORMAL_INTENSITY]
+/// turns off both [BOLD] & [DIM]. So every span is wrapped w/ [wrap_style], which re-applies
+/// the outer style after each inner span that resets it.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct AnsiRenderer;
+
+/// ANSI SGR escape sequences used by [AnsiRenderer].
+pub mod ansi {
+    pub const BOLD: &str = "\x1b[1m";
+    pub const DIM: &str = "\x1b[2m";
+    /// Turns off both [BOLD] & [DIM].
+    pub const NORMAL_INTENSITY: &str = "\x1b[22m";
+    pub const ITALIC: &str = "\x1b[3m";
+    pub const NO_ITALIC: &str = "\x1b[23m";
+    pub const UNDERLINE: &str = "\x1b[4m";
+    pub const NO_UNDERLINE: &str = "\x1b[24m";
+    pub const STRIKETHROUGH: &str = "\x1b[9m";
+    pub const NO_STRIKETHROUGH: &str = "\x1b[29m";
+    pub const FG_GREEN: &str = "\x1b[32m";
+    pub const FG_YELLOW: &str = "\x1b[33m";
+    pub const FG_BLUE: &str = "\x1b[34m";
+    pub const FG_CYAN: &str = "\x1b[36m";
+    pub const FG_DEFAULT: &str = "\x1b[39m";
+}
+use ansi::*;
+
+/// Wraps `text` in `style` & `reset`. Any `reset` that is already in `text` (from an inner
+/// span) is followed by `style`, so that the outer style stays on for the rest of `text`, eg:
+/// bold text after a link's dim URL, in a bold heading.
+pub fn wrap_style(style: &str, text: &str, reset: &str) -> String {
+    let restore = format!("{reset}{style}");
+    format!("{style}{}{reset}", text.replace(reset, &restore))
+}
+
+/// Number of columns that `text` takes up in a terminal, ie: its chars w/out any ANSI escape
+/// sequences.
+pub fn ansi_visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(it) = chars.next() {
+        if it == '\x1b' {
+            // Skip the rest of the sequence, which ends w/ a letter, eg: `[1m`.
+            for it in chars.by_ref() {
+                if it.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+impl Renderer for AnsiRenderer {
+    fn title(&self, title: &str) -> String {
+        let title = wrap_style(UNDERLINE, title, NO_UNDERLINE);
+        format!("{}\n", wrap_style(BOLD, &title, NORMAL_INTENSITY))
+    }
+
+    fn tags(&self, tags: &[&str]) -> String {
+        let tags = tags
+            .iter()
+            .map(|it| format!("#{it}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!("{FG_YELLOW}{tags}{FG_DEFAULT}\n")
+    }
+
+    fn metadata(&self, metadata: &Metadata<'_>) -> String {
+        metadata
+            .map
+            .iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    MetadataValue::Text(it) => it.to_string(),
+                    MetadataValue::List(it) => it.join(", "),
+                };
+                let line = format!("{key}: {value}");
+                format!("{}\n", wrap_style(DIM, &line, NORMAL_INTENSITY))
+            })
+            .collect()
+    }
+
+    fn heading(&self, level: Level, text: String) -> String {
+        let text = format!("{} {text}", "#".repeat(level as usize));
+        let text = match level {
+            Level::Heading1 => wrap_style(UNDERLINE, &text, NO_UNDERLINE),
+            _ => text,
+        };
+        format!("{}\n", wrap_style(BOLD, &text, NORMAL_INTENSITY))
+    }
+
+    fn list(&self, _kind: ListKind, _depth: usize, items: Vec<String>) -> String {
+        items.join("")
+    }
+
+    fn list_item(
+        &self,
+        kind: ListKind,
+        depth: usize,
+        index: usize,
+        text: String,
+        children: String,
+    ) -> String {
+        let indent = "  ".repeat(depth);
+        let tag = match kind {
+            ListKind::Unordered => "•".to_string(),
+            ListKind::Ordered => format!("{}.", index + 1),
+        };
+        format!("{indent}{FG_YELLOW}{tag}{FG_DEFAULT} {text}\n{children}")
+    }
+
+    fn block_quote(&self, lines: Vec<String>) -> String {
+        lines
+            .into_iter()
+            .map(|line| format!("{DIM}│{NORMAL_INTENSITY} {}\n", self.italic(line)))
+            .collect()
+    }
+
+    fn horizontal_rule(&self) -> String {
+        format!("{DIM}{}{NORMAL_INTENSITY}\n", "─".repeat(40))
+    }
+
+    fn table(
+        &self,
+        alignments: &[Alignment],
+        header: Vec<String>,
+        rows: Vec<Vec<String>>,
+    ) -> String {
+        let header = header
+            .into_iter()
+            .map(|it| wrap_style(BOLD, &it, NORMAL_INTENSITY))
+            .collect();
+        let rows = std::iter::once(header).chain(rows).collect();
+        let separator = format!(" {DIM}│{NORMAL_INTENSITY} ");
+        align_table_cells(alignments, rows, ansi_visible_width)
+            .into_iter()
+            .map(|row| format!("{}\n", row.join(&separator)))
+            .collect()
+    }
+
+    fn code_block(&self, code_block: &CodeBlock<'_>) -> String {
+        code_block
+            .text
+            .lines()
+            .map(|line| format!("  {FG_GREEN}{line}{FG_DEFAULT}\n"))
+            .collect()
+    }
+
+    fn text(&self, text: String) -> String {
+        format!("{text}\n")
+    }
+
+    fn plain(&self, text: &str) -> String {
+        text.to_string()
+    }
+
+    fn escaped(&self, text: &str) -> String {
+        text.to_string()
+    }
+
+    fn inline_code(&self, code: &str) -> String {
+        wrap_style(FG_CYAN, code, FG_DEFAULT)
+    }
+
+    fn link(&self, text: &str, url: &str) -> String {
+        format!(
+            "{} {}",
+            self.autolink(text),
+            wrap_style(DIM, &format!("({url})"), NORMAL_INTENSITY)
+        )
+    }
+
+    fn image(&self, text: &str, url: &str) -> String {
+        wrap_style(DIM, &format!("[image: {text}] ({url})"), NORMAL_INTENSITY)
+    }
+
+    fn autolink(&self, url: &str) -> String {
+        let url = wrap_style(FG_BLUE, url, FG_DEFAULT);
+        wrap_style(UNDERLINE, &url, NO_UNDERLINE)
+    }
+
+    /// Each [Block::Text] already ends w/ a new line.
+    fn line_break(&self, _matched: &str) -> String {
+        String::new()
+    }
+
+    fn bold(&self, text: String) -> String {
+        wrap_style(BOLD, &text, NORMAL_INTENSITY)
+    }
+
+    fn italic(&self, text: String) -> String {
+        wrap_style(ITALIC, &text, NO_ITALIC)
+    }
+
+    fn bold_italic(&self, text: String) -> String {
+        self.bold(self.italic(text))
+    }
+
+    fn strikethrough(&self, text: String) -> String {
+        wrap_style(STRIKETHROUGH, &text, NO_STRIKETHROUGH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ansi_visible_width() {
+        assert_eq!(ansi_visible_width("abc"), 3);
+        assert_eq!(ansi_visible_width(&AnsiRenderer.bold("•é".to_string())), 2);
+        assert_eq!(ansi_visible_width(""), 0);
+    }
+
+    #[test]
+    fn test_render_ansi_nested_styles() {
+        assert_eq!(
+            AnsiRenderer.render_fragments(&[Fragment::Bold(vec![
+                Fragment::Plain("a "),
+                Fragment::Italic(vec![Fragment::Plain("b")]),
+                Fragment::Plain(" c"),
+            ])]),
+            "\x1b[1ma \x1b[3mb\x1b[23m c\x1b[22m"
+        );
+    }
+
+    #[test]
+    fn test_render_ansi_inner_span_keeps_outer_intensity() {
+        // Bold inside a heading.
+        assert_eq!(
+            AnsiRenderer.heading(
+                Level::Heading2,
+                AnsiRenderer.render_fragments(&[
+                    Fragment::Bold(vec![Fragment::Plain("a")]),
+                    Fragment::Plain(" b"),
+                ])
+            ),
+            "\x1b[1m## \x1b[1ma\x1b[22m\x1b[1m b\x1b[22m\n"
+        );
+
+        // A link's dim URL inside bold.
+        assert_eq!(
+            AnsiRenderer.render_fragments(&[Fragment::Bold(vec![
+                Fragment::Link(("a", "b")),
+                Fragment::Plain(" c"),
+            ])]),
+            "\x1b[1m\x1b[4m\x1b[34ma\x1b[39m\x1b[24m \x1b[2m(b)\x1b[22m\x1b[1m c\x1b[22m"
+        );
+
+        // Underlined H1 w/ an (underlined) link inside.
+        assert_eq!(
+            AnsiRenderer.heading(Level::Heading1, AnsiRenderer.autolink("a") + " b"),
+            "\x1b[1m\x1b[4m# \x1b[4m\x1b[34ma\x1b[39m\x1b[24m\x1b[4m b\x1b[24m\x1b[22m\n"
+        );
+    }
+}
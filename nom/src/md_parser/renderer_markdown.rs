@@ -0,0 +1,340 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use crate::*;
+use constants::*;
+
+/// Pretty prints a [Document] back into Markdown. Parsing the output w/ [root_parse_markdown]
+/// results in the same [Document]. The output is normalized, so it isn't always the same as the
+/// original source, eg: `__bold__` is printed as `**bold**`, and every horizontal rule as `***`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct MarkdownRenderer;
+
+/// Number of spaces that a sub list is indented by, for each level of nesting.
+const LIST_INDENT: usize = 2;
+
+impl MarkdownRenderer {
+    /// Use `primary` to delimit `text`, unless `text` starts or ends w/ a `*` (eg: when it is
+    /// itself bold or italic), in which case `alternate` is used so the delimiters don't run
+    /// into each other.
+    fn delimit(text: String, primary: &str, alternate: &str) -> String {
+        let it = if text.starts_with(ITALIC_1) || text.ends_with(ITALIC_1) {
+            alternate
+        } else {
+            primary
+        };
+        format!("{it}{text}{it}")
+    }
+}
+
+/// Text is only quoted if it wouldn't be parsed back as is, while list items are always quoted.
+fn yaml_value(value: &MetadataValue<'_>) -> String {
+    match value {
+        MetadataValue::Text(it) => {
+            let is_plain = it.trim() == it
+                && ![QUOTE, SINGLE_QUOTE, LEFT_BRACKET]
+                    .iter()
+                    .any(|prefix| it.starts_with(prefix))
+                && !it.contains(NEW_LINE);
+            match is_plain {
+                true => it.to_string(),
+                false => json_string(it),
+            }
+        }
+        MetadataValue::List(_) => json_value(value),
+    }
+}
+
+fn json_value(value: &MetadataValue<'_>) -> String {
+    match value {
+        MetadataValue::Text(it) => json_string(it),
+        MetadataValue::List(items) => {
+            let items = items
+                .iter()
+                .map(|it| json_string(it))
+                .collect::<Vec<_>>()
+                .join(&format!("{COMMA}{SPACE}"));
+            format!("{LEFT_BRACKET}{items}{RIGHT_BRACKET}")
+        }
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut acc = String::from(QUOTE);
+    for it in text.chars() {
+        match it {
+            '"' => acc.push_str("\\\""),
+            '\\' => acc.push_str("\\\\"),
+            '\n' => acc.push_str("\\n"),
+            '\r' => acc.push_str("\\r"),
+            '\t' => acc.push_str("\\t"),
+            it if it.is_control() => acc.push_str(&format!("\\u{:04x}", it as u32)),
+            it => acc.push(it),
+        }
+    }
+    acc.push_str(QUOTE);
+    acc
+}
+
+impl Renderer for MarkdownRenderer {
+    fn title(&self, title: &str) -> String {
+        format!("{TITLE}{COLON} {title}{NEW_LINE}")
+    }
+
+    fn tags(&self, tags: &[&str]) -> String {
+        format!(
+            "{TAGS}{COLON} {LEFT_BRACKET}{}{RIGHT_BRACKET}{NEW_LINE}",
+            tags.join(&format!("{COMMA}{SPACE}"))
+        )
+    }
+
+    /// Metadata is printed in the same syntax that it was parsed from, so that `@title` in YAML
+    /// front matter isn't turned into a [Block::Title].
+    fn metadata(&self, metadata: &Metadata<'_>) -> String {
+        let Metadata { syntax, map } = metadata;
+        match syntax {
+            MetadataSyntax::AtKey => map
+                .iter()
+                .map(|(key, value)| format!("{AT}{key}{COLON} {}{NEW_LINE}", yaml_value(value)))
+                .collect(),
+            MetadataSyntax::Yaml => {
+                let entries = map
+                    .iter()
+                    .map(|(key, value)| format!("{key}{COLON} {}{NEW_LINE}", yaml_value(value)))
+                    .collect::<String>();
+                format!("{FRONT_MATTER_YAML}{NEW_LINE}{entries}{FRONT_MATTER_YAML}{NEW_LINE}")
+            }
+            MetadataSyntax::Json => {
+                let entries = map
+                    .iter()
+                    .map(|(key, value)| {
+                        format!("  {}{COLON} {}", json_string(key), json_value(value))
+                    })
+                    .collect::<Vec<_>>()
+                    .join(&format!("{COMMA}{NEW_LINE}"));
+                format!(
+                    "{FRONT_MATTER_JSON}{NEW_LINE}{LEFT_BRACE}{NEW_LINE}{entries}{NEW_LINE}\
+                    {RIGHT_BRACE}{NEW_LINE}{FRONT_MATTER_JSON}{NEW_LINE}"
+                )
+            }
+        }
+    }
+
+    fn heading(&self, level: Level, text: String) -> String {
+        let tag = HEADING_CHAR.to_string().repeat(level as usize);
+        format!("{tag}{SPACE}{text}{NEW_LINE}")
+    }
+
+    fn list(&self, _kind: ListKind, _depth: usize, items: Vec<String>) -> String {
+        items.join("")
+    }
+
+    fn list_item(
+        &self,
+        kind: ListKind,
+        depth: usize,
+        index: usize,
+        text: String,
+        children: String,
+    ) -> String {
+        let indent = SPACE.repeat(depth * LIST_INDENT);
+        let tag = match kind {
+            ListKind::Unordered => UNORDERED_LIST.to_string(),
+            ListKind::Ordered => format!("{}{PERIOD}", index + 1),
+        };
+        format!("{indent}{tag}{SPACE}{text}{NEW_LINE}{children}")
+    }
+
+    fn block_quote(&self, lines: Vec<String>) -> String {
+        lines
+            .into_iter()
+            .map(|line| match line.is_empty() {
+                true => format!("{BLOCK_QUOTE}{NEW_LINE}"),
+                false => format!("{BLOCK_QUOTE}{SPACE}{line}{NEW_LINE}"),
+            })
+            .collect()
+    }
+
+    fn horizontal_rule(&self) -> String {
+        format!(
+            "{}{NEW_LINE}",
+            HORIZONTAL_RULE_CHARS[1].to_string().repeat(3)
+        )
+    }
+
+    fn table(
+        &self,
+        alignments: &[Alignment],
+        header: Vec<String>,
+        rows: Vec<Vec<String>>,
+    ) -> String {
+        let render_row = |cells: Vec<String>| -> String {
+            let cells = cells
+                .into_iter()
+                .map(|cell| format!("{SPACE}{cell}{SPACE}{TABLE_PIPE}"))
+                .collect::<String>();
+            format!("{TABLE_PIPE}{cells}{NEW_LINE}")
+        };
+        let delimiters = alignments
+            .iter()
+            .map(|alignment| match alignment {
+                Alignment::None => "---",
+                Alignment::Left => ":--",
+                Alignment::Center => ":-:",
+                Alignment::Right => "--:",
+            })
+            .map(str::to_string)
+            .collect();
+        let mut acc = render_row(header);
+        acc.push_str(&render_row(delimiters));
+        for row in rows {
+            acc.push_str(&render_row(row));
+        }
+        acc
+    }
+
+    /// The new line after the closing fence is not part of the [CodeBlock], it is parsed into an
+    /// empty [Block::Text] instead.
+    fn code_block(&self, code_block: &CodeBlock<'_>) -> String {
+        let CodeBlock { language, text } = code_block;
+        let language = match *language {
+            UNKNOWN_LANGUAGE => "",
+            it => it,
+        };
+        format!("{CODE_BLOCK}{language}{NEW_LINE}{text}{CODE_BLOCK}")
+    }
+
+    fn text(&self, text: String) -> String {
+        format!("{text}{NEW_LINE}")
+    }
+
+    fn plain(&self, text: &str) -> String {
+        text.to_string()
+    }
+
+    fn escaped(&self, text: &str) -> String {
+        format!("{BACKSLASH}{text}")
+    }
+
+    fn inline_code(&self, code: &str) -> String {
+        format!("{BACKTICK}{code}{BACKTICK}")
+    }
+
+    fn link(&self, text: &str, url: &str) -> String {
+        format!("{LEFT_BRACKET}{text}{RIGHT_BRACKET}{LEFT_PAREN}{url}{RIGHT_PAREN}")
+    }
+
+    fn image(&self, text: &str, url: &str) -> String {
+        format!("{LEFT_IMG}{text}{RIGHT_IMG}{LEFT_PAREN}{url}{RIGHT_PAREN}")
+    }
+
+    fn autolink(&self, url: &str) -> String {
+        format!("{LEFT_ANGLE}{url}{RIGHT_ANGLE}")
+    }
+
+    fn line_break(&self, matched: &str) -> String {
+        matched.to_string()
+    }
+
+    fn bold(&self, text: String) -> String {
+        Self::delimit(text, BOLD_1, BOLD_2)
+    }
+
+    fn italic(&self, text: String) -> String {
+        Self::delimit(text, ITALIC_1, ITALIC_2)
+    }
+
+    fn bold_italic(&self, text: String) -> String {
+        Self::delimit(text, BITALIC_1, BITALIC_2)
+    }
+
+    fn strikethrough(&self, text: String) -> String {
+        format!("{STRIKETHROUGH}{text}{STRIKETHROUGH}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_round_trip(input: &str) {
+        let (remainder, document) = root_parse_markdown(input).unwrap();
+        assert_eq!(remainder, "");
+        let output = MarkdownRenderer.render_document(&document);
+        assert_eq!(root_parse_markdown(&output), Ok(("", document)), "{output}");
+    }
+
+    #[test]
+    fn test_render_markdown_fragments() {
+        let input = "__a__ *b _c_* **_d_** ~~e~~ `f` [g](h) ![i](j) <k:l> \\*\n";
+        let (_, document) = root_parse_markdown(input).unwrap();
+        assert_eq!(
+            MarkdownRenderer.render_document(&document),
+            "**a** _b *c*_ __*d*__ ~~e~~ `f` [g](h) ![i](j) <k:l> \\*\n"
+        );
+        assert_round_trip(input);
+    }
+
+    #[test]
+    fn test_render_markdown_nested_emphasis() {
+        // The delimiters of nested emphasis don't run into each other.
+        assert_eq!(
+            MarkdownRenderer.render_fragments(&[Fragment::Italic(vec![Fragment::Bold(vec![
+                Fragment::Plain("a")
+            ])])]),
+            "_**a**_"
+        );
+        assert_eq!(
+            MarkdownRenderer.render_fragments(&[Fragment::Bold(vec![Fragment::Italic(vec![
+                Fragment::Plain("b")
+            ])])]),
+            "__*b*__"
+        );
+        assert_round_trip("_**a**_ **_b_** *snake_case*\n");
+    }
+
+    #[test]
+    fn test_render_markdown_metadata() {
+        let input = "---\ntitle: \" Hi\"\ntags:\n  - a\n  - \"b\"\n---\n@date: 2023-06-01\n\
+            @authors: [Foo, \"Bar, Baz\"]\n";
+        let (_, document) = root_parse_markdown(input).unwrap();
+        assert_eq!(
+            MarkdownRenderer.render_document(&document),
+            "---\ntags: [\"a\", \"b\"]\ntitle: \" Hi\"\n---\n@authors: [\"Foo\", \"Bar, Baz\"]\n\
+            @date: 2023-06-01\n"
+        );
+        assert_round_trip(input);
+        assert_round_trip(
+            ";;;\n{\"title\": \"a\\\"b\\\\c\", \"n\": 1, \"tags\": [\"x\"]}\n;;;\n# Hi\n",
+        );
+    }
+
+    #[test]
+    fn test_render_markdown_blocks() {
+        let input = "@title: Foo\n@tags: [a, b]\n# Heading\n___\n1. one\n   - two\n\
+            - three\n- four\n> quote\n>\n> **more**\n| a | b |\n|:-:|--:|\n| `1` | 2 |\n\n\
+            ```\nlet x = `y`;\n```\nfoo  \nbar\n";
+        let (_, document) = root_parse_markdown(input).unwrap();
+        assert_eq!(
+            MarkdownRenderer.render_document(&document),
+            "@title: Foo\n@tags: [a, b]\n# Heading\n***\n1. one\n  - two\n\
+            - three\n- four\n> quote\n>\n> **more**\n| a | b |\n| :-: | --: |\n| `1` | 2 |\n\n\
+            ```\nlet x = `y`;\n```\nfoo  \nbar\n"
+        );
+        assert_round_trip(input);
+    }
+}
@@ -0,0 +1,154 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use crate::*;
+
+/// Renders a [Document] as plain text, w/out any of the Markdown syntax. The structure of
+/// lists, block quotes, and tables is kept by indenting & padding them.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PlainTextRenderer;
+
+impl Renderer for PlainTextRenderer {
+    fn title(&self, title: &str) -> String {
+        format!("{title}\n")
+    }
+
+    fn tags(&self, _tags: &[&str]) -> String {
+        String::new()
+    }
+
+    fn metadata(&self, _metadata: &Metadata<'_>) -> String {
+        String::new()
+    }
+
+    fn heading(&self, _level: Level, text: String) -> String {
+        format!("{text}\n")
+    }
+
+    fn list(&self, _kind: ListKind, _depth: usize, items: Vec<String>) -> String {
+        items.join("")
+    }
+
+    fn list_item(
+        &self,
+        kind: ListKind,
+        depth: usize,
+        index: usize,
+        text: String,
+        children: String,
+    ) -> String {
+        let indent = "  ".repeat(depth);
+        match kind {
+            ListKind::Unordered => format!("{indent}- {text}\n{children}"),
+            ListKind::Ordered => format!("{indent}{}. {text}\n{children}", index + 1),
+        }
+    }
+
+    fn block_quote(&self, lines: Vec<String>) -> String {
+        lines
+            .into_iter()
+            .map(|line| format!("{}\n", format!("  {line}").trim_end()))
+            .collect()
+    }
+
+    fn horizontal_rule(&self) -> String {
+        "\n".to_string()
+    }
+
+    fn table(
+        &self,
+        alignments: &[Alignment],
+        header: Vec<String>,
+        rows: Vec<Vec<String>>,
+    ) -> String {
+        let rows = std::iter::once(header).chain(rows).collect();
+        align_table_cells(alignments, rows, |it| it.chars().count())
+            .into_iter()
+            .map(|row| format!("{}\n", row.join("  ").trim_end()))
+            .collect()
+    }
+
+    fn code_block(&self, code_block: &CodeBlock<'_>) -> String {
+        code_block.text.to_string()
+    }
+
+    fn text(&self, text: String) -> String {
+        format!("{text}\n")
+    }
+
+    fn plain(&self, text: &str) -> String {
+        text.to_string()
+    }
+
+    fn escaped(&self, text: &str) -> String {
+        text.to_string()
+    }
+
+    fn inline_code(&self, code: &str) -> String {
+        code.to_string()
+    }
+
+    fn link(&self, text: &str, url: &str) -> String {
+        format!("{text} ({url})")
+    }
+
+    fn image(&self, text: &str, _url: &str) -> String {
+        text.to_string()
+    }
+
+    fn autolink(&self, url: &str) -> String {
+        url.to_string()
+    }
+
+    /// Each [Block::Text] already ends w/ a new line.
+    fn line_break(&self, _matched: &str) -> String {
+        String::new()
+    }
+
+    fn bold(&self, text: String) -> String {
+        text
+    }
+
+    fn italic(&self, text: String) -> String {
+        text
+    }
+
+    fn bold_italic(&self, text: String) -> String {
+        text
+    }
+
+    fn strikethrough(&self, text: String) -> String {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_plain_text() {
+        let (_, document) = root_parse_markdown(
+            "# **Hi** there\n1. one\n   - `two`\n> [quote](url)\n| a | bb |\n|--:|:-:|\n| ccc | d |\n",
+        )
+        .unwrap();
+        assert_eq!(
+            PlainTextRenderer.render_document(&document),
+            "Hi there\n1. one\n  - two\n  quote (url)\n  a  bb\nccc  d\n"
+        );
+    }
+}
@@ -0,0 +1,219 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Positions in the Markdown source. The block parsers don't track where they are, so
+//! [root_parse_markdown_with_recovery] works out the [Span] of each block (and of the nodes in
+//! it) from how much input its parsers consume, and [SourceMap] turns those byte offsets into
+//! lines & columns.
+
+use crate::*;
+use std::ops::{Deref, Range};
+
+/// A byte range in the Markdown source, along w/ the line & column where it starts. Both the
+/// line & column are 1 based, and the column is counted in chars (not bytes).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+impl From<Span> for miette::SourceSpan {
+    fn from(span: Span) -> Self {
+        (span.start, span.len()).into()
+    }
+}
+
+/// A value along w/ the [Span] of the Markdown source that it was parsed from, and the spans
+/// of the nodes inside of it (see [SpanTree]).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spanned<T> {
+    pub span: Span,
+    pub value: T,
+    pub children: Vec<SpanTree>,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(span: Span, value: T) -> Self {
+        Spanned {
+            span,
+            value,
+            children: vec![],
+        }
+    }
+
+    pub fn with_children(mut self, children: Vec<SpanTree>) -> Self {
+        self.children = children;
+        self
+    }
+}
+
+impl<T> Deref for Spanned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+/// The [Span] of a node inside of a [Block], along w/ the spans of the nodes inside of it, in
+/// the same order as they are in the node. The children of a [Spanned] block are:
+/// - [Block::Text] & [Block::Heading]: a node for each [Fragment] of the text.
+/// - [Block::OrderedList], [Block::UnorderedList] & [Block::BlockQuote]: a node for each line
+///   (w/out its tag), which has a node for each [Fragment] of the line.
+/// - [Block::NestedList]: a node for each [ListItem] (from its tag to the end of its sub list).
+///   An item has a node for its content line, and then one for its sub list (if any), which
+///   has a node for each of its items.
+/// - [Block::Table]: a node for each row (the header, then the body rows), from its first to
+///   its last `|`. A row has a node for each (trimmed) cell, which has a node for each
+///   [Fragment] of the cell.
+/// - Any other block has no children.
+///
+/// The span of a [Fragment] includes its delimiters (eg: the `**` of [Fragment::Bold]), and
+/// the emphasis fragments (eg: [Fragment::Bold]) have a node for each of their fragments.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SpanTree {
+    pub span: Span,
+    pub children: Vec<SpanTree>,
+}
+
+impl SpanTree {
+    pub fn new(span: Span, children: Vec<SpanTree>) -> Self {
+        SpanTree { span, children }
+    }
+}
+
+/// Maps string slices of the Markdown source (that a [Document] holds on to) back to their
+/// [Span] in the source.
+#[derive(Clone, Debug)]
+pub struct SourceMap<'a> {
+    source: &'a str,
+    /// Byte offset of the start of each line.
+    line_starts: Vec<usize>,
+}
+
+impl<'a> SourceMap<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(
+                source
+                    .match_indices(constants::NEW_LINE)
+                    .map(|(index, _)| index + 1),
+            )
+            .collect();
+        SourceMap {
+            source,
+            line_starts,
+        }
+    }
+
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// Returns the 1 based (line, column) of the given byte `offset`.
+    ///
+    /// Panics if `offset` is out of bounds, or isn't on a char boundary.
+    pub fn line_column(&self, offset: usize) -> (usize, usize) {
+        let line_index = match self.line_starts.binary_search(&offset) {
+            Ok(index) => index,
+            Err(index) => index - 1,
+        };
+        let line_start = self.line_starts[line_index];
+        let column = self.source[line_start..offset].chars().count() + 1;
+        (line_index + 1, column)
+    }
+
+    /// Returns the [Span] for the given byte `range` of the source.
+    ///
+    /// Panics if `range.start` is out of bounds, or isn't on a char boundary.
+    pub fn span(&self, range: Range<usize>) -> Span {
+        let (line, column) = self.line_column(range.start);
+        Span {
+            start: range.start,
+            end: range.end,
+            line,
+            column,
+        }
+    }
+
+    /// Returns the byte offset of `slice` in the source, or [None] if `slice` doesn't point
+    /// into the source (eg: [constants::UNKNOWN_LANGUAGE]).
+    pub fn offset_of(&self, slice: &str) -> Option<usize> {
+        let source_start = self.source.as_ptr() as usize;
+        let slice_start = slice.as_ptr() as usize;
+        let is_inside = slice_start >= source_start
+            && slice_start + slice.len() <= source_start + self.source.len();
+        is_inside.then(|| slice_start - source_start)
+    }
+
+    pub fn span_of(&self, slice: &str) -> Option<Span> {
+        let start = self.offset_of(slice)?;
+        Some(self.span(start..start + slice.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_column() {
+        let source_map = SourceMap::new("ab\nçd\n\ne");
+        assert_eq!(source_map.line_column(0), (1, 1));
+        assert_eq!(source_map.line_column(1), (1, 2));
+        assert_eq!(source_map.line_column(3), (2, 1));
+        // `ç` is 2 bytes wide, but only 1 column wide.
+        assert_eq!(source_map.line_column(5), (2, 2));
+        assert_eq!(source_map.line_column(7), (3, 1));
+        assert_eq!(source_map.line_column(8), (4, 1));
+        assert_eq!(source_map.line_column(9), (4, 2));
+    }
+
+    #[test]
+    fn test_span_of() {
+        let source = "foo\nbar baz";
+        let source_map = SourceMap::new(source);
+        assert_eq!(
+            source_map.span_of(&source[8..]),
+            Some(Span {
+                start: 8,
+                end: 11,
+                line: 2,
+                column: 5
+            })
+        );
+        assert_eq!(source_map.span_of(constants::UNKNOWN_LANGUAGE), None);
+        let miette_span: miette::SourceSpan = source_map.span_of(&source[8..]).unwrap().into();
+        assert_eq!(miette_span, (8, 3).into());
+    }
+}
@@ -0,0 +1,323 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Syntax highlighting of [Block::CodeBlock]s w/ [syntect], which is only available w/ the
+//! `syntax-highlighting` feature. Wrap a renderer in a [SyntaxHighlightingRenderer] to use it,
+//! eg: `SyntaxHighlightingRenderer::new(HtmlRenderer, &highlighter).render_document(&doc)`.
+
+use crate::*;
+use constants::*;
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{Color, FontStyle, Style, Theme},
+    parsing::SyntaxSet,
+    util::LinesWithEndings,
+};
+
+/// The r3bl theme & its loader are shared w/ the `syn-hi` crate, so they only live there. That
+/// file is formatted w/ `syn-hi`'s settings, and `load_default_theme` isn't used here.
+#[rustfmt::skip]
+#[allow(dead_code)]
+#[path = "../../../syn-hi/tests/common/lib_syntect.rs"]
+mod lib_syntect;
+pub use lib_syntect::try_load_r3bl_theme;
+
+/// A line of code (including its new line) split into spans, each w/ a single [Style].
+pub type HighlightedLine<'a> = Vec<(Style, &'a str)>;
+
+/// Holds the syntaxes & the theme, which are expensive to load, so create this once & reuse it.
+pub struct SyntaxHighlighter {
+    syntax_set: SyntaxSet,
+    theme: Theme,
+}
+
+impl SyntaxHighlighter {
+    pub fn new(theme: Theme) -> Self {
+        SyntaxHighlighter {
+            syntax_set: SyntaxSet::load_defaults_newlines(),
+            theme,
+        }
+    }
+
+    /// Uses the theme from [try_load_r3bl_theme].
+    pub fn try_new_w_r3bl_theme() -> std::io::Result<Self> {
+        Ok(Self::new(try_load_r3bl_theme()?))
+    }
+
+    /// Returns [None] for [UNKNOWN_LANGUAGE] & for languages that syntect doesn't know about.
+    /// The language can be a name or a file extension, eg: `rust` or `rs`.
+    pub fn highlight<'a>(&self, code_block: &CodeBlock<'a>) -> Option<Vec<HighlightedLine<'a>>> {
+        if code_block.language == UNKNOWN_LANGUAGE {
+            return None;
+        }
+        let syntax = self.syntax_set.find_syntax_by_token(code_block.language)?;
+        let mut highlight_lines = HighlightLines::new(syntax, &self.theme);
+        LinesWithEndings::from(code_block.text)
+            .map(|line| highlight_lines.highlight_line(line, &self.syntax_set).ok())
+            .collect()
+    }
+}
+
+/// Renderers that know how to display highlighted code, see [SyntaxHighlightingRenderer].
+pub trait HighlightedCodeBlockRenderer: Renderer {
+    fn highlighted_code_block(&self, language: &str, lines: &[HighlightedLine<'_>]) -> String;
+}
+
+/// Wraps `renderer` so that its code blocks are highlighted. Code blocks w/ an unknown language
+/// are passed on to `renderer` as is, and so is everything else.
+pub struct SyntaxHighlightingRenderer<'h, R> {
+    pub renderer: R,
+    pub highlighter: &'h SyntaxHighlighter,
+}
+
+impl<'h, R: HighlightedCodeBlockRenderer> SyntaxHighlightingRenderer<'h, R> {
+    pub fn new(renderer: R, highlighter: &'h SyntaxHighlighter) -> Self {
+        SyntaxHighlightingRenderer {
+            renderer,
+            highlighter,
+        }
+    }
+}
+
+impl<R: HighlightedCodeBlockRenderer> Renderer for SyntaxHighlightingRenderer<'_, R> {
+    fn code_block(&self, code_block: &CodeBlock<'_>) -> String {
+        match self.highlighter.highlight(code_block) {
+            Some(lines) => self
+                .renderer
+                .highlighted_code_block(code_block.language, &lines),
+            None => self.renderer.code_block(code_block),
+        }
+    }
+
+    fn title(&self, title: &str) -> String {
+        self.renderer.title(title)
+    }
+
+    fn tags(&self, tags: &[&str]) -> String {
+        self.renderer.tags(tags)
+    }
+
+    fn metadata(&self, metadata: &Metadata<'_>) -> String {
+        self.renderer.metadata(metadata)
+    }
+
+    fn heading(&self, level: Level, text: String) -> String {
+        self.renderer.heading(level, text)
+    }
+
+    fn list(&self, kind: ListKind, depth: usize, items: Vec<String>) -> String {
+        self.renderer.list(kind, depth, items)
+    }
+
+    fn list_item(
+        &self,
+        kind: ListKind,
+        depth: usize,
+        index: usize,
+        text: String,
+        children: String,
+    ) -> String {
+        self.renderer.list_item(kind, depth, index, text, children)
+    }
+
+    fn block_quote(&self, lines: Vec<String>) -> String {
+        self.renderer.block_quote(lines)
+    }
+
+    fn horizontal_rule(&self) -> String {
+        self.renderer.horizontal_rule()
+    }
+
+    fn table(
+        &self,
+        alignments: &[Alignment],
+        header: Vec<String>,
+        rows: Vec<Vec<String>>,
+    ) -> String {
+        self.renderer.table(alignments, header, rows)
+    }
+
+    fn text(&self, text: String) -> String {
+        self.renderer.text(text)
+    }
+
+    fn plain(&self, text: &str) -> String {
+        self.renderer.plain(text)
+    }
+
+    fn escaped(&self, text: &str) -> String {
+        self.renderer.escaped(text)
+    }
+
+    fn inline_code(&self, code: &str) -> String {
+        self.renderer.inline_code(code)
+    }
+
+    fn link(&self, text: &str, url: &str) -> String {
+        self.renderer.link(text, url)
+    }
+
+    fn image(&self, text: &str, url: &str) -> String {
+        self.renderer.image(text, url)
+    }
+
+    fn autolink(&self, url: &str) -> String {
+        self.renderer.autolink(url)
+    }
+
+    fn line_break(&self, matched: &str) -> String {
+        self.renderer.line_break(matched)
+    }
+
+    fn bold(&self, text: String) -> String {
+        self.renderer.bold(text)
+    }
+
+    fn italic(&self, text: String) -> String {
+        self.renderer.italic(text)
+    }
+
+    fn bold_italic(&self, text: String) -> String {
+        self.renderer.bold_italic(text)
+    }
+
+    fn strikethrough(&self, text: String) -> String {
+        self.renderer.strikethrough(text)
+    }
+}
+
+/// Each span gets an inline style, so the output doesn't need a separate stylesheet.
+impl HighlightedCodeBlockRenderer for HtmlRenderer {
+    fn highlighted_code_block(&self, language: &str, lines: &[HighlightedLine<'_>]) -> String {
+        let spans: String = lines
+            .iter()
+            .flatten()
+            .map(|(style, text)| {
+                let Color { r, g, b, .. } = style.foreground;
+                let mut css = format!("color:#{r:02x}{g:02x}{b:02x}");
+                if style.font_style.contains(FontStyle::BOLD) {
+                    css.push_str(";font-weight:bold");
+                }
+                if style.font_style.contains(FontStyle::ITALIC) {
+                    css.push_str(";font-style:italic");
+                }
+                if style.font_style.contains(FontStyle::UNDERLINE) {
+                    css.push_str(";text-decoration:underline");
+                }
+                format!("<span style=\"{css}\">{}</span>", escape_html(text))
+            })
+            .collect();
+        format!(
+            "<pre><code class=\"lang-{}\">{spans}</code></pre>",
+            escape_html(language)
+        )
+    }
+}
+
+/// Uses 24 bit (true color) escape sequences for the foreground colors from the theme.
+impl HighlightedCodeBlockRenderer for AnsiRenderer {
+    fn highlighted_code_block(&self, _language: &str, lines: &[HighlightedLine<'_>]) -> String {
+        use renderer_ansi::ansi::*;
+        lines
+            .iter()
+            .map(|line| {
+                let spans: String = line
+                    .iter()
+                    .map(|(style, text)| {
+                        let Color { r, g, b, .. } = style.foreground;
+                        let text = text.trim_end_matches(NEW_LINE);
+                        let mut span = format!("\x1b[38;2;{r};{g};{b}m{text}{FG_DEFAULT}");
+                        if style.font_style.contains(FontStyle::BOLD) {
+                            span = format!("{BOLD}{span}{NORMAL_INTENSITY}");
+                        }
+                        if style.font_style.contains(FontStyle::ITALIC) {
+                            span = format!("{ITALIC}{span}{NO_ITALIC}");
+                        }
+                        if style.font_style.contains(FontStyle::UNDERLINE) {
+                            span = format!("{UNDERLINE}{span}{NO_UNDERLINE}");
+                        }
+                        span
+                    })
+                    .collect();
+                format!("  {spans}\n")
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn highlighter() -> SyntaxHighlighter {
+        SyntaxHighlighter::try_new_w_r3bl_theme().unwrap()
+    }
+
+    #[test]
+    fn test_highlight_keeps_the_code() {
+        let code_block = CodeBlock::from(("rs", "fn main() {\n    let a = 1;\n}\n"));
+        let lines = highlighter().highlight(&code_block).unwrap();
+        assert_eq!(lines.len(), 3);
+        let text: String = lines.iter().flatten().map(|(_, it)| *it).collect();
+        assert_eq!(text, code_block.text);
+        // More than one style is used.
+        assert!(lines[0].len() > 1);
+    }
+
+    #[test]
+    fn test_highlight_unknown_language() {
+        let highlighter = highlighter();
+        let code_block = CodeBlock::from((UNKNOWN_LANGUAGE, "fn main() {}\n"));
+        assert_eq!(highlighter.highlight(&code_block), None);
+        let code_block = CodeBlock::from(("not-a-language", "fn main() {}\n"));
+        assert_eq!(highlighter.highlight(&code_block), None);
+
+        // Both renderers fall back to plain text.
+        let block = Block::CodeBlock(code_block);
+        assert_eq!(
+            SyntaxHighlightingRenderer::new(HtmlRenderer, &highlighter).render_block(&block),
+            HtmlRenderer.render_block(&block)
+        );
+        assert_eq!(
+            SyntaxHighlightingRenderer::new(AnsiRenderer, &highlighter).render_block(&block),
+            AnsiRenderer.render_block(&block)
+        );
+    }
+
+    #[test]
+    fn test_render_highlighted_html() {
+        let highlighter = highlighter();
+        let (_, document) = root_parse_markdown("# Code\n```rs\nlet a = \"<b>\";\n```\n").unwrap();
+        let html =
+            SyntaxHighlightingRenderer::new(HtmlRenderer, &highlighter).render_document(&document);
+        assert!(html.starts_with("<h1>Code</h1><pre><code class=\"lang-rs\"><span style=\"color:#"));
+        assert!(html.contains("&lt;b&gt;"));
+        assert!(html.ends_with("</span></code></pre>"));
+    }
+
+    #[test]
+    fn test_render_highlighted_ansi() {
+        let highlighter = highlighter();
+        let code_block = CodeBlock::from(("rust", "let a = 1;\nlet b = 2;\n"));
+        let ansi = SyntaxHighlightingRenderer::new(AnsiRenderer, &highlighter)
+            .render_block(&Block::CodeBlock(code_block));
+        let lines: Vec<&str> = ansi.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("\x1b[38;2;"));
+        assert_eq!(ansi_visible_width(lines[0]), "  let a = 1;".len());
+    }
+}
@@ -17,94 +17,182 @@
 
 use crate::*;
 
+/// Render the given [Document] as HTML, using [HtmlRenderer].
 pub fn translate(doc: Document) -> String {
-    let mut acc = vec![];
-    for block in doc {
-        match block {
-            Block::Heading((level, line)) => acc.push(translate_header(&level, line.to_vec())),
-            Block::UnorderedList(lines) => acc.push(translate_unordered_list(lines.to_vec())),
-            Block::OrderedList(lines) => acc.push(translate_ordered_list(lines.to_vec())),
-            Block::CodeBlock(code_block) => acc.push(translate_codeblock(&code_block)),
-            Block::Text(line) => acc.push(translate_line(line.to_vec())),
-            _ => {}
+    HtmlRenderer.render_document(&doc)
+}
+
+/// Renders a [Document] as HTML. All the text (including URLs & code) is escaped. [Block::Title],
+/// [Block::Tags], and [Block::Metadata] are not rendered.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct HtmlRenderer;
+
+/// Escape the chars that can't show up as is in HTML text, or in a `"` quoted attribute value.
+pub fn escape_html(text: &str) -> String {
+    let mut acc = String::with_capacity(text.len());
+    for it in text.chars() {
+        match it {
+            '&' => acc.push_str("&amp;"),
+            '<' => acc.push_str("&lt;"),
+            '>' => acc.push_str("&gt;"),
+            '"' => acc.push_str("&quot;"),
+            _ => acc.push(it),
         }
     }
-    acc.join("")
+    acc
 }
 
-fn translate_bold(input: &str) -> String {
-    format!("<b>{input}</b>")
-}
+impl Renderer for HtmlRenderer {
+    fn title(&self, _title: &str) -> String {
+        String::new()
+    }
 
-fn translate_italic(input: &str) -> String {
-    format!("<i>{input}</i>")
-}
+    fn tags(&self, _tags: &[&str]) -> String {
+        String::new()
+    }
 
-fn translate_inline_code(input: &str) -> String {
-    format!("<code>{input}</code>")
-}
+    fn metadata(&self, _metadata: &Metadata<'_>) -> String {
+        String::new()
+    }
 
-fn translate_link(link_text: &str, url: &str) -> String {
-    format!("<a href=\"{url}\">{link_text}</a>")
-}
+    fn heading(&self, level: Level, text: String) -> String {
+        let level = level as u8;
+        format!("<h{level}>{text}</h{level}>")
+    }
 
-fn translate_image(link_text: &str, url: &str) -> String {
-    format!("<img src=\"{url}\" alt=\"{link_text}\" />")
-}
+    fn list(&self, kind: ListKind, _depth: usize, items: Vec<String>) -> String {
+        let items = items.join("");
+        match kind {
+            ListKind::Unordered => format!("<ul>{items}</ul>"),
+            ListKind::Ordered => format!("<ol>{items}</ol>"),
+        }
+    }
 
-fn translate_list_elements(lines: Vec<Fragments>) -> String {
-    lines
-        .iter()
-        .map(|line| format!("<li>{}</li>", translate_text(line.to_vec())))
-        .collect::<Vec<String>>()
-        .join("")
-}
+    fn list_item(
+        &self,
+        _kind: ListKind,
+        _depth: usize,
+        _index: usize,
+        text: String,
+        children: String,
+    ) -> String {
+        format!("<li>{text}{children}</li>")
+    }
 
-fn translate_header(heading_level: &Level, text: Fragments) -> String {
-    let heading_level_number = (*heading_level) as u8;
-    format!(
-        "<h{}>{}</h{}>",
-        heading_level_number,
-        translate_text(text),
-        heading_level_number
-    )
-}
+    fn block_quote(&self, lines: Vec<String>) -> String {
+        let lines = lines
+            .into_iter()
+            .map(|line| self.text(line))
+            .collect::<Vec<String>>()
+            .join("");
+        format!("<blockquote>{lines}</blockquote>")
+    }
 
-fn translate_unordered_list(lines: Vec<Fragments>) -> String {
-    format!("<ul>{}</ul>", translate_list_elements(lines.to_vec()))
-}
+    fn horizontal_rule(&self) -> String {
+        "<hr />".to_string()
+    }
 
-fn translate_ordered_list(lines: Vec<Fragments>) -> String {
-    format!("<ol>{}</ol>", translate_list_elements(lines.to_vec()))
-}
+    fn table(
+        &self,
+        alignments: &[Alignment],
+        header: Vec<String>,
+        rows: Vec<Vec<String>>,
+    ) -> String {
+        let header = translate_table_row("th", alignments, header);
+        let rows = rows
+            .into_iter()
+            .map(|row| translate_table_row("td", alignments, row))
+            .collect::<Vec<String>>()
+            .join("");
+        format!("<table><thead>{header}</thead><tbody>{rows}</tbody></table>")
+    }
+
+    fn code_block(&self, code_block: &CodeBlock<'_>) -> String {
+        let CodeBlock { language, text } = code_block;
+        format!(
+            "<pre><code class=\"lang-{}\">{}</code></pre>",
+            escape_html(language),
+            escape_html(text)
+        )
+    }
 
-fn translate_codeblock(code_block: &CodeBlock) -> String {
-    let CodeBlock { language, text } = code_block;
-    format!("<pre><code class=\"lang-{language}\">{text}</code></pre>")
+    fn text(&self, text: String) -> String {
+        if !text.is_empty() {
+            format!("<p>{text}</p>")
+        } else {
+            text
+        }
+    }
+
+    fn plain(&self, text: &str) -> String {
+        escape_html(text)
+    }
+
+    fn escaped(&self, text: &str) -> String {
+        escape_html(text)
+    }
+
+    fn inline_code(&self, code: &str) -> String {
+        format!("<code>{}</code>", escape_html(code))
+    }
+
+    fn link(&self, text: &str, url: &str) -> String {
+        format!("<a href=\"{}\">{}</a>", escape_html(url), escape_html(text))
+    }
+
+    fn image(&self, text: &str, url: &str) -> String {
+        format!(
+            "<img src=\"{}\" alt=\"{}\" />",
+            escape_html(url),
+            escape_html(text)
+        )
+    }
+
+    fn autolink(&self, url: &str) -> String {
+        self.link(url, url)
+    }
+
+    fn line_break(&self, _matched: &str) -> String {
+        "<br />".to_string()
+    }
+
+    fn bold(&self, text: String) -> String {
+        format!("<b>{text}</b>")
+    }
+
+    fn italic(&self, text: String) -> String {
+        format!("<i>{text}</i>")
+    }
+
+    fn bold_italic(&self, text: String) -> String {
+        self.italic(self.bold(text))
+    }
+
+    fn strikethrough(&self, text: String) -> String {
+        format!("<del>{text}</del>")
+    }
 }
 
-fn translate_line(text: Fragments) -> String {
-    let line = translate_text(text);
-    if !line.is_empty() {
-        format!("<p>{line}</p>")
-    } else {
-        line
+fn translate_table_cell(tag: &str, alignment: &Alignment, text: String) -> String {
+    match alignment {
+        Alignment::None => format!("<{tag}>{text}</{tag}>"),
+        Alignment::Left => format!("<{tag} align=\"left\">{text}</{tag}>"),
+        Alignment::Center => format!("<{tag} align=\"center\">{text}</{tag}>"),
+        Alignment::Right => format!("<{tag} align=\"right\">{text}</{tag}>"),
     }
 }
 
-fn translate_text(text: Fragments) -> String {
-    text.iter()
-        .map(|part| match part {
-            Fragment::Bold(text) => translate_bold(text),
-            Fragment::Italic(text) => translate_italic(text),
-            Fragment::BoldItalic(text) => translate_italic(&translate_bold(text)),
-            Fragment::InlineCode(code) => translate_inline_code(code),
-            Fragment::Link((text, url)) => translate_link(text, url),
-            Fragment::Image((text, url)) => translate_image(text, url),
-            Fragment::Plain(text) => text.to_string(),
+fn translate_table_row(tag: &str, alignments: &[Alignment], cells: Vec<String>) -> String {
+    let cells = cells
+        .into_iter()
+        .enumerate()
+        .map(|(index, cell)| {
+            let alignment = alignments.get(index).unwrap_or(&Alignment::None);
+            translate_table_cell(tag, alignment, cell)
         })
         .collect::<Vec<String>>()
-        .join("")
+        .join("");
+    format!("<tr>{cells}</tr>")
 }
 
 #[cfg(test)]
@@ -113,21 +201,54 @@ mod tests {
 
     #[test]
     fn test_translate_bold() {
-        assert_eq!(translate_bold("bold af"), String::from("<b>bold af</b>"));
+        assert_eq!(
+            HtmlRenderer.bold("bold af".to_string()),
+            String::from("<b>bold af</b>")
+        );
     }
 
     #[test]
     fn test_translate_italic() {
         assert_eq!(
-            translate_italic("italic af"),
+            HtmlRenderer.italic("italic af".to_string()),
             String::from("<i>italic af</i>")
         );
     }
 
+    #[test]
+    fn test_translate_strikethrough() {
+        assert_eq!(
+            HtmlRenderer.strikethrough("struck af".to_string()),
+            String::from("<del>struck af</del>")
+        );
+    }
+
+    #[test]
+    fn test_translate_nested_text() {
+        let x = HtmlRenderer.render_fragments(&[
+            Fragment::BoldItalic(vec![Fragment::Plain("a")]),
+            Fragment::Bold(vec![
+                Fragment::Plain("bold with "),
+                Fragment::InlineCode("code"),
+                Fragment::Strikethrough(vec![Fragment::Italic(vec![Fragment::Plain("b")])]),
+            ]),
+            Fragment::Escaped("*"),
+            Fragment::Autolink("https://r3bl.com"),
+            Fragment::LineBreak("  "),
+        ]);
+        assert_eq!(
+            x,
+            String::from(
+                "<i><b>a</b></i><b>bold with <code>code</code><del><i>b</i></del></b>*\
+                <a href=\"https://r3bl.com\">https://r3bl.com</a><br />"
+            )
+        );
+    }
+
     #[test]
     fn test_translate_inline_code() {
         assert_eq!(
-            translate_inline_code("code af"),
+            HtmlRenderer.inline_code("code af"),
             String::from("<code>code af</code>")
         );
     }
@@ -135,7 +256,7 @@ mod tests {
     #[test]
     fn test_translate_link() {
         assert_eq!(
-            translate_link("click me!", "https://github.com"),
+            HtmlRenderer.link("click me!", "https://github.com"),
             String::from("<a href=\"https://github.com\">click me!</a>")
         );
     }
@@ -143,57 +264,82 @@ mod tests {
     #[test]
     fn test_translate_image() {
         assert_eq!(
-            translate_image("alt text", "https://github.com"),
+            HtmlRenderer.image("alt text", "https://github.com"),
             String::from("<img src=\"https://github.com\" alt=\"alt text\" />")
         );
     }
 
     #[test]
     fn test_translate_text() {
-        let x = translate_text(vec![
+        let x = HtmlRenderer.render_fragments(&[
             Fragment::Plain("Foobar is a Python library for dealing with word pluralization."),
-            Fragment::Bold("bold"),
-            Fragment::Italic("italic"),
+            Fragment::Bold(vec![Fragment::Plain("bold")]),
+            Fragment::Italic(vec![Fragment::Plain("italic")]),
             Fragment::InlineCode("code"),
             Fragment::Link(("tag", "https://link.com")),
             Fragment::Image(("tag", "https://link.com")),
             Fragment::Plain(". the end!"),
         ]);
         assert_eq!(x, String::from("Foobar is a Python library for dealing with word pluralization.<b>bold</b><i>italic</i><code>code</code><a href=\"https://link.com\">tag</a><img src=\"https://link.com\" alt=\"tag\" />. the end!"));
-        let x = translate_text(vec![]);
+        let x = HtmlRenderer.render_fragments(&[]);
         assert_eq!(x, String::from(""));
     }
 
+    #[test]
+    fn test_translate_escapes_html() {
+        let x = HtmlRenderer.render_document(&[
+            Block::Text(vec![
+                Fragment::Plain("1 < 2 & \"3\" > 0"),
+                Fragment::Escaped("<"),
+                Fragment::InlineCode("<b>"),
+                Fragment::Link(("<a>", "https://r3bl.com/?a=1&b=\"2\"")),
+            ]),
+            Block::CodeBlock(CodeBlock::from(("rs", "if a < b {}\n"))),
+        ]);
+        assert_eq!(
+            x,
+            String::from(
+                "<p>1 &lt; 2 &amp; &quot;3&quot; &gt; 0&lt;<code>&lt;b&gt;</code>\
+                <a href=\"https://r3bl.com/?a=1&amp;b=&quot;2&quot;\">&lt;a&gt;</a></p>\
+                <pre><code class=\"lang-rs\">if a &lt; b {}\n</code></pre>"
+            )
+        );
+    }
+
     #[test]
     fn test_translate_header() {
         assert_eq!(
-            translate_header(&Level::Heading1, vec![Fragment::Plain("Foobar")]),
+            HtmlRenderer.render_block(&Block::Heading((
+                Level::Heading1,
+                vec![Fragment::Plain("Foobar")]
+            ))),
             String::from("<h1>Foobar</h1>")
         );
     }
 
     #[test]
-    fn test_translate_list_elements() {
+    fn test_translate_list_item() {
         assert_eq!(
-            translate_list_elements(vec![
-                vec![Fragment::Plain("Foobar")],
-                vec![Fragment::Plain("Foobar")],
-                vec![Fragment::Plain("Foobar")],
-                vec![Fragment::Plain("Foobar")],
-            ]),
-            String::from("<li>Foobar</li><li>Foobar</li><li>Foobar</li><li>Foobar</li>")
+            HtmlRenderer.list_item(
+                ListKind::Unordered,
+                0,
+                0,
+                "Foobar".to_string(),
+                String::new()
+            ),
+            String::from("<li>Foobar</li>")
         );
     }
 
     #[test]
     fn test_translate_unordered_list() {
         assert_eq!(
-            translate_unordered_list(vec![
+            HtmlRenderer.render_block(&Block::UnorderedList(vec![
                 vec![Fragment::Plain("Foobar")],
                 vec![Fragment::Plain("Foobar")],
                 vec![Fragment::Plain("Foobar")],
                 vec![Fragment::Plain("Foobar")],
-            ]),
+            ])),
             String::from("<ul><li>Foobar</li><li>Foobar</li><li>Foobar</li><li>Foobar</li></ul>")
         );
     }
@@ -201,20 +347,78 @@ mod tests {
     #[test]
     fn test_translate_ordered_list() {
         assert_eq!(
-            translate_ordered_list(vec![
+            HtmlRenderer.render_block(&Block::OrderedList(vec![
                 vec![Fragment::Plain("Foobar")],
                 vec![Fragment::Plain("Foobar")],
                 vec![Fragment::Plain("Foobar")],
                 vec![Fragment::Plain("Foobar")],
-            ]),
+            ])),
             String::from("<ol><li>Foobar</li><li>Foobar</li><li>Foobar</li><li>Foobar</li></ol>")
         );
     }
 
+    #[test]
+    fn test_translate_nested_list() {
+        assert_eq!(
+            HtmlRenderer.render_list(
+                &List {
+                    kind: ListKind::Unordered,
+                    items: vec![
+                        ListItem::from((
+                            vec![Fragment::Plain("Foo")],
+                            Some(List {
+                                kind: ListKind::Ordered,
+                                items: vec![ListItem::from((vec![Fragment::Plain("Bar")], None))],
+                            }),
+                        )),
+                        ListItem::from((vec![Fragment::Plain("Baz")], None)),
+                    ],
+                },
+                0
+            ),
+            String::from("<ul><li>Foo<ol><li>Bar</li></ol></li><li>Baz</li></ul>")
+        );
+    }
+
+    #[test]
+    fn test_translate_block_quote() {
+        assert_eq!(
+            HtmlRenderer.render_block(&Block::BlockQuote(vec![
+                vec![Fragment::Plain("Foobar")],
+                vec![],
+                vec![Fragment::Bold(vec![Fragment::Plain("Foobar")])],
+            ])),
+            String::from("<blockquote><p>Foobar</p><p><b>Foobar</b></p></blockquote>")
+        );
+    }
+
+    #[test]
+    fn test_translate_horizontal_rule() {
+        assert_eq!(HtmlRenderer.horizontal_rule(), String::from("<hr />"));
+    }
+
+    #[test]
+    fn test_translate_table() {
+        assert_eq!(
+            HtmlRenderer.render_table(&Table {
+                header: vec![vec![Fragment::Plain("Name")], vec![Fragment::Plain("Age")]],
+                alignments: vec![Alignment::None, Alignment::Right],
+                rows: vec![vec![
+                    vec![Fragment::InlineCode("foo")],
+                    vec![Fragment::Plain("42")]
+                ]],
+            }),
+            String::from(
+                "<table><thead><tr><th>Name</th><th align=\"right\">Age</th></tr></thead>\
+                <tbody><tr><td><code>foo</code></td><td align=\"right\">42</td></tr></tbody></table>"
+            )
+        );
+    }
+
     #[test]
     fn test_translate_codeblock() {
         assert_eq!(
-            translate_codeblock(&CodeBlock::from(("python", raw_strings::CODE_BLOCK))),
+            HtmlRenderer.code_block(&CodeBlock::from(("python", raw_strings::CODE_BLOCK))),
             String::from(raw_strings::CODE_BLOCK_HTML)
         );
     }
@@ -242,12 +446,12 @@ foobar.singularize(\'phenomena\') # returns \'phenomenon\'
     #[test]
     fn test_translate_line() {
         assert_eq!(
-            translate_line(vec![
+            HtmlRenderer.render_block(&Block::Text(vec![
                 Fragment::Plain("Foobar"),
-                Fragment::Bold("Foobar"),
-                Fragment::Italic("Foobar"),
+                Fragment::Bold(vec![Fragment::Plain("Foobar")]),
+                Fragment::Italic(vec![Fragment::Plain("Foobar")]),
                 Fragment::InlineCode("Foobar"),
-            ]),
+            ])),
             String::from("<p>Foobar<b>Foobar</b><i>Foobar</i><code>Foobar</code></p>")
         );
     }
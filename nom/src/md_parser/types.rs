@@ -15,6 +15,8 @@
  *   limitations under the License.
  */
 
+use std::{borrow::Cow, collections::BTreeMap};
+
 /// This corresponds to a single Markdown document, which is produced after a successful parse
 /// operation [crate::parse_markdown].
 pub type Document<'a> = Vec<Block<'a>>;
@@ -36,22 +38,41 @@ pub enum Block<'a> {
     Heading((Level, Fragments<'a>)),
     OrderedList(Lines<'a>),
     UnorderedList(Lines<'a>),
+    /// Only lists that have at least one indented sub list end up here. Flat lists are
+    /// still parsed into [Block::OrderedList] or [Block::UnorderedList].
+    NestedList(List<'a>),
+    BlockQuote(Lines<'a>),
+    HorizontalRule,
+    Table(Table<'a>),
     Text(Fragments<'a>),
     CodeBlock(CodeBlock<'a>),
     Title(&'a str),
     Tags(Vec<&'a str>),
+    /// Any `@key: value` lines other than `@title` & `@tags`, or the YAML (`---`) or JSON
+    /// (`;;;`) front matter at the start of a document.
+    Metadata(Metadata<'a>),
 }
 
 /// These are things that show up in a single line of Markdown text [Fragments]. They do
 /// not include other Markdown blocks (like code blocks, lists, headings, etc).
+///
+/// The emphasis variants ([Fragment::Bold], [Fragment::Italic], etc) are recursive, since
+/// they can contain other fragments, eg: `**bold with `code` inside**`.
 #[derive(Clone, Debug, PartialEq)]
 pub enum Fragment<'a> {
     Link((&'a str, &'a str)),
     Image((&'a str, &'a str)),
+    /// Eg: `<https://r3bl.com>`. Holds the URL w/out the angle brackets.
+    Autolink(&'a str),
     InlineCode(&'a str),
-    Bold(&'a str),
-    BoldItalic(&'a str),
-    Italic(&'a str),
+    Bold(Fragments<'a>),
+    BoldItalic(Fragments<'a>),
+    Italic(Fragments<'a>),
+    Strikethrough(Fragments<'a>),
+    /// Eg: `\*`. Holds the escaped char w/out the backslash.
+    Escaped(&'a str),
+    /// Two or more spaces, or a backslash, at the end of a line. Holds the matched chars.
+    LineBreak(&'a str),
     Plain(&'a str),
 }
 
@@ -84,21 +105,42 @@ impl From<usize> for Level {
 pub mod constants {
     pub const TITLE: &str = "@title";
     pub const TAGS: &str = "@tags";
+    pub const AT: &str = "@";
+    pub const TITLE_KEY: &str = "title";
+    pub const TAGS_KEY: &str = "tags";
+    pub const DATE_KEY: &str = "date";
+    pub const DATE_FORMAT: &str = "%Y-%m-%d";
+    pub const FRONT_MATTER_YAML: &str = "---";
+    pub const FRONT_MATTER_JSON: &str = ";;;";
+    pub const YAML_COMMENT: &str = "#";
     pub const COLON: &str = ":";
     pub const COMMA: &str = ",";
     pub const QUOTE: &str = "\"";
+    pub const SINGLE_QUOTE: &str = "'";
     pub const HEADING_CHAR: char = '#';
     pub const UNKNOWN_LANGUAGE: &str = "__UNKNOWN_LANGUAGE__";
     pub const SPACE: &str = " ";
     pub const PERIOD: &str = ".";
     pub const UNORDERED_LIST: &str = "-";
+    pub const BLOCK_QUOTE: &str = ">";
+    pub const HORIZONTAL_RULE_CHARS: [char; 3] = ['-', '*', '_'];
+    pub const TABLE_PIPE: &str = "|";
+    pub const TABLE_ALIGN_COLON: char = ':';
+    pub const TABLE_DASH: char = '-';
     pub const BITALIC_1: &str = "***";
     pub const BITALIC_2: &str = "___";
     pub const BOLD_1: &str = "**";
     pub const BOLD_2: &str = "__";
     pub const ITALIC_1: &str = "*";
     pub const ITALIC_2: &str = "_";
+    pub const STRIKETHROUGH: &str = "~~";
     pub const BACKTICK: &str = "`";
+    pub const BACKSLASH: &str = "\\";
+    pub const LEFT_ANGLE: &str = "<";
+    pub const RIGHT_ANGLE: &str = ">";
+    pub const HARD_LINE_BREAK_SPACES: &str = "  ";
+    pub const LEFT_BRACE: &str = "{";
+    pub const RIGHT_BRACE: &str = "}";
     pub const LEFT_BRACKET: &str = "[";
     pub const RIGHT_BRACKET: &str = "]";
     pub const LEFT_PAREN: &str = "(";
@@ -124,3 +166,90 @@ mod code_block_impl {
         }
     }
 }
+
+/// A list that contains sub lists, eg:
+/// ```text
+/// - item 1
+///   - item 1.1
+///     1. item 1.1.1
+/// - item 2
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct List<'a> {
+    pub kind: ListKind,
+    pub items: Vec<ListItem<'a>>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ListKind {
+    Ordered,
+    Unordered,
+}
+
+/// A single item in a [List]. It may have a sub list that is indented further than itself.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ListItem<'a> {
+    pub content: Fragments<'a>,
+    pub children: Option<List<'a>>,
+}
+
+/// A GitHub flavored Markdown (GFM) pipe table, eg:
+/// ```text
+/// | Name | Age |
+/// |:-----|----:|
+/// | Foo  |  42 |
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Table<'a> {
+    pub header: Vec<Fragments<'a>>,
+    pub alignments: Vec<Alignment>,
+    pub rows: Vec<Vec<Fragments<'a>>>,
+}
+
+/// The alignment of a [Table] column, which comes from the delimiter row: `---`, `:--`,
+/// `:-:`, or `--:`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Alignment {
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+mod list_impl {
+    use super::*;
+
+    impl<'a> From<(Fragments<'a>, Option<List<'a>>)> for ListItem<'a> {
+        fn from((content, children): (Fragments<'a>, Option<List<'a>>)) -> Self {
+            ListItem { content, children }
+        }
+    }
+}
+
+/// Key value pairs, see [Block::Metadata]. The [MetadataSyntax] is kept so that the metadata can
+/// be printed back out the same way.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Metadata<'a> {
+    pub syntax: MetadataSyntax,
+    pub map: MetadataMap<'a>,
+}
+
+/// Keys & values only allocate when they have to be unescaped (eg: JSON strings w/ `\"`).
+pub type MetadataMap<'a> = BTreeMap<Cow<'a, str>, MetadataValue<'a>>;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MetadataSyntax {
+    /// `@key: value` lines.
+    AtKey,
+    /// Front matter between `---` lines.
+    Yaml,
+    /// Front matter between `;;;` lines.
+    Json,
+}
+
+/// Numbers & booleans are kept as [MetadataValue::Text], just like in the Markdown source.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MetadataValue<'a> {
+    Text(Cow<'a, str>),
+    List(Vec<Cow<'a, str>>),
+}
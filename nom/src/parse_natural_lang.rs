@@ -27,6 +27,9 @@
 //!
 //! Sentences of this form need to be parsed into a struct, one for each sentence. This example is
 //! inspired by this [video](https://gist.github.com/lmammino/0c3e7e6dbaf41303d1059c6a279c59d1).
+//!
+//! For a reusable version of this, where the sentences (commands) are declared in a grammar, see
+//! [crate::command_dsl].
 
 use nom::{
     branch::*, bytes::complete::*, character::complete::*, combinator::*, sequence::*, IResult,
@@ -51,7 +54,7 @@ mod parse_sentence {
 
     /// Sentence starts w/ "hello". Then optional whitespace. Then optional ",". Then optional
     /// whitespace.
-    pub fn root(input: &str) -> IResult</* remainder */ &str, Sentence> {
+    pub fn root(input: &str) -> IResult</* remainder */ &str, Sentence<'_>> {
         let (rem, _) = tuple((
             tag_no_case("hello"),
             multispace0,
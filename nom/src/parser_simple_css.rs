@@ -21,7 +21,7 @@
 //! 2. `#RRGGBBAA`, eg: `#FF0000FF` for red with alpha.
 //!
 //! Here are some examples of valid input strings:
-//! ```
+//! ```text
 //! style = {
 //!     fg_color: #FF0000;
 //!     bg_color: #FF0000FF;
@@ -29,6 +29,8 @@
 //! ```
 //! 1. The `fg_color` and `bg_color` are both optional.
 //! 2. The `style = {` and `}` are required.
+//!
+//! See [crate::style_sheet] for a full style sheet parser that isn't just a test.
 
 #[cfg(test)]
 mod tests {
@@ -118,14 +120,14 @@ mod tests {
     mod hex_color_parser_helper_fns {
         use super::*;
 
-        pub fn parse_single_hex_segment(input: &str) -> IResult<&str, u8, VError> {
+        pub fn parse_single_hex_segment(input: &str) -> IResult<&str, u8, VError<'_>> {
             map_res(
                 take_while_m_n(2, 2, |it: char| it.is_ascii_hexdigit()),
                 |it: &str| u8::from_str_radix(it, 16),
             )(input)
         }
 
-        pub fn parse_hex_color_no_alpha(input: &str) -> IResult<&str, Color, VError> {
+        pub fn parse_hex_color_no_alpha(input: &str) -> IResult<&str, Color, VError<'_>> {
             let (input, _) = tag("#")(input)?;
             let (input, (red, green, blue)) = tuple((
                 parse_single_hex_segment,
@@ -136,7 +138,7 @@ mod tests {
             Ok((input, Color::NoAlpha(ColorNoAlpha::new(red, green, blue))))
         }
 
-        pub fn parse_hex_color_with_alpha(input: &str) -> IResult<&str, Color, VError> {
+        pub fn parse_hex_color_with_alpha(input: &str) -> IResult<&str, Color, VError<'_>> {
             let (input, _) = tag("#")(input)?;
             let (input, (red, green, blue, alpha)) = tuple((
                 parse_single_hex_segment,
@@ -160,7 +162,7 @@ mod tests {
         /// Parse `style = { bg_color: .. , fg_color: .. }` parser.
         pub fn parse_style(
             input: &str,
-        ) -> IResult<&str, Option<HashMap<ColorKind, Color>>, VError> {
+        ) -> IResult<&str, Option<HashMap<ColorKind, Color>>, VError<'_>> {
             // Parse `style = {`.
             let (input, _) = tuple((
                 tag("style"),
@@ -191,7 +193,7 @@ mod tests {
         /// Parse `<key> : <val> ;`, where:
         /// 1. `<key>` can be `fg_color` or `bg_color`.
         /// 2. `<val>` can be `#RRGGBB` or `#RRGGBBAA`.
-        pub fn parse_color_key_value(input: &str) -> IResult<&str, (ColorKind, Color), VError> {
+        pub fn parse_color_key_value(input: &str) -> IResult<&str, (ColorKind, Color), VError<'_>> {
             // Parse `fg_color` or `bg_color`.
             let (input, key_str) = alt((tag("fg_color"), tag("bg_color")))(input)?;
 
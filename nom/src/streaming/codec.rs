@@ -0,0 +1,352 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! [Decoder]s that wrap the streaming parsers, so they can be used w/
+//! [tokio_util::codec::FramedRead], eg: `FramedRead::new(socket, HexColorCodec::default())`.
+
+use crate::{md_parser::constants::NEW_LINE, streaming::*, style_sheet::Color, *};
+use bytes::{Buf, BytesMut};
+use nom::{error::ErrorKind, Err as NomErr, IResult};
+use thiserror::Error;
+use tokio_util::codec::Decoder;
+
+#[derive(Error, Debug)]
+pub enum DecodeError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("Could not parse the {item} at byte {offset} of the stream ({kind:?})")]
+    Parse {
+        item: &'static str,
+        offset: usize,
+        kind: ErrorKind,
+    },
+
+    #[error("The line at byte {offset} of the stream is longer than {max_length} bytes")]
+    LineTooLong { offset: usize, max_length: usize },
+}
+
+/// A line of Markdown text from [MarkdownLineCodec]. The [Fragments] borrow from the line, so
+/// they are parsed (again) by [MarkdownLine::fragments].
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarkdownLine {
+    /// Includes the new line.
+    pub line: String,
+}
+
+impl MarkdownLine {
+    /// The line w/out its new line.
+    pub fn text(&self) -> &str {
+        self.line.strip_suffix(NEW_LINE).unwrap_or(&self.line)
+    }
+
+    /// The codec only produces lines that parse, so this never falls back to an empty [Vec].
+    pub fn fragments(&self) -> Fragments<'_> {
+        parse_block_markdown_text_until_eol(&self.line)
+            .map(|(_, fragments)| fragments)
+            .unwrap_or_default()
+    }
+}
+
+/// Decodes a stream of bytes into [MarkdownLine]s w/ [parse_markdown_line_streaming]. At the
+/// end of the stream, the last line doesn't need a new line.
+///
+/// A line is only parsed once its new line has arrived, and (like
+/// [tokio_util::codec::LinesCodec]) the search for the new line picks up where the last one
+/// left off. So a long line that arrives a few bytes at a time is scanned once, instead of once
+/// per chunk. Lines longer than [MarkdownLineCodec::max_length] are a
+/// [DecodeError::LineTooLong], so a peer that never sends a new line can't grow the buffer w/out
+/// bound.
+#[derive(Clone, Debug)]
+pub struct MarkdownLineCodec {
+    /// Number of bytes decoded so far, to report where an error is in the stream.
+    consumed: usize,
+    /// Index in the buffer to resume the search for the next new line from.
+    next_index: usize,
+    /// Max number of bytes in a line, not counting its new line.
+    max_length: usize,
+}
+
+impl MarkdownLineCodec {
+    pub const DEFAULT_MAX_LENGTH: usize = 64 * 1024;
+
+    pub fn new_with_max_length(max_length: usize) -> Self {
+        MarkdownLineCodec {
+            consumed: 0,
+            next_index: 0,
+            max_length,
+        }
+    }
+
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+}
+
+impl Default for MarkdownLineCodec {
+    fn default() -> Self {
+        Self::new_with_max_length(Self::DEFAULT_MAX_LENGTH)
+    }
+}
+
+impl Decoder for MarkdownLineCodec {
+    type Item = MarkdownLine;
+    type Error = DecodeError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        // Only look at the bytes that haven't been searched yet, up to the max length.
+        let new_line = NEW_LINE.as_bytes()[0];
+        let search_end = src.len().min(self.max_length.saturating_add(1));
+        let has_new_line = src[self.next_index.min(search_end)..search_end].contains(&new_line);
+        if !has_new_line {
+            if src.len() > self.max_length {
+                return Err(DecodeError::LineTooLong {
+                    offset: self.consumed,
+                    max_length: self.max_length,
+                });
+            }
+            self.next_index = src.len();
+            return Ok(None);
+        }
+        self.next_index = 0;
+
+        decode_w_parser(src, &mut self.consumed, "Markdown line", |input| {
+            let (rest, _) = parse_markdown_line_streaming(input)?;
+            let line = &input[..input.len() - rest.len()];
+            let line = String::from_utf8_lossy(line).into_owned();
+            Ok((rest, MarkdownLine { line }))
+        })
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match self.decode(src)? {
+            Some(it) => Ok(Some(it)),
+            None if src.is_empty() => Ok(None),
+            None => {
+                src.extend_from_slice(NEW_LINE.as_bytes());
+                self.decode(src)
+            }
+        }
+    }
+}
+
+/// Decodes a stream of bytes into [Color]s w/ [parse_hex_color_streaming]. The colors can be
+/// separated by whitespace, eg: `#FF0000 #00FF00\n#0000FF`.
+#[derive(Clone, Debug, Default)]
+pub struct HexColorCodec {
+    /// Number of bytes decoded so far, to report where an error is in the stream.
+    consumed: usize,
+}
+
+impl Decoder for HexColorCodec {
+    type Item = Color;
+    type Error = DecodeError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let whitespace = src.iter().take_while(|it| it.is_ascii_whitespace()).count();
+        src.advance(whitespace);
+        self.consumed += whitespace;
+
+        decode_w_parser(
+            src,
+            &mut self.consumed,
+            "hex color",
+            parse_hex_color_streaming,
+        )
+    }
+}
+
+/// Run `parser` on `src`, and remove the bytes that it consumed. [NomErr::Incomplete] means
+/// that more bytes are needed, which is [None] for a [Decoder].
+fn decode_w_parser<O>(
+    src: &mut BytesMut,
+    consumed: &mut usize,
+    item: &'static str,
+    parser: impl FnOnce(&[u8]) -> IResult<&[u8], O>,
+) -> Result<Option<O>, DecodeError> {
+    if src.is_empty() {
+        return Ok(None);
+    }
+    match parser(src) {
+        Ok((rest, output)) => {
+            let length = src.len() - rest.len();
+            src.advance(length);
+            *consumed += length;
+            Ok(Some(output))
+        }
+        Err(NomErr::Incomplete(_)) => Ok(None),
+        Err(NomErr::Error(error) | NomErr::Failure(error)) => Err(DecodeError::Parse {
+            item,
+            offset: *consumed + src.len() - error.input.len(),
+            kind: error.code,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed `chunks` to `decoder` one at a time, like they'd arrive from a socket, and then
+    /// end the stream.
+    fn decode_chunks<D: Decoder>(
+        mut decoder: D,
+        chunks: &[&[u8]],
+    ) -> Result<Vec<D::Item>, D::Error> {
+        let mut src = BytesMut::new();
+        let mut items = vec![];
+        for chunk in chunks {
+            src.extend_from_slice(chunk);
+            while let Some(it) = decoder.decode(&mut src)? {
+                items.push(it);
+            }
+        }
+        while let Some(it) = decoder.decode_eof(&mut src)? {
+            items.push(it);
+        }
+        Ok(items)
+    }
+
+    /// Split `input` at every byte boundary (into 2 chunks), and also into 1 byte chunks.
+    fn assert_decodes_at_every_split<D: Decoder + Clone>(
+        decoder: D,
+        input: &[u8],
+        expected: &[D::Item],
+    ) where
+        D::Item: PartialEq + std::fmt::Debug,
+        D::Error: std::fmt::Debug,
+    {
+        for index in 0..=input.len() {
+            let items = decode_chunks(decoder.clone(), &[&input[..index], &input[index..]]);
+            assert_eq!(items.unwrap(), expected, "split at: {index}");
+        }
+        let bytes: Vec<&[u8]> = input.chunks(1).collect();
+        assert_eq!(decode_chunks(decoder, &bytes).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_markdown_line_codec() {
+        let line = |it: &str| MarkdownLine {
+            line: it.to_string(),
+        };
+        assert_decodes_at_every_split(
+            MarkdownLineCodec::default(),
+            "# **Hi** é\n\n[link](url) 🦀\nno new line".as_bytes(),
+            &[
+                line("# **Hi** é\n"),
+                line("\n"),
+                line("[link](url) 🦀\n"),
+                line("no new line\n"),
+            ],
+        );
+
+        let it = line("**Hi** `there`\n");
+        assert_eq!(it.text(), "**Hi** `there`");
+        assert_eq!(
+            it.fragments(),
+            vec![
+                Fragment::Bold(vec![Fragment::Plain("Hi")]),
+                Fragment::Plain(" "),
+                Fragment::InlineCode("there"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_markdown_line_codec_max_length() {
+        let line = |it: &str| MarkdownLine {
+            line: it.to_string(),
+        };
+
+        // The new line doesn't count towards the max length.
+        assert_eq!(
+            decode_chunks(
+                MarkdownLineCodec::new_with_max_length(3),
+                &[b"abc\n", b"d\n"]
+            )
+            .unwrap(),
+            vec![line("abc\n"), line("d\n")]
+        );
+
+        // A peer that never sends a new line.
+        let mut codec = MarkdownLineCodec::new_with_max_length(4);
+        let mut src = BytesMut::new();
+        src.extend_from_slice(b"ok\n");
+        assert_eq!(codec.decode(&mut src).unwrap(), Some(line("ok\n")));
+        for chunk in [b"ab", b"cd"] {
+            src.extend_from_slice(chunk);
+            assert_eq!(codec.decode(&mut src).unwrap(), None);
+        }
+        src.extend_from_slice(b"e");
+        assert!(matches!(
+            codec.decode(&mut src),
+            Err(DecodeError::LineTooLong {
+                offset: 3,
+                max_length: 4
+            })
+        ));
+
+        // A long line that arrives 1 byte at a time, still decodes.
+        let input = format!("{}\n", "a".repeat(1000));
+        let bytes: Vec<&[u8]> = input.as_bytes().chunks(1).collect();
+        assert_eq!(
+            decode_chunks(MarkdownLineCodec::default(), &bytes).unwrap(),
+            vec![line(&input)]
+        );
+    }
+
+    #[test]
+    fn test_hex_color_codec() {
+        assert_decodes_at_every_split(
+            HexColorCodec::default(),
+            b"#FF0000 #00ff00\n#0000FF\n",
+            &[
+                Color::rgb(255, 0, 0),
+                Color::rgb(0, 255, 0),
+                Color::rgb(0, 0, 255),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let error = decode_chunks(HexColorCodec::default(), &[b"#FF0000\n#FFXX00"]).unwrap_err();
+        assert!(matches!(
+            error,
+            DecodeError::Parse {
+                item: "hex color",
+                offset: 11,
+                kind: ErrorKind::TakeWhileMN
+            }
+        ));
+
+        // An incomplete color at the end of the stream.
+        let error = decode_chunks(HexColorCodec::default(), &[b"#FF00"]).unwrap_err();
+        assert!(matches!(error, DecodeError::Io(_)));
+
+        let error =
+            decode_chunks(MarkdownLineCodec::default(), &[b"ok\n", b"a **b\n"]).unwrap_err();
+        assert!(matches!(
+            error,
+            DecodeError::Parse {
+                item: "Markdown line",
+                offset: 5,
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,27 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Parsers for input that arrives in chunks, eg: from a socket or a PTY. Unlike the rest of the
+//! crate, these use `nom::*::streaming`, so they return [nom::Err::Incomplete] (instead of an
+//! error) when `input` ends before the item does. The [Decoder](tokio_util::codec::Decoder)s in
+//! [codec] wrap them.
+
+pub mod codec;
+pub mod parser;
+
+pub use codec::*;
+pub use parser::*;
@@ -0,0 +1,147 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use crate::{md_parser::constants::NEW_LINE, style_sheet::Color, *};
+use nom::{
+    bytes::streaming::{tag, take_until, take_while_m_n},
+    character::is_hex_digit,
+    combinator::{map_res, recognize},
+    error::Error,
+    sequence::{terminated, tuple},
+    Err as NomErr, IResult,
+};
+
+/// Streaming version of [parse_block_markdown_text_until_eol]. Returns [NomErr::Incomplete]
+/// until the whole line (including its new line) is in `input`. The line is split into
+/// [Fragments] by the same (complete) parser, so both produce the same output.
+///
+/// Since a new line byte can't show up inside of a multi byte UTF-8 char, `input` can be cut
+/// off at any byte, and the line is only decoded once it is complete.
+pub fn parse_markdown_line_streaming(input: &[u8]) -> IResult<&[u8], Fragments<'_>> {
+    let (rest, line) = map_res(
+        recognize(terminated(
+            take_until(NEW_LINE.as_bytes()),
+            tag(NEW_LINE.as_bytes()),
+        )),
+        std::str::from_utf8,
+    )(input)?;
+
+    match parse_block_markdown_text_until_eol(line) {
+        Ok((_, fragments)) => Ok((rest, fragments)),
+        // Point the error at the same place in `input`.
+        Err(NomErr::Error(error) | NomErr::Failure(error)) => {
+            let offset = line.len() - error.input.len();
+            Err(NomErr::Error(Error::new(&input[offset..], error.code)))
+        }
+        Err(NomErr::Incomplete(needed)) => Err(NomErr::Incomplete(needed)),
+    }
+}
+
+/// Streaming version of the `#RRGGBB` parser in [crate::parser_simple_hex]. Returns
+/// [NomErr::Incomplete] until all 7 bytes are in `input`.
+pub fn parse_hex_color_streaming(input: &[u8]) -> IResult<&[u8], Color> {
+    let (input, (_, r, g, b)) = tuple((
+        tag("#"),
+        parse_hex_segment_streaming,
+        parse_hex_segment_streaming,
+        parse_hex_segment_streaming,
+    ))(input)?;
+    Ok((input, Color::rgb(r, g, b)))
+}
+
+fn parse_hex_segment_streaming(input: &[u8]) -> IResult<&[u8], u8> {
+    map_res(
+        map_res(take_while_m_n(2, 2, is_hex_digit), std::str::from_utf8),
+        |it| u8::from_str_radix(it, 16),
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::{error::ErrorKind, Needed};
+
+    #[test]
+    fn test_parse_markdown_line_streaming() {
+        let input = "**bold** and `code` é\nnext".as_bytes();
+        let line_length = input.iter().position(|it| *it == b'\n').unwrap() + 1;
+
+        // Every cut before the end of the line is incomplete.
+        for index in 0..line_length {
+            assert!(
+                matches!(
+                    parse_markdown_line_streaming(&input[..index]),
+                    Err(NomErr::Incomplete(_))
+                ),
+                "index: {index}"
+            );
+        }
+
+        // Once the line is complete, the output is the same as the complete parser's.
+        for index in line_length..=input.len() {
+            let (rest, fragments) = parse_markdown_line_streaming(&input[..index]).unwrap();
+            assert_eq!(rest, &input[line_length..index]);
+            assert_eq!(
+                fragments,
+                parse_block_markdown_text_until_eol("**bold** and `code` é\n")
+                    .unwrap()
+                    .1
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_markdown_line_streaming_errors() {
+        // Not UTF-8.
+        assert_eq!(
+            parse_markdown_line_streaming(b"\xff\n"),
+            Err(NomErr::Error(Error::new(&b"\xff\n"[..], ErrorKind::MapRes)))
+        );
+
+        // Can't be parsed by the complete parser.
+        let input = b"a **b\n";
+        assert_eq!(
+            parse_markdown_line_streaming(input),
+            Err(NomErr::Error(Error::new(&input[2..], ErrorKind::Tag)))
+        );
+    }
+
+    #[test]
+    fn test_parse_hex_color_streaming() {
+        let input = b"#2F14DF;";
+        for index in 0..7 {
+            assert!(
+                matches!(
+                    parse_hex_color_streaming(&input[..index]),
+                    Err(NomErr::Incomplete(Needed::Size(_)))
+                ),
+                "index: {index}"
+            );
+        }
+        assert_eq!(
+            parse_hex_color_streaming(input),
+            Ok((&b";"[..], Color::rgb(0x2F, 0x14, 0xDF)))
+        );
+        assert_eq!(
+            parse_hex_color_streaming(b"#2G"),
+            Err(NomErr::Error(Error::new(
+                &b"2G"[..],
+                ErrorKind::TakeWhileMN
+            )))
+        );
+    }
+}
@@ -0,0 +1,135 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use crate::style_sheet::*;
+use miette::{Diagnostic, SourceSpan};
+use nom::error::VerboseErrorKind;
+use thiserror::Error;
+
+/// Returned by [parse_style_sheet]. It holds a copy of the source, so it can be rendered w/
+/// [miette] on its own, eg: `println!("{:?}", miette::Report::new(error))`.
+#[derive(Error, Diagnostic, Clone, Debug, PartialEq)]
+#[error("Could not parse the style sheet, expected {expected}")]
+#[diagnostic(code(style_sheet::parse_error))]
+pub struct StyleSheetError {
+    pub expected: String,
+    #[source_code]
+    pub source_code: String,
+    #[label("here")]
+    pub span: SourceSpan,
+    /// The contexts that the error happened in, eg: `in a color`.
+    #[help]
+    pub help: Option<String>,
+}
+
+impl StyleSheetError {
+    /// The first entry in the [VError] is where parsing failed, and the contexts that it bubbled
+    /// up through follow it. The innermost context describes what was expected (if there's no
+    /// context then the failed char or parser is used instead).
+    pub fn new(source: &str, error: VError<'_>) -> Self {
+        let offset = |input: &str| source.len() - input.len();
+
+        let (failed_input, failed_kind) = match error.errors.first() {
+            Some((input, kind)) => (*input, kind.clone()),
+            None => (source, VerboseErrorKind::Context("valid input")),
+        };
+        let mut contexts = error.errors.iter().filter_map(|(_, kind)| match kind {
+            VerboseErrorKind::Context(it) => Some(*it),
+            _ => None,
+        });
+
+        let expected = match (&failed_kind, contexts.next()) {
+            (_, Some(context)) => context.to_string(),
+            (VerboseErrorKind::Char(it), None) => format!("`{it}`"),
+            (VerboseErrorKind::Nom(it), None) => it.description().to_lowercase(),
+            (VerboseErrorKind::Context(it), None) => it.to_string(),
+        };
+        let help = contexts
+            .map(|it| format!("in {it}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let length = failed_input.chars().next().map_or(0, char::len_utf8);
+
+        StyleSheetError {
+            expected,
+            source_code: source.to_string(),
+            span: (offset(failed_input), length).into(),
+            help: (!help.is_empty()).then_some(help),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miette::{GraphicalReportHandler, GraphicalTheme};
+
+    fn error(input: &str) -> StyleSheetError {
+        parse_style_sheet(input).unwrap_err()
+    }
+
+    #[test]
+    fn test_error_points_at_the_problem() {
+        let it = error("a {\n  fg_color: #12345;\n}");
+        assert_eq!(it.expected, "3, 4, 6, or 8 hex digits");
+        assert_eq!(it.span, (17, 1).into());
+        assert_eq!(it.help.as_deref(), Some("in a color"));
+
+        let it = error("a { fg_color: red }");
+        assert_eq!(it.expected, "`;`");
+        assert_eq!(it.span, (18, 1).into());
+
+        let it = error("a { fg: red; }");
+        assert_eq!(
+            it.expected,
+            "a property (`fg_color`, `bg_color`, or `attributes`) or `}`"
+        );
+        assert_eq!(it.span, (4, 1).into());
+
+        let it = error("a { bg_color: rgba(1, 2, 3); }");
+        assert_eq!(it.expected, "`,`");
+
+        let it = error("a { attributes: blink; }");
+        assert_eq!(
+            it.expected,
+            "an attribute (`bold`, `italic`, `underline`, or `dim`)"
+        );
+
+        let it = error("a, { }");
+        assert_eq!(it.expected, "`{`");
+        assert_eq!(it.span, (1, 1).into());
+
+        let it = error("a { } 42 { }");
+        assert_eq!(it.expected, "a selector");
+        assert_eq!(it.span, (6, 1).into());
+
+        let it = error("a {");
+        assert_eq!(it.span, (3, 0).into());
+    }
+
+    #[test]
+    fn test_error_renders_w_miette() {
+        let mut rendered = String::new();
+        GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+            .render_report(&mut rendered, &error("a {\n  fg_color: nope;\n}"))
+            .unwrap();
+        assert!(rendered.contains("style_sheet::parse_error"));
+        assert!(rendered.contains("expected a color"));
+        assert!(rendered.contains("fg_color: nope;"));
+        assert!(rendered.contains("here"));
+    }
+}
@@ -0,0 +1,36 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! A parser for CSS like style sheets, which is the grown up version of
+//! [crate::parser_simple_css]. The main entry point is [parse_style_sheet], which returns a
+//! [StyleSheet] that can be looked up by selector name. Eg:
+//! ```text
+//! /* Comments can be block comments, or line comments. */
+//! heading, title {
+//!     fg_color: #F80;                 // #RGB, #RGBA, #RRGGBB, or #RRGGBBAA.
+//!     bg_color: rgba(0, 0, 0, 0.5);   // Or rgb(r, g, b), or a named color like `navy`.
+//!     attributes: bold underline;     // bold, italic, underline, and dim.
+//! }
+//! ```
+
+pub mod error;
+pub mod parser;
+pub mod types;
+
+pub use error::*;
+pub use parser::*;
+pub use types::*;
@@ -0,0 +1,450 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use crate::style_sheet::*;
+use constants::*;
+use nom::{
+    branch::*, bytes::complete::*, character::complete::*, combinator::*, error::context, multi::*,
+    number::complete::double, sequence::*, Err as NomErr, IResult,
+};
+
+/// Type alias for [nom::error::VerboseError] to make the code more readable. The contexts in it
+/// are used to build a [StyleSheetError].
+pub type VError<'input> = nom::error::VerboseError<&'input str>;
+
+/// The main entry point to parse a style sheet. Once a rule has started (ie: after its
+/// selectors), the parser doesn't backtrack, so the error points at what is actually wrong.
+pub fn parse_style_sheet(input: &str) -> Result<StyleSheet, StyleSheetError> {
+    let rules = match parse_rules(input) {
+        Ok((_, rules)) => rules,
+        Err(NomErr::Error(error) | NomErr::Failure(error)) => {
+            return Err(StyleSheetError::new(input, error))
+        }
+        Err(NomErr::Incomplete(_)) => unreachable!("Only complete parsers are used"),
+    };
+
+    let mut style_sheet = StyleSheet::default();
+    for Rule {
+        selectors,
+        declarations,
+    } in rules
+    {
+        for selector in selectors {
+            let style = style_sheet.styles.entry(selector.to_string()).or_default();
+            for declaration in &declarations {
+                style.apply(*declaration);
+            }
+        }
+    }
+    Ok(style_sheet)
+}
+
+/// Parse all the rules, up to the end of `input`.
+pub fn parse_rules(input: &str) -> IResult<&str, Vec<Rule<'_>>, VError<'_>> {
+    preceded(
+        parse_whitespace_and_comments,
+        terminated(
+            many0(terminated(parse_rule, parse_whitespace_and_comments)),
+            context("a selector", eof),
+        ),
+    )(input)
+}
+
+/// Parse `<selector>, <selector> { <declaration> ... }`. The `=` of the old `style = { .. }`
+/// syntax is optional.
+pub fn parse_rule(input: &str) -> IResult<&str, Rule<'_>, VError<'_>> {
+    let (input, selectors) = separated_list1(
+        tuple((
+            parse_whitespace_and_comments,
+            char(','),
+            parse_whitespace_and_comments,
+        )),
+        parse_identifier,
+    )(input)?;
+
+    let (input, _) = cut(tuple((
+        parse_whitespace_and_comments,
+        opt(char('=')),
+        parse_whitespace_and_comments,
+        context("`{`", char('{')),
+        parse_whitespace_and_comments,
+    )))(input)?;
+
+    let (input, declarations) =
+        many0(terminated(parse_declaration, parse_whitespace_and_comments))(input)?;
+
+    let (input, _) = cut(context(
+        "a property (`fg_color`, `bg_color`, or `attributes`) or `}`",
+        char('}'),
+    ))(input)?;
+
+    Ok((
+        input,
+        Rule {
+            selectors,
+            declarations,
+        },
+    ))
+}
+
+/// Parse `<property>: <value>;`.
+pub fn parse_declaration(input: &str) -> IResult<&str, Declaration, VError<'_>> {
+    terminated(
+        alt((
+            preceded(
+                parse_property_name(FG_COLOR),
+                cut(map(parse_color, Declaration::FgColor)),
+            ),
+            preceded(
+                parse_property_name(BG_COLOR),
+                cut(map(parse_color, Declaration::BgColor)),
+            ),
+            preceded(
+                parse_property_name(ATTRIBUTES),
+                cut(map(parse_attributes, Declaration::Attributes)),
+            ),
+        )),
+        cut(pair(
+            parse_whitespace_and_comments,
+            context("`;`", char(';')),
+        )),
+    )(input)
+}
+
+/// Parse `<name>:` (and the whitespace around the colon).
+fn parse_property_name<'a>(
+    name: &'static str,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (), VError<'a>> {
+    value(
+        (),
+        tuple((
+            verify(parse_identifier, move |it: &str| it == name),
+            parse_whitespace_and_comments,
+            char(':'),
+            parse_whitespace_and_comments,
+        )),
+    )
+}
+
+/// Parse `#RGB`, `#RGBA`, `#RRGGBB`, `#RRGGBBAA`, `rgb(r, g, b)`, `rgba(r, g, b, a)`, or a
+/// named color (see [NAMED_COLORS]).
+pub fn parse_color(input: &str) -> IResult<&str, Color, VError<'_>> {
+    context(
+        "a color",
+        alt((
+            parse_hex_color,
+            parse_rgb_function,
+            map_opt(parse_identifier, Color::from_name),
+        )),
+    )(input)
+}
+
+pub fn parse_hex_color(input: &str) -> IResult<&str, Color, VError<'_>> {
+    preceded(
+        tag(HASH),
+        cut(context(
+            "3, 4, 6, or 8 hex digits",
+            map_opt(
+                take_while1(|it: char| it.is_ascii_hexdigit()),
+                hex_digits_to_color,
+            ),
+        )),
+    )(input)
+}
+
+/// The short forms (`#RGB` & `#RGBA`) repeat each digit, eg: `#F80` is `#FF8800`.
+fn hex_digits_to_color(digits: &str) -> Option<Color> {
+    let channels: Vec<u8> = match digits.len() {
+        3 | 4 => digits
+            .chars()
+            .map(|it| it.to_digit(16).map(|it| it as u8 * 17))
+            .collect::<Option<_>>()?,
+        6 | 8 => (0..digits.len())
+            .step_by(2)
+            .map(|index| u8::from_str_radix(&digits[index..index + 2], 16).ok())
+            .collect::<Option<_>>()?,
+        _ => return None,
+    };
+    Some(match channels[..] {
+        [r, g, b] => Color::rgb(r, g, b),
+        [r, g, b, a] => Color::rgba(r, g, b, a),
+        _ => return None,
+    })
+}
+
+/// Parse `rgb(r, g, b)` or `rgba(r, g, b, a)`, where `r`, `g`, & `b` are from 0 to 255, and `a`
+/// is from 0 to 1 (or a percentage).
+pub fn parse_rgb_function(input: &str) -> IResult<&str, Color, VError<'_>> {
+    let separator = || {
+        tuple((
+            parse_whitespace_and_comments,
+            context("`,`", char(',')),
+            parse_whitespace_and_comments,
+        ))
+    };
+
+    let (input, name) = alt((tag(RGBA), tag(RGB)))(input)?;
+    let (input, (_, _, r, _, g, _, b)) = cut(tuple((
+        context("`(`", char('(')),
+        parse_whitespace_and_comments,
+        parse_color_channel,
+        separator(),
+        parse_color_channel,
+        separator(),
+        parse_color_channel,
+    )))(input)?;
+    let (input, a) = match name {
+        RGBA => cut(preceded(separator(), parse_alpha_channel))(input)?,
+        _ => (input, u8::MAX),
+    };
+    let (input, _) = cut(pair(
+        parse_whitespace_and_comments,
+        context("`)`", char(')')),
+    ))(input)?;
+
+    Ok((input, Color::rgba(r, g, b, a)))
+}
+
+fn parse_color_channel(input: &str) -> IResult<&str, u8, VError<'_>> {
+    context(
+        "a number from 0 to 255",
+        map_res(digit1, |it: &str| it.parse::<u8>()),
+    )(input)
+}
+
+/// Eg: `0.5` or `50%`, which are both turned into 128.
+fn parse_alpha_channel(input: &str) -> IResult<&str, u8, VError<'_>> {
+    let to_u8 = |fraction: f64| {
+        (0.0..=1.0)
+            .contains(&fraction)
+            .then(|| (fraction * 255.0).round() as u8)
+    };
+    context(
+        "an alpha from 0 to 1, or a percentage",
+        alt((
+            map_opt(terminated(double, tag(PERCENT)), move |it| {
+                to_u8(it / 100.0)
+            }),
+            map_opt(double, to_u8),
+        )),
+    )(input)
+}
+
+/// Parse a list of attributes separated by whitespace or commas, eg: `bold, underline`.
+pub fn parse_attributes(input: &str) -> IResult<&str, Attributes, VError<'_>> {
+    let (input, names) = separated_list1(
+        alt((
+            recognize(tuple((
+                parse_whitespace_and_comments,
+                char(','),
+                parse_whitespace_and_comments,
+            ))),
+            multispace1,
+        )),
+        context(
+            "an attribute (`bold`, `italic`, `underline`, or `dim`)",
+            verify(parse_identifier, |it: &str| {
+                [BOLD, ITALIC, UNDERLINE, DIM].contains(&it)
+            }),
+        ),
+    )(input)?;
+
+    let mut attributes = Attributes::default();
+    for name in names {
+        match name {
+            BOLD => attributes.bold = true,
+            ITALIC => attributes.italic = true,
+            UNDERLINE => attributes.underline = true,
+            _ => attributes.dim = true,
+        }
+    }
+    Ok((input, attributes))
+}
+
+/// Selectors, properties, & named colors, eg: `code_block`, `fg_color`, or `red`.
+pub fn parse_identifier(input: &str) -> IResult<&str, &str, VError<'_>> {
+    recognize(pair(
+        alt((alpha1, tag("_"))),
+        many0_count(alt((alphanumeric1, tag("_"), tag("-")))),
+    ))(input)
+}
+
+/// Skip over whitespace, `/* block */` comments, and `// line` comments.
+pub fn parse_whitespace_and_comments(input: &str) -> IResult<&str, (), VError<'_>> {
+    value(
+        (),
+        many0_count(alt((
+            multispace1,
+            recognize(tuple((
+                tag(BLOCK_COMMENT_START),
+                cut(context("`*/`", take_until(BLOCK_COMMENT_END))),
+                tag(BLOCK_COMMENT_END),
+            ))),
+            recognize(pair(tag(LINE_COMMENT), not_line_ending)),
+        ))),
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#F80"), Ok(("", Color::rgb(255, 136, 0))));
+        assert_eq!(
+            parse_hex_color("#F808;"),
+            Ok((";", Color::rgba(255, 136, 0, 136)))
+        );
+        assert_eq!(parse_hex_color("#00ff00"), Ok(("", Color::rgb(0, 255, 0))));
+        assert_eq!(
+            parse_hex_color("#00FF0080"),
+            Ok(("", Color::rgba(0, 255, 0, 128)))
+        );
+        assert!(matches!(parse_hex_color("#12345"), Err(NomErr::Failure(_))));
+        assert!(matches!(parse_hex_color("F80"), Err(NomErr::Error(_))));
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("rgb(1, 2,3)"), Ok(("", Color::rgb(1, 2, 3))));
+        assert_eq!(
+            parse_color("rgba( 1 , 2 , 3 , 0.5 )"),
+            Ok(("", Color::rgba(1, 2, 3, 128)))
+        );
+        assert_eq!(
+            parse_color("rgba(1, 2, 3, 25%)"),
+            Ok(("", Color::rgba(1, 2, 3, 64)))
+        );
+        assert_eq!(parse_color("Orange;"), Ok((";", Color::rgb(255, 165, 0))));
+        assert!(matches!(
+            parse_color("rgb(1, 2, 256)"),
+            Err(NomErr::Failure(_))
+        ));
+        assert!(matches!(
+            parse_color("rgba(1, 2, 3, 2)"),
+            Err(NomErr::Failure(_))
+        ));
+        assert!(matches!(parse_color("rgb(1, 2)"), Err(NomErr::Failure(_))));
+        assert!(matches!(parse_color("not_a_color"), Err(NomErr::Error(_))));
+    }
+
+    #[test]
+    fn test_parse_attributes() {
+        assert_eq!(
+            parse_attributes("bold underline;"),
+            Ok((
+                ";",
+                Attributes {
+                    bold: true,
+                    underline: true,
+                    ..Default::default()
+                }
+            ))
+        );
+        assert_eq!(
+            parse_attributes("italic, dim ;"),
+            Ok((
+                " ;",
+                Attributes {
+                    italic: true,
+                    dim: true,
+                    ..Default::default()
+                }
+            ))
+        );
+        assert!(parse_attributes("blink").is_err());
+    }
+
+    #[test]
+    fn test_parse_whitespace_and_comments() {
+        assert_eq!(
+            parse_whitespace_and_comments(" /* a\n b */ // c\n\td"),
+            Ok(("d", ()))
+        );
+        assert!(matches!(
+            parse_whitespace_and_comments("/* a"),
+            Err(NomErr::Failure(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_style_sheet() {
+        let input = r#"
+            /* Headings. */
+            heading, title {
+                fg_color: #FF0000;
+                attributes: bold underline;
+            }
+
+            // The old syntax still works.
+            style = {
+                fg_color: #FF0000;
+                bg_color: #00FF00FF;
+            }
+
+            code_block { bg_color: rgba(0, 0, 0, 50%); attributes: dim; }
+            title { fg_color: white; }
+        "#;
+        let style_sheet = parse_style_sheet(input).unwrap();
+        assert_eq!(style_sheet.styles.len(), 4);
+
+        let bold_underline = Attributes {
+            bold: true,
+            underline: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            style_sheet.get("heading"),
+            Some(&Style {
+                fg_color: Some(Color::rgb(255, 0, 0)),
+                bg_color: None,
+                attributes: bold_underline,
+            })
+        );
+        // Later rules override earlier ones.
+        assert_eq!(
+            style_sheet.get("title"),
+            Some(&Style {
+                fg_color: Some(Color::rgb(255, 255, 255)),
+                bg_color: None,
+                attributes: bold_underline,
+            })
+        );
+        assert_eq!(
+            style_sheet.get("style"),
+            Some(&Style {
+                fg_color: Some(Color::rgb(255, 0, 0)),
+                bg_color: Some(Color::rgba(0, 255, 0, 255)),
+                attributes: Attributes::default(),
+            })
+        );
+        assert_eq!(
+            style_sheet.get("code_block").unwrap().bg_color,
+            Some(Color::rgba(0, 0, 0, 128))
+        );
+        assert_eq!(style_sheet.get("nope"), None);
+    }
+
+    #[test]
+    fn test_parse_empty_style_sheet() {
+        assert_eq!(
+            parse_style_sheet(" // nothing\n"),
+            Ok(StyleSheet::default())
+        );
+    }
+}
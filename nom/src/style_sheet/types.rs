@@ -0,0 +1,141 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::collections::HashMap;
+
+/// All the styles in a style sheet, which can be looked up by the name of their selector.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StyleSheet {
+    pub styles: HashMap<String, Style>,
+}
+
+impl StyleSheet {
+    pub fn get(&self, name: &str) -> Option<&Style> {
+        self.styles.get(name)
+    }
+}
+
+/// Properties that aren't set in the style sheet are [None] (or `false` for [Attributes]).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    pub fg_color: Option<Color>,
+    pub bg_color: Option<Color>,
+    pub attributes: Attributes,
+}
+
+impl Style {
+    /// Later declarations override earlier ones, just like in CSS.
+    pub fn apply(&mut self, declaration: Declaration) {
+        match declaration {
+            Declaration::FgColor(color) => self.fg_color = Some(color),
+            Declaration::BgColor(color) => self.bg_color = Some(color),
+            Declaration::Attributes(attributes) => self.attributes = attributes,
+        }
+    }
+}
+
+/// A rule in the style sheet, eg: `heading, title { fg_color: red; }`. The same declarations apply
+/// to each one of the `selectors`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rule<'a> {
+    pub selectors: Vec<&'a str>,
+    pub declarations: Vec<Declaration>,
+}
+
+/// A single `<property>: <value>;` in a rule.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Declaration {
+    FgColor(Color),
+    BgColor(Color),
+    Attributes(Attributes),
+}
+
+/// Colors w/out an alpha channel are opaque, ie: `a` is 255.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b, a: 255 }
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color { r, g, b, a }
+    }
+
+    /// Case insensitive lookup of [constants::NAMED_COLORS].
+    pub fn from_name(name: &str) -> Option<Self> {
+        constants::NAMED_COLORS
+            .iter()
+            .find(|(it, _)| it.eq_ignore_ascii_case(name))
+            .map(|(_, color)| *color)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Attributes {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub dim: bool,
+}
+
+/// All the literals that are used to parse a style sheet.
+pub mod constants {
+    use super::Color;
+
+    pub const FG_COLOR: &str = "fg_color";
+    pub const BG_COLOR: &str = "bg_color";
+    pub const ATTRIBUTES: &str = "attributes";
+    pub const BOLD: &str = "bold";
+    pub const ITALIC: &str = "italic";
+    pub const UNDERLINE: &str = "underline";
+    pub const DIM: &str = "dim";
+    pub const RGB: &str = "rgb";
+    pub const RGBA: &str = "rgba";
+    pub const HASH: &str = "#";
+    pub const PERCENT: &str = "%";
+    pub const BLOCK_COMMENT_START: &str = "/*";
+    pub const BLOCK_COMMENT_END: &str = "*/";
+    pub const LINE_COMMENT: &str = "//";
+
+    pub const NAMED_COLORS: [(&str, Color); 18] = [
+        ("black", Color::rgb(0, 0, 0)),
+        ("white", Color::rgb(255, 255, 255)),
+        ("red", Color::rgb(255, 0, 0)),
+        ("green", Color::rgb(0, 128, 0)),
+        ("lime", Color::rgb(0, 255, 0)),
+        ("blue", Color::rgb(0, 0, 255)),
+        ("yellow", Color::rgb(255, 255, 0)),
+        ("cyan", Color::rgb(0, 255, 255)),
+        ("magenta", Color::rgb(255, 0, 255)),
+        ("gray", Color::rgb(128, 128, 128)),
+        ("grey", Color::rgb(128, 128, 128)),
+        ("silver", Color::rgb(192, 192, 192)),
+        ("maroon", Color::rgb(128, 0, 0)),
+        ("navy", Color::rgb(0, 0, 128)),
+        ("olive", Color::rgb(128, 128, 0)),
+        ("purple", Color::rgb(128, 0, 128)),
+        ("orange", Color::rgb(255, 165, 0)),
+        ("transparent", Color::rgba(0, 0, 0, 0)),
+    ];
+}
@@ -0,0 +1,72 @@
+/*
+ *   Copyright (c) 2023 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Golden file tests for each [Renderer]. The input is `tests/golden/document.md`, and the
+//! expected output of each renderer is in `tests/golden/document.<extension>`. To update the
+//! expected output after an intentional change, run: `UPDATE_GOLDEN=1 cargo test`.
+
+use nom_scratch::*;
+use std::{fs, path::PathBuf};
+
+fn golden_path(file_name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(file_name)
+}
+
+fn assert_golden(renderer: &impl Renderer, extension: &str) {
+    let input = fs::read_to_string(golden_path("document.md")).unwrap();
+    let (remainder, document) = root_parse_markdown(&input).unwrap();
+    assert_eq!(remainder, "");
+
+    let output = renderer.render_document(&document);
+    let expected_path = golden_path(&format!("document.{extension}"));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&expected_path, &output).unwrap();
+    }
+    let expected = fs::read_to_string(&expected_path).unwrap();
+    assert_eq!(output, expected, "{expected_path:?} is out of date");
+}
+
+#[test]
+fn test_golden_html() {
+    assert_golden(&HtmlRenderer, "html");
+}
+
+#[test]
+fn test_golden_ansi() {
+    assert_golden(&AnsiRenderer, "ansi");
+}
+
+#[test]
+fn test_golden_plain_text() {
+    assert_golden(&PlainTextRenderer, "txt");
+}
+
+#[test]
+fn test_golden_markdown() {
+    assert_golden(&MarkdownRenderer, "roundtrip.md");
+}
+
+#[test]
+fn test_golden_markdown_round_trip() {
+    let input = fs::read_to_string(golden_path("document.md")).unwrap();
+    let (_, document) = root_parse_markdown(&input).unwrap();
+    let output = MarkdownRenderer.render_document(&document);
+    assert_eq!(root_parse_markdown(&output), Ok(("", document)));
+}
@@ -0,0 +1,99 @@
+/*
+ *   Copyright (c) 2025 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Compares the throughput of [ringbuffer::spsc] to [std::sync::mpsc], w/ one producer
+//! thread and one consumer thread. Run w/ `cargo bench --bench spsc`.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use ringbuffer::spsc;
+use std::{hint::black_box, sync::mpsc, thread};
+
+const ITEM_COUNT: u64 = 100_000;
+const CAPACITY: usize = 1024;
+
+fn bench_spsc(item_count: u64) {
+    let (mut producer, mut consumer) = spsc::channel::<u64, CAPACITY>();
+    let handle = thread::spawn(move || {
+        for item in 0..item_count {
+            let mut item = item;
+            while let Err(it) = producer.try_push(item) {
+                item = it;
+                thread::yield_now();
+            }
+        }
+    });
+    for _ in 0..item_count {
+        loop {
+            if let Some(item) = consumer.try_pop() {
+                black_box(item);
+                break;
+            }
+            thread::yield_now();
+        }
+    }
+    handle.join().unwrap();
+}
+
+fn bench_mpsc_sync_channel(item_count: u64) {
+    let (sender, receiver) = mpsc::sync_channel::<u64>(CAPACITY);
+    let handle = thread::spawn(move || {
+        for item in 0..item_count {
+            sender.send(item).unwrap();
+        }
+    });
+    for item in receiver.iter() {
+        black_box(item);
+    }
+    handle.join().unwrap();
+}
+
+fn bench_mpsc_channel(item_count: u64) {
+    let (sender, receiver) = mpsc::channel::<u64>();
+    let handle = thread::spawn(move || {
+        for item in 0..item_count {
+            sender.send(item).unwrap();
+        }
+    });
+    for item in receiver.iter() {
+        black_box(item);
+    }
+    handle.join().unwrap();
+}
+
+fn throughput(c: &mut Criterion) {
+    let mut group = c.benchmark_group("spsc_vs_mpsc");
+    group.throughput(Throughput::Elements(ITEM_COUNT));
+    group.bench_with_input(
+        BenchmarkId::new("spsc", ITEM_COUNT),
+        &ITEM_COUNT,
+        |b, &n| b.iter(|| bench_spsc(n)),
+    );
+    group.bench_with_input(
+        BenchmarkId::new("mpsc_sync_channel", ITEM_COUNT),
+        &ITEM_COUNT,
+        |b, &n| b.iter(|| bench_mpsc_sync_channel(n)),
+    );
+    group.bench_with_input(
+        BenchmarkId::new("mpsc_channel", ITEM_COUNT),
+        &ITEM_COUNT,
+        |b, &n| b.iter(|| bench_mpsc_channel(n)),
+    );
+    group.finish();
+}
+
+criterion_group!(benches, throughput);
+criterion_main!(benches);
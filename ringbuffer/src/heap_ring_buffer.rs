@@ -0,0 +1,370 @@
+/*
+ *   Copyright (c) 2025 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use crate::FullPolicy;
+use std::{
+    collections::{VecDeque, vec_deque},
+    ops::{Index, IndexMut},
+};
+
+/// Same as [crate::RingBuffer], but the capacity is chosen at runtime (eg, from config),
+/// and it can be changed w/ [Self::resize]. The items are stored on the heap.
+///
+/// # Fields
+///
+/// * `internal_storage`: A [VecDeque] that never holds more than `capacity` items.
+/// * `capacity`: The maximum number of items.
+/// * `policy`: What [Self::add] does when the buffer is full.
+///
+/// # Examples
+///
+/// ```
+/// use ringbuffer::HeapRingBuffer;
+///
+/// let mut ring_buffer: HeapRingBuffer<i32> = HeapRingBuffer::new(2);
+/// ring_buffer.extend([1, 2, 3]);
+/// assert_eq!(ring_buffer.iter().collect::<Vec<&i32>>(), vec![&2, &3]);
+///
+/// ring_buffer.resize(3);
+/// ring_buffer.add(4);
+/// assert_eq!(ring_buffer.iter().collect::<Vec<&i32>>(), vec![&2, &3, &4]);
+///
+/// ring_buffer.resize(1);
+/// assert_eq!(ring_buffer.iter().collect::<Vec<&i32>>(), vec![&4]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeapRingBuffer<T> {
+    internal_storage: VecDeque<T>,
+    capacity: usize,
+    policy: FullPolicy,
+}
+
+mod constructor {
+    use super::*;
+
+    impl<T> HeapRingBuffer<T> {
+        pub fn new(capacity: usize) -> Self {
+            Self::with_policy(capacity, FullPolicy::default())
+        }
+
+        pub fn with_policy(capacity: usize, policy: FullPolicy) -> Self {
+            HeapRingBuffer {
+                internal_storage: VecDeque::with_capacity(capacity),
+                capacity,
+                policy,
+            }
+        }
+
+        /// The newest `items` that fit in `capacity`. Since `capacity` may come from
+        /// untrusted bytes, nothing is preallocated for it, and the storage only grows as
+        /// items are added.
+        #[cfg(feature = "serde")]
+        pub(crate) fn from_newest_items(
+            capacity: usize,
+            policy: FullPolicy,
+            mut items: Vec<T>,
+        ) -> Self {
+            let excess = items.len().saturating_sub(capacity);
+            items.drain(..excess);
+            HeapRingBuffer {
+                internal_storage: VecDeque::from(items),
+                capacity,
+                policy,
+            }
+        }
+    }
+}
+
+mod mutator {
+    use super::*;
+
+    impl<T> HeapRingBuffer<T> {
+        /// Insert the newest item. If the buffer is full, then the [FullPolicy] decides
+        /// which item doesn't fit, and it is returned.
+        pub fn add(&mut self, value: T) -> Option<T> {
+            if !self.is_full() {
+                self.internal_storage.push_back(value);
+                return None;
+            }
+
+            match self.policy {
+                FullPolicy::Reject => Some(value),
+                FullPolicy::Overwrite => {
+                    // A zero capacity buffer can't hold the new item either.
+                    let Some(oldest) = self.internal_storage.pop_front() else {
+                        return Some(value);
+                    };
+                    self.internal_storage.push_back(value);
+                    Some(oldest)
+                }
+            }
+        }
+
+        /// Insert the newest item, unless the buffer is full (regardless of the
+        /// [FullPolicy]), in which case the `value` is returned.
+        pub fn try_add(&mut self, value: T) -> Result<(), T> {
+            if self.is_full() {
+                return Err(value);
+            }
+            self.internal_storage.push_back(value);
+            Ok(())
+        }
+
+        /// Remove the oldest item.
+        pub fn remove(&mut self) -> Option<T> {
+            self.internal_storage.pop_front()
+        }
+
+        /// Remove the newest item.
+        pub fn remove_newest(&mut self) -> Option<T> {
+            self.internal_storage.pop_back()
+        }
+
+        /// Remove all the items, oldest first. The items that aren't consumed are dropped
+        /// when the returned iterator is dropped.
+        pub fn drain(&mut self) -> vec_deque::Drain<'_, T> {
+            self.internal_storage.drain(..)
+        }
+
+        /// Clear (and drop) all items. The capacity remains the same.
+        pub fn clear(&mut self) {
+            self.internal_storage.clear();
+        }
+
+        /// Change the capacity, keeping the order of the items. If there are more items
+        /// than the new capacity, the oldest ones are dropped (regardless of the
+        /// [FullPolicy]), so that the newest ones are kept.
+        pub fn resize(&mut self, new_capacity: usize) {
+            let excess = self.len().saturating_sub(new_capacity);
+            self.internal_storage.drain(..excess);
+
+            if new_capacity < self.capacity {
+                self.internal_storage.shrink_to(new_capacity);
+            } else {
+                self.internal_storage
+                    .reserve_exact(new_capacity - self.internal_storage.len());
+            }
+            self.capacity = new_capacity;
+        }
+    }
+}
+
+mod size {
+    use super::*;
+
+    impl<T> HeapRingBuffer<T> {
+        pub fn len(&self) -> usize {
+            self.internal_storage.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.internal_storage.is_empty()
+        }
+
+        pub fn is_full(&self) -> bool {
+            self.internal_storage.len() == self.capacity
+        }
+
+        pub fn capacity(&self) -> usize {
+            self.capacity
+        }
+
+        pub fn policy(&self) -> FullPolicy {
+            self.policy
+        }
+
+        pub fn set_policy(&mut self, policy: FullPolicy) {
+            self.policy = policy;
+        }
+    }
+}
+
+mod accessor {
+    use super::*;
+
+    impl<T> HeapRingBuffer<T> {
+        /// The item at `index`, where `0` is the oldest item.
+        pub fn get(&self, index: usize) -> Option<&T> {
+            self.internal_storage.get(index)
+        }
+
+        /// The item at `index`, where `0` is the oldest item.
+        pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+            self.internal_storage.get_mut(index)
+        }
+
+        /// The item that [Self::remove] would return.
+        pub fn peek_oldest(&self) -> Option<&T> {
+            self.internal_storage.front()
+        }
+
+        /// The item that was added last.
+        pub fn peek_newest(&self) -> Option<&T> {
+            self.internal_storage.back()
+        }
+
+        /// The items (oldest first) are in the first slice, and then the second slice if
+        /// they wrap around the end of the `internal_storage`.
+        pub fn as_slices(&self) -> (&[T], &[T]) {
+            self.internal_storage.as_slices()
+        }
+
+        /// Same as [Self::as_slices], but mutable.
+        pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+            self.internal_storage.as_mut_slices()
+        }
+    }
+
+    /// Panics if `index` is out of bounds, where `0` is the oldest item.
+    impl<T> Index<usize> for HeapRingBuffer<T> {
+        type Output = T;
+
+        fn index(&self, index: usize) -> &Self::Output {
+            &self.internal_storage[index]
+        }
+    }
+
+    /// Panics if `index` is out of bounds, where `0` is the oldest item.
+    impl<T> IndexMut<usize> for HeapRingBuffer<T> {
+        fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+            &mut self.internal_storage[index]
+        }
+    }
+}
+
+mod iterator {
+    use super::*;
+
+    impl<T> HeapRingBuffer<T> {
+        /// Iterates the items from the oldest to the newest (or the reverse, w/
+        /// [Iterator::rev]).
+        pub fn iter(&self) -> vec_deque::Iter<'_, T> {
+            self.internal_storage.iter()
+        }
+
+        pub fn iter_mut(&mut self) -> vec_deque::IterMut<'_, T> {
+            self.internal_storage.iter_mut()
+        }
+    }
+
+    impl<T> IntoIterator for HeapRingBuffer<T> {
+        type Item = T;
+        type IntoIter = vec_deque::IntoIter<T>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.internal_storage.into_iter()
+        }
+    }
+
+    impl<'a, T> IntoIterator for &'a HeapRingBuffer<T> {
+        type Item = &'a T;
+        type IntoIter = vec_deque::Iter<'a, T>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.iter()
+        }
+    }
+
+    impl<'a, T> IntoIterator for &'a mut HeapRingBuffer<T> {
+        type Item = &'a mut T;
+        type IntoIter = vec_deque::IterMut<'a, T>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.iter_mut()
+        }
+    }
+}
+
+mod traits {
+    use super::*;
+
+    /// Adds each item w/ [HeapRingBuffer::add], so the [FullPolicy] decides what happens
+    /// when the buffer is full.
+    impl<T> Extend<T> for HeapRingBuffer<T> {
+        fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+            for item in iter {
+                self.add(item);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_heap_ring_buffer {
+    use super::*;
+
+    #[test]
+    fn test_heap_ring_buffer_add_remove() {
+        let mut ring_buffer: HeapRingBuffer<i32> = HeapRingBuffer::new(3);
+        assert_eq!(ring_buffer.capacity(), 3);
+        assert!(ring_buffer.is_empty());
+
+        assert_eq!(ring_buffer.add(1), None);
+        assert_eq!(ring_buffer.add(2), None);
+        assert_eq!(ring_buffer.add(3), None);
+        assert!(ring_buffer.is_full());
+        assert_eq!(ring_buffer.add(4), Some(1)); // Oldest is overwritten.
+        assert_eq!(ring_buffer.try_add(5), Err(5));
+        assert_eq!(ring_buffer.iter().collect::<Vec<_>>(), vec![&2, &3, &4]);
+        assert_eq!(
+            ring_buffer.iter().rev().collect::<Vec<_>>(),
+            vec![&4, &3, &2]
+        );
+
+        assert_eq!(ring_buffer.peek_oldest(), Some(&2));
+        assert_eq!(ring_buffer.peek_newest(), Some(&4));
+        assert_eq!(ring_buffer[1], 3);
+
+        assert_eq!(ring_buffer.remove(), Some(2));
+        assert_eq!(ring_buffer.remove_newest(), Some(4));
+        assert_eq!(ring_buffer.len(), 1);
+
+        ring_buffer.set_policy(FullPolicy::Reject);
+        ring_buffer.extend([5, 6, 7]);
+        assert_eq!(ring_buffer.drain().collect::<Vec<_>>(), vec![3, 5, 6]);
+        assert!(ring_buffer.is_empty());
+    }
+
+    #[test]
+    fn test_heap_ring_buffer_resize() {
+        let mut ring_buffer: HeapRingBuffer<i32> = HeapRingBuffer::new(3);
+        ring_buffer.extend([1, 2, 3, 4]);
+
+        // Grow.
+        ring_buffer.resize(5);
+        assert_eq!(ring_buffer.capacity(), 5);
+        assert!(!ring_buffer.is_full());
+        ring_buffer.extend([5, 6]);
+        assert_eq!(
+            ring_buffer.iter().collect::<Vec<_>>(),
+            vec![&2, &3, &4, &5, &6]
+        );
+
+        // Shrink keeps the newest items.
+        ring_buffer.resize(2);
+        assert_eq!(ring_buffer.capacity(), 2);
+        assert!(ring_buffer.is_full());
+        assert_eq!(ring_buffer.iter().collect::<Vec<_>>(), vec![&5, &6]);
+        assert_eq!(ring_buffer.add(7), Some(5));
+
+        // Zero capacity holds nothing.
+        ring_buffer.resize(0);
+        assert!(ring_buffer.is_empty());
+        assert_eq!(ring_buffer.add(8), Some(8));
+        assert!(ring_buffer.is_empty());
+    }
+}
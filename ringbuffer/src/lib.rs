@@ -15,12 +15,34 @@
  *   limitations under the License.
  */
 
+use std::{
+    fmt,
+    mem::MaybeUninit,
+    ops::{Index, IndexMut, Range},
+};
+
+mod heap_ring_buffer;
+mod ring_buffer_ops;
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(feature = "serde")]
+mod snapshot;
+pub mod spsc;
+
+pub use heap_ring_buffer::*;
+pub use iterator::*;
+pub use policy::*;
+pub use ring_buffer_ops::*;
+#[cfg(feature = "serde")]
+pub use snapshot::*;
+
 /// A fixed-size ring buffer implementation.
 ///
 /// The `RingBuffer` struct is a generic data structure that allows for efficient
 /// insertion and removal of elements in a circular buffer. It maintains a fixed capacity
-/// and overwrites the oldest elements when the buffer is full. It behaves like a queue
-/// with a fixed size ([Self::add] and [Self::remove]).
+/// and (by default) overwrites the oldest elements when the buffer is full, see
+/// [FullPolicy]. It behaves like a queue with a fixed size ([Self::add] and
+/// [Self::remove]).
 ///
 /// # Type Parameters
 ///
@@ -29,18 +51,23 @@
 ///
 /// # Fields
 ///
-/// * `internal_storage`: An array of `Option<T>` used to store the elements.
+/// * `internal_storage`: An array of `MaybeUninit<T>` used to store the elements. Only
+///   the `count` slots starting at `tail` (wrapping around) are initialized.
 /// * `head`: The index of the next insertion point.
 /// * `tail`: The index of the next removal point.
 /// * `count`: The current number of elements in the buffer.
+/// * `policy`: What [Self::add] does when the buffer is full.
 ///
 /// # Modules
 ///
 /// * `constructor`: Contains the implementation of the `new` method and the `Default`
 ///   trait.
+/// * `policy`: Contains the [FullPolicy] enum.
 /// * `mutator`: Contains methods for inserting and removing elements.
 /// * `size`: Contains methods for querying the size and state of the buffer.
-/// * `iterator`: Contains the implementation of an iterator for the ring buffer.
+/// * `accessor`: Contains methods for looking at elements w/out removing them.
+/// * `iterator`: Contains the implementation of the iterators for the ring buffer.
+/// * `traits`: Contains the implementations of the std traits.
 ///
 /// # Examples
 ///
@@ -62,14 +89,15 @@
 /// assert_eq!(ring_buffer.len(), 2);
 /// assert_eq!(ring_buffer.is_empty(), false);
 /// assert_eq!(ring_buffer.iter().collect::<Vec<&i32>>(), vec![&2, &3]);
+/// assert_eq!(ring_buffer[0], 2);
+/// assert_eq!(ring_buffer.peek_newest(), Some(&3));
 /// ```
-
-#[derive(Debug, PartialEq)]
 pub struct RingBuffer<T, const N: usize> {
-    internal_storage: [Option<T>; N],
+    internal_storage: [MaybeUninit<T>; N],
     head: usize,
     tail: usize,
     count: usize,
+    policy: FullPolicy,
 }
 
 mod constructor {
@@ -83,35 +111,86 @@ mod constructor {
 
     impl<T, const N: usize> RingBuffer<T, N> {
         pub fn new() -> Self {
+            Self::with_policy(FullPolicy::default())
+        }
+
+        pub fn with_policy(policy: FullPolicy) -> Self {
             RingBuffer {
-                internal_storage: [(); N].map(|_| None),
+                internal_storage: [const { MaybeUninit::uninit() }; N],
                 head: 0,
                 tail: 0,
                 count: 0,
+                policy,
             }
         }
     }
 }
 
+mod policy {
+    /// What [crate::RingBuffer::add] does when the buffer is full.
+    ///
+    /// ```
+    /// use ringbuffer::{FullPolicy, RingBuffer};
+    ///
+    /// let mut ring_buffer: RingBuffer<i32, 2> = RingBuffer::with_policy(FullPolicy::Reject);
+    /// ring_buffer.add(1);
+    /// ring_buffer.add(2);
+    /// assert_eq!(ring_buffer.add(3), Some(3));
+    /// assert_eq!(ring_buffer.iter().collect::<Vec<&i32>>(), vec![&1, &2]);
+    /// ```
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum FullPolicy {
+        /// Overwrite the oldest item, which is returned.
+        #[default]
+        Overwrite,
+        /// Keep the oldest item, and return the new one.
+        Reject,
+    }
+}
+
 mod mutator {
     use super::*;
 
     impl<T, const N: usize> RingBuffer<T, N> {
-        /// Insert at head (ie, insert the newest item).
-        pub fn add(&mut self, value: T) {
-            if self.count == N
-            // Wrap around. Update both head and tail.
-            {
-                self.internal_storage[self.head] = Some(value);
-                self.head = (self.head + 1) % N;
-                self.tail = (self.tail + 1) % N;
+        /// Insert at head (ie, insert the newest item). If the buffer is full, then the
+        /// [FullPolicy] decides which item doesn't fit, and it is returned. A buffer w/ a
+        /// capacity of 0 is always full, so it returns the `value`.
+        pub fn add(&mut self, value: T) -> Option<T> {
+            if self.count == N {
+                match self.policy {
+                    FullPolicy::Reject => Some(value),
+                    // There is no oldest item to overwrite.
+                    FullPolicy::Overwrite if N == 0 => Some(value),
+                    // Wrap around. Update both head and tail.
+                    FullPolicy::Overwrite => {
+                        // SAFETY: The buffer is full, so the slot at head (which is the
+                        // same as tail) is initialized.
+                        let oldest = unsafe { self.internal_storage[self.head].assume_init_read() };
+                        self.internal_storage[self.head].write(value);
+                        self.head = (self.head + 1) % N;
+                        self.tail = (self.tail + 1) % N;
+                        Some(oldest)
+                    }
+                }
             }
             // Normal insert. Don't touch the tail.
             else {
-                self.internal_storage[self.head] = Some(value);
+                self.internal_storage[self.head].write(value);
                 self.head = (self.head + 1) % N;
                 self.count += 1;
+                None
+            }
+        }
+
+        /// Insert at head, unless the buffer is full (regardless of the [FullPolicy]), in
+        /// which case the `value` is returned.
+        pub fn try_add(&mut self, value: T) -> Result<(), T> {
+            if self.is_full() {
+                return Err(value);
             }
+            self.add(value);
+            Ok(())
         }
 
         /// Remove from tail (ie, remove the oldest item).
@@ -120,21 +199,40 @@ mod mutator {
                 return None;
             }
 
-            let value = self.internal_storage[self.tail].take();
+            // SAFETY: The buffer isn't empty, so the slot at tail is initialized. It is
+            // treated as uninitialized after this.
+            let value = unsafe { self.internal_storage[self.tail].assume_init_read() };
             self.tail = (self.tail + 1) % N;
             self.count -= 1;
 
-            value
+            Some(value)
         }
 
-        /// Clear all items. This does not affect memory allocation (the capacity remains
-        /// the same). The `internal_storage` array is not modified, so there maybe stale
-        /// data in there, which does not affect the behavior of the ring buffer
-        /// ([Self::iter], [Self::add], [Self::remove], etc.) work as expected.
+        /// Remove from head (ie, remove the newest item).
+        pub fn remove_newest(&mut self) -> Option<T> {
+            if self.count == 0 {
+                return None;
+            }
+
+            self.head = (self.head + N - 1) % N;
+            self.count -= 1;
+            // SAFETY: The slot before head is the newest item, so it is initialized. It is
+            // treated as uninitialized after this.
+            Some(unsafe { self.internal_storage[self.head].assume_init_read() })
+        }
+
+        /// Remove all the items, oldest first. The items that aren't consumed are dropped
+        /// when the returned iterator is dropped.
+        pub fn drain(&mut self) -> RingBufferDrain<'_, T, N> {
+            RingBufferDrain { ring_buffer: self }
+        }
+
+        /// Clear (and drop) all items. This does not affect memory allocation (the
+        /// capacity remains the same).
         pub fn clear(&mut self) {
+            while self.remove().is_some() {}
             self.head = 0;
             self.tail = 0;
-            self.count = 0;
         }
     }
 }
@@ -154,41 +252,339 @@ mod size {
         pub fn is_full(&self) -> bool {
             self.count == N
         }
+
+        pub fn capacity(&self) -> usize {
+            N
+        }
+
+        pub fn policy(&self) -> FullPolicy {
+            self.policy
+        }
+
+        pub fn set_policy(&mut self, policy: FullPolicy) {
+            self.policy = policy;
+        }
+    }
+}
+
+mod accessor {
+    use super::*;
+
+    impl<T, const N: usize> RingBuffer<T, N> {
+        /// The item at `index`, where `0` is the oldest item.
+        pub fn get(&self, index: usize) -> Option<&T> {
+            if index >= self.count {
+                return None;
+            }
+            // SAFETY: The first `count` items (starting at tail) are initialized.
+            Some(unsafe { self.internal_storage[(self.tail + index) % N].assume_init_ref() })
+        }
+
+        /// The item at `index`, where `0` is the oldest item.
+        pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+            if index >= self.count {
+                return None;
+            }
+            // SAFETY: The first `count` items (starting at tail) are initialized.
+            Some(unsafe { self.internal_storage[(self.tail + index) % N].assume_init_mut() })
+        }
+
+        /// The item that [Self::remove] would return.
+        pub fn peek_oldest(&self) -> Option<&T> {
+            self.get(0)
+        }
+
+        /// The item that was added last.
+        pub fn peek_newest(&self) -> Option<&T> {
+            self.get(self.count.checked_sub(1)?)
+        }
+
+        /// The items (oldest first) are in the first slice, and then the second slice if
+        /// they wrap around the end of the `internal_storage`.
+        pub fn as_slices(&self) -> (&[T], &[T]) {
+            let (first, second) = self.slice_ranges();
+            // SAFETY: The slots in both ranges are initialized, and `MaybeUninit<T>` has
+            // the same layout as `T`.
+            unsafe {
+                (
+                    &*(&self.internal_storage[first] as *const [MaybeUninit<T>] as *const [T]),
+                    &*(&self.internal_storage[second] as *const [MaybeUninit<T>] as *const [T]),
+                )
+            }
+        }
+
+        /// Same as [Self::as_slices], but mutable.
+        pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+            let (first, second) = self.slice_ranges();
+            // The second range is always before the first one.
+            let (before, after) = self.internal_storage.split_at_mut(first.start);
+            let first = &mut after[..first.len()];
+            let second = &mut before[second];
+            // SAFETY: The slots in both ranges are initialized, and `MaybeUninit<T>` has
+            // the same layout as `T`.
+            unsafe {
+                (
+                    &mut *(first as *mut [MaybeUninit<T>] as *mut [T]),
+                    &mut *(second as *mut [MaybeUninit<T>] as *mut [T]),
+                )
+            }
+        }
+
+        /// The indices of the `internal_storage` for [Self::as_slices].
+        fn slice_ranges(&self) -> (Range<usize>, Range<usize>) {
+            if self.tail + self.count <= N {
+                (self.tail..self.tail + self.count, 0..0)
+            } else {
+                (self.tail..N, 0..self.tail + self.count - N)
+            }
+        }
+    }
+
+    /// Panics if `index` is out of bounds, where `0` is the oldest item.
+    impl<T, const N: usize> Index<usize> for RingBuffer<T, N> {
+        type Output = T;
+
+        fn index(&self, index: usize) -> &Self::Output {
+            let len = self.count;
+            self.get(index).unwrap_or_else(|| {
+                panic!("index out of bounds: the len is {len} but the index is {index}")
+            })
+        }
+    }
+
+    /// Panics if `index` is out of bounds, where `0` is the oldest item.
+    impl<T, const N: usize> IndexMut<usize> for RingBuffer<T, N> {
+        fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+            let len = self.count;
+            self.get_mut(index).unwrap_or_else(|| {
+                panic!("index out of bounds: the len is {len} but the index is {index}")
+            })
+        }
     }
 }
 
 mod iterator {
     use super::*;
+    use std::{iter::Chain, slice};
+
+    /// Iterates the items from the oldest to the newest (or the reverse, w/ [Iterator::rev]).
     pub struct RingBufferIterator<'a, T, const N: usize> {
         ring_buffer: &'a RingBuffer<T, N>,
         iterator_index: usize,
+        /// One past the last item that hasn't been returned by [Self::next_back].
+        back_index: usize,
     }
 
     impl<'a, T, const N: usize> Iterator for RingBufferIterator<'a, T, N> {
         type Item = &'a T;
 
         fn next(&mut self) -> Option<Self::Item> {
-            if self.iterator_index == self.ring_buffer.count {
+            if self.iterator_index == self.back_index {
                 return None;
             }
 
-            let actual_index = (self.ring_buffer.tail + self.iterator_index) % N;
+            let item = self.ring_buffer.get(self.iterator_index);
             self.iterator_index += 1;
 
-            self.ring_buffer.internal_storage[actual_index].as_ref()
+            item
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            let len = self.back_index - self.iterator_index;
+            (len, Some(len))
+        }
+    }
+
+    impl<T, const N: usize> DoubleEndedIterator for RingBufferIterator<'_, T, N> {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            if self.iterator_index == self.back_index {
+                return None;
+            }
+
+            self.back_index -= 1;
+            self.ring_buffer.get(self.back_index)
+        }
+    }
+
+    impl<T, const N: usize> ExactSizeIterator for RingBufferIterator<'_, T, N> {}
+
+    /// Same as [RingBufferIterator], but mutable.
+    pub struct RingBufferIteratorMut<'a, T> {
+        inner: Chain<slice::IterMut<'a, T>, slice::IterMut<'a, T>>,
+    }
+
+    impl<'a, T> Iterator for RingBufferIteratorMut<'a, T> {
+        type Item = &'a mut T;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.inner.next()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            self.inner.size_hint()
+        }
+    }
+
+    impl<T> DoubleEndedIterator for RingBufferIteratorMut<'_, T> {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            self.inner.next_back()
+        }
+    }
+
+    impl<T> ExactSizeIterator for RingBufferIteratorMut<'_, T> {}
+
+    /// Returned by [RingBuffer::into_iter]. Removes the items from the oldest to the
+    /// newest (or the reverse, w/ [Iterator::rev]).
+    pub struct RingBufferIntoIterator<T, const N: usize> {
+        ring_buffer: RingBuffer<T, N>,
+    }
+
+    impl<T, const N: usize> Iterator for RingBufferIntoIterator<T, N> {
+        type Item = T;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.ring_buffer.remove()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.ring_buffer.len(), Some(self.ring_buffer.len()))
+        }
+    }
+
+    impl<T, const N: usize> DoubleEndedIterator for RingBufferIntoIterator<T, N> {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            self.ring_buffer.remove_newest()
+        }
+    }
+
+    impl<T, const N: usize> ExactSizeIterator for RingBufferIntoIterator<T, N> {}
+
+    /// Returned by [RingBuffer::drain].
+    pub struct RingBufferDrain<'a, T, const N: usize> {
+        pub(crate) ring_buffer: &'a mut RingBuffer<T, N>,
+    }
+
+    impl<T, const N: usize> Iterator for RingBufferDrain<'_, T, N> {
+        type Item = T;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.ring_buffer.remove()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.ring_buffer.len(), Some(self.ring_buffer.len()))
+        }
+    }
+
+    impl<T, const N: usize> DoubleEndedIterator for RingBufferDrain<'_, T, N> {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            self.ring_buffer.remove_newest()
+        }
+    }
+
+    impl<T, const N: usize> ExactSizeIterator for RingBufferDrain<'_, T, N> {}
+
+    impl<T, const N: usize> Drop for RingBufferDrain<'_, T, N> {
+        fn drop(&mut self) {
+            self.ring_buffer.clear();
         }
     }
 
     impl<T, const N: usize> RingBuffer<T, N> {
-        pub fn iter(&self) -> RingBufferIterator<T, N> {
+        pub fn iter(&self) -> RingBufferIterator<'_, T, N> {
             RingBufferIterator {
                 ring_buffer: self,
                 iterator_index: 0,
+                back_index: self.count,
             }
         }
+
+        pub fn iter_mut(&mut self) -> RingBufferIteratorMut<'_, T> {
+            let (first, second) = self.as_mut_slices();
+            RingBufferIteratorMut {
+                inner: first.iter_mut().chain(second.iter_mut()),
+            }
+        }
+    }
+
+    impl<T, const N: usize> IntoIterator for RingBuffer<T, N> {
+        type Item = T;
+        type IntoIter = RingBufferIntoIterator<T, N>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            RingBufferIntoIterator { ring_buffer: self }
+        }
+    }
+
+    impl<'a, T, const N: usize> IntoIterator for &'a RingBuffer<T, N> {
+        type Item = &'a T;
+        type IntoIter = RingBufferIterator<'a, T, N>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.iter()
+        }
+    }
+
+    impl<'a, T, const N: usize> IntoIterator for &'a mut RingBuffer<T, N> {
+        type Item = &'a mut T;
+        type IntoIter = RingBufferIteratorMut<'a, T>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.iter_mut()
+        }
     }
 }
 
+mod traits {
+    use super::*;
+
+    impl<T, const N: usize> Drop for RingBuffer<T, N> {
+        fn drop(&mut self) {
+            self.clear();
+        }
+    }
+
+    /// Adds each item w/ [RingBuffer::add], so the [FullPolicy] decides what happens when
+    /// the buffer is full.
+    impl<T, const N: usize> Extend<T> for RingBuffer<T, N> {
+        fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+            for item in iter {
+                self.add(item);
+            }
+        }
+    }
+
+    impl<T: Clone, const N: usize> Clone for RingBuffer<T, N> {
+        fn clone(&self) -> Self {
+            let mut it = Self::with_policy(self.policy);
+            it.extend(self.iter().cloned());
+            it
+        }
+    }
+
+    impl<T: fmt::Debug, const N: usize> fmt::Debug for RingBuffer<T, N> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("RingBuffer")
+                .field("items", &self.iter().collect::<Vec<_>>())
+                .field("head", &self.head)
+                .field("tail", &self.tail)
+                .field("count", &self.count)
+                .field("policy", &self.policy)
+                .finish()
+        }
+    }
+
+    /// Compares the items (oldest first) and the [FullPolicy], not where the items are in
+    /// the `internal_storage`.
+    impl<T: PartialEq, const N: usize> PartialEq for RingBuffer<T, N> {
+        fn eq(&self, other: &Self) -> bool {
+            self.policy == other.policy && self.iter().eq(other.iter())
+        }
+    }
+
+    impl<T: Eq, const N: usize> Eq for RingBuffer<T, N> {}
+}
+
 #[cfg(test)]
 mod tests_ring_buffer {
     use super::*;
@@ -453,4 +849,163 @@ mod tests_ring_buffer {
             assert!(vec.is_empty());
         }
     }
+
+    #[test]
+    fn test_ring_buffer_full_policy() {
+        let mut ring_buffer: RingBuffer<i32, 2> = RingBuffer::new();
+        assert_eq!(ring_buffer.policy(), FullPolicy::Overwrite);
+        assert_eq!(ring_buffer.add(1), None);
+        assert_eq!(ring_buffer.add(2), None);
+        assert_eq!(ring_buffer.add(3), Some(1)); // Oldest is overwritten.
+        assert_eq!(ring_buffer.try_add(4), Err(4)); // Full, regardless of policy.
+        assert_eq!(ring_buffer.iter().collect::<Vec<_>>(), vec![&2, &3]);
+
+        ring_buffer.set_policy(FullPolicy::Reject);
+        assert_eq!(ring_buffer.add(5), Some(5)); // New item is rejected.
+        assert_eq!(ring_buffer.iter().collect::<Vec<_>>(), vec![&2, &3]);
+
+        ring_buffer.remove();
+        assert_eq!(ring_buffer.try_add(6), Ok(()));
+        assert_eq!(ring_buffer.iter().collect::<Vec<_>>(), vec![&3, &6]);
+
+        let mut ring_buffer: RingBuffer<i32, 3> = RingBuffer::with_policy(FullPolicy::Reject);
+        ring_buffer.extend(1..=5);
+        assert_eq!(ring_buffer.iter().collect::<Vec<_>>(), vec![&1, &2, &3]);
+    }
+
+    #[test]
+    fn test_ring_buffer_zero_capacity() {
+        for policy in [FullPolicy::Overwrite, FullPolicy::Reject] {
+            let mut ring_buffer: RingBuffer<i32, 0> = RingBuffer::with_policy(policy);
+            assert!(ring_buffer.is_full());
+            assert_eq!(ring_buffer.add(1), Some(1));
+            assert_eq!(ring_buffer.try_add(2), Err(2));
+            ring_buffer.extend(3..=4);
+            assert!(ring_buffer.is_empty());
+            assert_eq!(ring_buffer.remove(), None);
+            assert_eq!(ring_buffer.remove_newest(), None);
+            assert_eq!(ring_buffer.as_slices(), (&[][..], &[][..]));
+        }
+    }
+
+    #[test]
+    fn test_ring_buffer_peek_and_index() {
+        let mut ring_buffer: RingBuffer<i32, 3> = RingBuffer::new();
+        assert_eq!(ring_buffer.peek_oldest(), None);
+        assert_eq!(ring_buffer.peek_newest(), None);
+        assert_eq!(ring_buffer.get(0), None);
+
+        ring_buffer.extend([1, 2, 3, 4]);
+        assert_eq!(ring_buffer.peek_oldest(), Some(&2));
+        assert_eq!(ring_buffer.peek_newest(), Some(&4));
+        assert_eq!(ring_buffer[0], 2);
+        assert_eq!(ring_buffer[2], 4);
+        assert_eq!(ring_buffer.get(3), None);
+
+        ring_buffer[1] = 30;
+        assert_eq!(ring_buffer.iter().collect::<Vec<_>>(), vec![&2, &30, &4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn test_ring_buffer_index_out_of_bounds() {
+        let mut ring_buffer: RingBuffer<i32, 3> = RingBuffer::new();
+        ring_buffer.add(1);
+        let _ = ring_buffer[1];
+    }
+
+    #[test]
+    fn test_ring_buffer_as_slices() {
+        let mut ring_buffer: RingBuffer<i32, 4> = RingBuffer::new();
+        assert_eq!(ring_buffer.as_slices(), (&[][..], &[][..]));
+
+        ring_buffer.extend([1, 2, 3]);
+        assert_eq!(ring_buffer.as_slices(), (&[1, 2, 3][..], &[][..]));
+
+        // Wrap around.
+        ring_buffer.extend([4, 5, 6]);
+        assert_eq!(ring_buffer.as_slices(), (&[3, 4][..], &[5, 6][..]));
+
+        let (first, second) = ring_buffer.as_mut_slices();
+        first[0] = 30;
+        second[1] = 60;
+        assert_eq!(
+            ring_buffer.iter().collect::<Vec<_>>(),
+            vec![&30, &4, &5, &60]
+        );
+    }
+
+    #[test]
+    fn test_ring_buffer_drain() {
+        let mut ring_buffer: RingBuffer<i32, 3> = RingBuffer::new();
+        ring_buffer.extend([1, 2, 3, 4]);
+        assert_eq!(ring_buffer.drain().collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert!(ring_buffer.is_empty());
+
+        // Items that aren't consumed are removed too.
+        ring_buffer.extend([1, 2, 3]);
+        let mut drain = ring_buffer.drain();
+        assert_eq!(drain.next(), Some(1));
+        assert_eq!(drain.next_back(), Some(3));
+        drop(drain);
+        assert!(ring_buffer.is_empty());
+    }
+
+    #[test]
+    fn test_ring_buffer_double_ended_and_into_iter() {
+        let mut ring_buffer: RingBuffer<i32, 3> = RingBuffer::new();
+        ring_buffer.extend([1, 2, 3, 4]);
+
+        assert_eq!(
+            ring_buffer.iter().rev().collect::<Vec<_>>(),
+            vec![&4, &3, &2]
+        );
+        let mut iter = ring_buffer.iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next_back(), Some(&4));
+        assert_eq!(iter.next(), Some(&3));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+
+        for item in &mut ring_buffer {
+            *item *= 10;
+        }
+        assert_eq!(
+            (&ring_buffer).into_iter().collect::<Vec<_>>(),
+            vec![&20, &30, &40]
+        );
+        assert_eq!(ring_buffer.iter_mut().next_back(), Some(&mut 40));
+
+        assert_eq!(
+            ring_buffer.clone().into_iter().collect::<Vec<_>>(),
+            vec![20, 30, 40]
+        );
+        assert_eq!(
+            ring_buffer.into_iter().rev().collect::<Vec<_>>(),
+            vec![40, 30, 20]
+        );
+    }
+
+    #[test]
+    fn test_ring_buffer_drops_each_item_once() {
+        use std::rc::Rc;
+
+        let item = Rc::new(());
+        {
+            let mut ring_buffer: RingBuffer<Rc<()>, 3> = RingBuffer::new();
+            ring_buffer.extend((0..5).map(|_| item.clone()));
+            assert_eq!(Rc::strong_count(&item), 4);
+
+            ring_buffer.remove();
+            ring_buffer.remove_newest();
+            assert_eq!(Rc::strong_count(&item), 2);
+
+            ring_buffer.extend((0..2).map(|_| item.clone()));
+            let mut into_iter = ring_buffer.into_iter();
+            into_iter.next();
+            assert_eq!(Rc::strong_count(&item), 3);
+        }
+        assert_eq!(Rc::strong_count(&item), 1);
+    }
 }
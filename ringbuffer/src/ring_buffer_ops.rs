@@ -0,0 +1,223 @@
+/*
+ *   Copyright (c) 2025 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use crate::{FullPolicy, HeapRingBuffer, RingBuffer};
+
+/// The operations that both [RingBuffer] and [HeapRingBuffer] support, so that code (eg,
+/// scrollback or history buffers) can be generic over either one.
+///
+/// ```
+/// use ringbuffer::{HeapRingBuffer, RingBuffer, RingBufferOps};
+///
+/// fn last_two<B: RingBufferOps<String>>(history: &B) -> Vec<&String> {
+///     history.iter().rev().take(2).collect()
+/// }
+///
+/// let mut stack: RingBuffer<String, 3> = RingBuffer::new();
+/// let mut heap: HeapRingBuffer<String> = HeapRingBuffer::new(3);
+/// for line in ["ls", "cd", "pwd"] {
+///     stack.add(line.to_string());
+///     heap.add(line.to_string());
+/// }
+/// assert_eq!(last_two(&stack), last_two(&heap));
+/// ```
+pub trait RingBufferOps<T> {
+    fn len(&self) -> usize;
+
+    fn capacity(&self) -> usize;
+
+    fn policy(&self) -> FullPolicy;
+
+    fn set_policy(&mut self, policy: FullPolicy);
+
+    /// Insert the newest item. If the buffer is full, then the [FullPolicy] decides which
+    /// item doesn't fit, and it is returned.
+    fn add(&mut self, value: T) -> Option<T>;
+
+    /// Remove the oldest item.
+    fn remove(&mut self) -> Option<T>;
+
+    /// Remove the newest item.
+    fn remove_newest(&mut self) -> Option<T>;
+
+    fn clear(&mut self);
+
+    /// The item at `index`, where `0` is the oldest item.
+    fn get(&self, index: usize) -> Option<&T>;
+
+    /// The item at `index`, where `0` is the oldest item.
+    fn get_mut(&mut self, index: usize) -> Option<&mut T>;
+
+    /// Iterates the items from the oldest to the newest.
+    fn iter<'a>(&'a self) -> impl DoubleEndedIterator<Item = &'a T> + ExactSizeIterator
+    where
+        T: 'a;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    /// Insert the newest item, unless the buffer is full (regardless of the
+    /// [FullPolicy]), in which case the `value` is returned.
+    fn try_add(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(value);
+        }
+        self.add(value);
+        Ok(())
+    }
+
+    fn peek_oldest(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    fn peek_newest(&self) -> Option<&T> {
+        self.get(self.len().checked_sub(1)?)
+    }
+}
+
+impl<T, const N: usize> RingBufferOps<T> for RingBuffer<T, N> {
+    fn len(&self) -> usize {
+        RingBuffer::len(self)
+    }
+
+    fn capacity(&self) -> usize {
+        RingBuffer::capacity(self)
+    }
+
+    fn policy(&self) -> FullPolicy {
+        RingBuffer::policy(self)
+    }
+
+    fn set_policy(&mut self, policy: FullPolicy) {
+        RingBuffer::set_policy(self, policy)
+    }
+
+    fn add(&mut self, value: T) -> Option<T> {
+        RingBuffer::add(self, value)
+    }
+
+    fn remove(&mut self) -> Option<T> {
+        RingBuffer::remove(self)
+    }
+
+    fn remove_newest(&mut self) -> Option<T> {
+        RingBuffer::remove_newest(self)
+    }
+
+    fn clear(&mut self) {
+        RingBuffer::clear(self)
+    }
+
+    fn get(&self, index: usize) -> Option<&T> {
+        RingBuffer::get(self, index)
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        RingBuffer::get_mut(self, index)
+    }
+
+    fn iter<'a>(&'a self) -> impl DoubleEndedIterator<Item = &'a T> + ExactSizeIterator
+    where
+        T: 'a,
+    {
+        RingBuffer::iter(self)
+    }
+}
+
+impl<T> RingBufferOps<T> for HeapRingBuffer<T> {
+    fn len(&self) -> usize {
+        HeapRingBuffer::len(self)
+    }
+
+    fn capacity(&self) -> usize {
+        HeapRingBuffer::capacity(self)
+    }
+
+    fn policy(&self) -> FullPolicy {
+        HeapRingBuffer::policy(self)
+    }
+
+    fn set_policy(&mut self, policy: FullPolicy) {
+        HeapRingBuffer::set_policy(self, policy)
+    }
+
+    fn add(&mut self, value: T) -> Option<T> {
+        HeapRingBuffer::add(self, value)
+    }
+
+    fn remove(&mut self) -> Option<T> {
+        HeapRingBuffer::remove(self)
+    }
+
+    fn remove_newest(&mut self) -> Option<T> {
+        HeapRingBuffer::remove_newest(self)
+    }
+
+    fn clear(&mut self) {
+        HeapRingBuffer::clear(self)
+    }
+
+    fn get(&self, index: usize) -> Option<&T> {
+        HeapRingBuffer::get(self, index)
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        HeapRingBuffer::get_mut(self, index)
+    }
+
+    fn iter<'a>(&'a self) -> impl DoubleEndedIterator<Item = &'a T> + ExactSizeIterator
+    where
+        T: 'a,
+    {
+        HeapRingBuffer::iter(self)
+    }
+}
+
+#[cfg(test)]
+mod tests_ring_buffer_ops {
+    use super::*;
+
+    /// A scrollback that only keeps the last `capacity` lines, w/ either buffer.
+    fn scrollback<B: RingBufferOps<usize>>(buffer: &mut B) -> Vec<usize> {
+        for line in 0..5 {
+            buffer.add(line);
+        }
+        if let Some(newest) = buffer.get_mut(buffer.len() - 1) {
+            *newest *= 10;
+        }
+        buffer.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_ring_buffer_ops_generic() {
+        let mut stack: RingBuffer<usize, 3> = RingBuffer::new();
+        let mut heap: HeapRingBuffer<usize> = HeapRingBuffer::new(3);
+        assert_eq!(scrollback(&mut stack), vec![2, 3, 40]);
+        assert_eq!(scrollback(&mut heap), vec![2, 3, 40]);
+
+        // Default methods.
+        assert!(RingBufferOps::is_full(&stack));
+        assert_eq!(RingBufferOps::try_add(&mut heap, 5), Err(5));
+        assert_eq!(RingBufferOps::peek_oldest(&heap), Some(&2));
+        assert_eq!(RingBufferOps::peek_newest(&stack), Some(&40));
+    }
+}
@@ -0,0 +1,164 @@
+/*
+ *   Copyright (c) 2025 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Both ring buffers are serialized as a struct w/ their `capacity`, `policy`, and
+//! `items` (oldest first), regardless of where the items are in the `internal_storage`.
+//! When deserializing, if there are more items than the capacity, only the newest ones
+//! are kept.
+
+use crate::{FullPolicy, HeapRingBuffer, RingBuffer};
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    ser::{SerializeSeq, SerializeStruct},
+};
+
+/// What is deserialized, which has the same fields as what [serialize] writes.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+#[serde(rename = "RingBuffer")]
+pub(crate) struct Repr<T> {
+    pub(crate) capacity: usize,
+    #[serde(default)]
+    pub(crate) policy: FullPolicy,
+    pub(crate) items: Vec<T>,
+}
+
+impl<T> Repr<T> {
+    /// The newest items that fit in `capacity`, oldest first.
+    pub(crate) fn into_newest(self, capacity: usize) -> impl Iterator<Item = T> {
+        let excess = self.items.len().saturating_sub(capacity);
+        self.items.into_iter().skip(excess)
+    }
+
+    pub(crate) fn into_ring_buffer<const N: usize>(self) -> RingBuffer<T, N> {
+        let mut ring_buffer = RingBuffer::with_policy(self.policy);
+        ring_buffer.extend(self.into_newest(N));
+        ring_buffer
+    }
+
+    /// `capacity` isn't preallocated, so a corrupt or hostile one (eg, `usize::MAX`)
+    /// doesn't panic or abort.
+    pub(crate) fn into_heap_ring_buffer(self, capacity: usize) -> HeapRingBuffer<T> {
+        HeapRingBuffer::from_newest_items(capacity, self.policy, self.items)
+    }
+}
+
+/// The items from [RingBuffer::as_slices] or [HeapRingBuffer::as_slices], as one sequence.
+struct Items<'a, T>(&'a [T], &'a [T]);
+
+impl<T: Serialize> Serialize for Items<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.len() + self.1.len()))?;
+        for item in self.0.iter().chain(self.1) {
+            seq.serialize_element(item)?;
+        }
+        seq.end()
+    }
+}
+
+pub(crate) fn serialize<S: Serializer, T: Serialize>(
+    capacity: usize,
+    policy: FullPolicy,
+    (first, second): (&[T], &[T]),
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut state = serializer.serialize_struct("RingBuffer", 3)?;
+    state.serialize_field("capacity", &capacity)?;
+    state.serialize_field("policy", &policy)?;
+    state.serialize_field("items", &Items(first, second))?;
+    state.end()
+}
+
+impl<T: Serialize, const N: usize> Serialize for RingBuffer<T, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(N, self.policy(), self.as_slices(), serializer)
+    }
+}
+
+/// The serialized `capacity` is ignored, since it is always `N`.
+impl<'de, T: Deserialize<'de>, const N: usize> Deserialize<'de> for RingBuffer<T, N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Repr::deserialize(deserializer)?.into_ring_buffer())
+    }
+}
+
+impl<T: Serialize> Serialize for HeapRingBuffer<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self.capacity(), self.policy(), self.as_slices(), serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for HeapRingBuffer<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = Repr::deserialize(deserializer)?;
+        let capacity = repr.capacity;
+        Ok(repr.into_heap_ring_buffer(capacity))
+    }
+}
+
+#[cfg(test)]
+mod tests_serde_impl {
+    use super::*;
+
+    #[test]
+    fn test_serde_logical_order() {
+        let mut ring_buffer: RingBuffer<String, 3> = RingBuffer::new();
+        ring_buffer.extend(["ls", "cd", "pwd", "exit"].map(String::from));
+
+        // Wrapped around, so the storage order isn't the logical order.
+        assert!(!ring_buffer.as_slices().1.is_empty());
+        let json = serde_json::to_string(&ring_buffer).unwrap();
+        assert_eq!(
+            json,
+            r#"{"capacity":3,"policy":"Overwrite","items":["cd","pwd","exit"]}"#
+        );
+
+        let round_trip: RingBuffer<String, 3> = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_trip, ring_buffer);
+        let heap: HeapRingBuffer<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(heap.capacity(), 3);
+        assert_eq!(
+            heap.iter().collect::<Vec<_>>(),
+            ring_buffer.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(serde_json::to_string(&heap).unwrap(), json);
+    }
+
+    #[test]
+    fn test_serde_keeps_newest_items() {
+        let json = r#"{"capacity":4,"policy":"Reject","items":[1,2,3,4]}"#;
+
+        let ring_buffer: RingBuffer<i32, 2> = serde_json::from_str(json).unwrap();
+        assert_eq!(ring_buffer.policy(), FullPolicy::Reject);
+        assert_eq!(ring_buffer.iter().collect::<Vec<_>>(), vec![&3, &4]);
+
+        // Missing policy uses the default, and extra items are dropped.
+        let json = r#"{"capacity":1,"items":[1,2]}"#;
+        let heap: HeapRingBuffer<i32> = serde_json::from_str(json).unwrap();
+        assert_eq!(heap.policy(), FullPolicy::Overwrite);
+        assert_eq!(heap.iter().collect::<Vec<_>>(), vec![&2]);
+    }
+
+    #[test]
+    fn test_serde_huge_capacity_is_not_preallocated() {
+        let json = format!(r#"{{"capacity":{},"items":[1,2]}}"#, usize::MAX);
+        let mut heap: HeapRingBuffer<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(heap.capacity(), usize::MAX);
+        heap.add(3);
+        assert_eq!(heap.iter().collect::<Vec<_>>(), vec![&1, &2, &3]);
+    }
+}
@@ -0,0 +1,224 @@
+/*
+ *   Copyright (c) 2025 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! A compact binary snapshot of a ring buffer, eg, to persist command history across
+//! restarts. It is a small header, followed by the same fields as the `serde`
+//! representation, encoded w/ [postcard](https://docs.rs/postcard).
+//!
+//! A snapshot can be restored into a buffer w/ a different capacity, in which case only
+//! the newest items that fit are kept.
+
+use crate::{HeapRingBuffer, RingBuffer, serde_impl};
+use serde::{Serialize, de::DeserializeOwned};
+use std::fmt;
+
+pub mod constants {
+    /// The first bytes of every snapshot.
+    pub const SNAPSHOT_MAGIC: &[u8; 4] = b"RBUF";
+    /// Bumped whenever the snapshot format changes.
+    pub const SNAPSHOT_VERSION: u8 = 1;
+}
+use constants::*;
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The bytes don't start w/ [SNAPSHOT_MAGIC].
+    InvalidHeader,
+    /// The snapshot has a different [SNAPSHOT_VERSION] (older or newer) than this crate.
+    UnsupportedVersion(u8),
+    Encoding(postcard::Error),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::InvalidHeader => write!(f, "not a ring buffer snapshot"),
+            SnapshotError::UnsupportedVersion(version) => {
+                write!(f, "unsupported ring buffer snapshot version: {version}")
+            }
+            SnapshotError::Encoding(error) => write!(f, "invalid ring buffer snapshot: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SnapshotError::Encoding(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<postcard::Error> for SnapshotError {
+    fn from(error: postcard::Error) -> Self {
+        SnapshotError::Encoding(error)
+    }
+}
+
+/// Write the header, and then `value` (which is either ring buffer).
+fn try_encode(value: &impl Serialize) -> Result<Vec<u8>, SnapshotError> {
+    let mut bytes = Vec::from(*SNAPSHOT_MAGIC);
+    bytes.push(SNAPSHOT_VERSION);
+    Ok(postcard::to_extend(value, bytes)?)
+}
+
+/// Check the header, and then read the rest.
+fn try_decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<serde_impl::Repr<T>, SnapshotError> {
+    let Some(rest) = bytes.strip_prefix(SNAPSHOT_MAGIC) else {
+        return Err(SnapshotError::InvalidHeader);
+    };
+    match rest.split_first() {
+        Some((&SNAPSHOT_VERSION, rest)) => Ok(postcard::from_bytes(rest)?),
+        Some((&version, _)) => Err(SnapshotError::UnsupportedVersion(version)),
+        None => Err(SnapshotError::InvalidHeader),
+    }
+}
+
+impl<T: Serialize, const N: usize> RingBuffer<T, N> {
+    pub fn try_snapshot(&self) -> Result<Vec<u8>, SnapshotError> {
+        try_encode(self)
+    }
+}
+
+impl<T: DeserializeOwned, const N: usize> RingBuffer<T, N> {
+    /// If the snapshot has more items than `N`, only the newest ones are kept.
+    pub fn try_restore(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        Ok(try_decode(bytes)?.into_ring_buffer())
+    }
+}
+
+impl<T: Serialize> HeapRingBuffer<T> {
+    pub fn try_snapshot(&self) -> Result<Vec<u8>, SnapshotError> {
+        try_encode(self)
+    }
+}
+
+impl<T: DeserializeOwned> HeapRingBuffer<T> {
+    /// Restore w/ the same capacity as when the snapshot was taken.
+    pub fn try_restore(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        let repr = try_decode(bytes)?;
+        let capacity = repr.capacity;
+        Ok(repr.into_heap_ring_buffer(capacity))
+    }
+
+    /// Restore w/ a new `capacity`. If the snapshot has more items than that, only the
+    /// newest ones are kept.
+    pub fn try_restore_with_capacity(bytes: &[u8], capacity: usize) -> Result<Self, SnapshotError> {
+        Ok(try_decode(bytes)?.into_heap_ring_buffer(capacity))
+    }
+}
+
+#[cfg(test)]
+mod tests_snapshot {
+    use super::*;
+    use crate::FullPolicy;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut history: RingBuffer<String, 3> = RingBuffer::with_policy(FullPolicy::Reject);
+        history.extend(["ls", "cd", "pwd"].map(String::from));
+        history.remove();
+        history.add("exit".to_string()); // Wrapped around.
+
+        let bytes = history.try_snapshot().unwrap();
+        // Header, capacity, policy, count, and each item's length + bytes.
+        assert_eq!(bytes.len(), 5 + 1 + 1 + 1 + (1 + 2) + (1 + 3) + (1 + 4));
+
+        let restored: RingBuffer<String, 3> = RingBuffer::try_restore(&bytes).unwrap();
+        assert_eq!(restored, history);
+
+        let heap: HeapRingBuffer<String> = HeapRingBuffer::try_restore(&bytes).unwrap();
+        assert_eq!(heap.capacity(), 3);
+        assert_eq!(heap.policy(), FullPolicy::Reject);
+        assert_eq!(heap.try_snapshot().unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_snapshot_capacity_change_keeps_newest() {
+        let mut history: HeapRingBuffer<u32> = HeapRingBuffer::new(5);
+        history.extend(1..=5);
+        let bytes = history.try_snapshot().unwrap();
+
+        let smaller: RingBuffer<u32, 2> = RingBuffer::try_restore(&bytes).unwrap();
+        assert_eq!(smaller.iter().collect::<Vec<_>>(), vec![&4, &5]);
+
+        let smaller = HeapRingBuffer::<u32>::try_restore_with_capacity(&bytes, 3).unwrap();
+        assert_eq!(smaller.iter().collect::<Vec<_>>(), vec![&3, &4, &5]);
+
+        let mut bigger: RingBuffer<u32, 8> = RingBuffer::try_restore(&bytes).unwrap();
+        assert_eq!(bigger.len(), 5);
+        bigger.add(6);
+        assert_eq!(bigger.peek_newest(), Some(&6));
+    }
+
+    #[test]
+    fn test_snapshot_huge_capacity() {
+        // A corrupt or hostile snapshot, which would panic or abort if its capacity were
+        // preallocated.
+        let bytes = try_encode(&serde_impl::Repr {
+            capacity: usize::MAX,
+            policy: FullPolicy::Overwrite,
+            items: vec![1u32, 2],
+        })
+        .unwrap();
+
+        let heap = HeapRingBuffer::<u32>::try_restore(&bytes).unwrap();
+        assert_eq!(heap.capacity(), usize::MAX);
+        assert_eq!(heap.iter().collect::<Vec<_>>(), vec![&1, &2]);
+
+        let fixed: RingBuffer<u32, 1> = RingBuffer::try_restore(&bytes).unwrap();
+        assert_eq!(fixed.iter().collect::<Vec<_>>(), vec![&2]);
+    }
+
+    #[test]
+    fn test_snapshot_errors() {
+        let bytes = RingBuffer::<u32, 2>::new().try_snapshot().unwrap();
+
+        assert!(matches!(
+            RingBuffer::<u32, 2>::try_restore(b"nope"),
+            Err(SnapshotError::InvalidHeader)
+        ));
+        assert!(matches!(
+            RingBuffer::<u32, 2>::try_restore(SNAPSHOT_MAGIC),
+            Err(SnapshotError::InvalidHeader)
+        ));
+
+        let mut newer = bytes.clone();
+        newer[4] = SNAPSHOT_VERSION + 1;
+        assert!(matches!(
+            RingBuffer::<u32, 2>::try_restore(&newer),
+            Err(SnapshotError::UnsupportedVersion(_))
+        ));
+
+        let mut older = bytes.clone();
+        older[4] = SNAPSHOT_VERSION - 1;
+        assert!(matches!(
+            RingBuffer::<u32, 2>::try_restore(&older),
+            Err(SnapshotError::UnsupportedVersion(_))
+        ));
+
+        let truncated = &bytes[..bytes.len() - 1];
+        let error = RingBuffer::<u32, 2>::try_restore(truncated).unwrap_err();
+        assert!(matches!(error, SnapshotError::Encoding(_)));
+        assert!(
+            error
+                .to_string()
+                .starts_with("invalid ring buffer snapshot")
+        );
+    }
+}
@@ -0,0 +1,620 @@
+/*
+ *   Copyright (c) 2025 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! A lock-free, single-producer / single-consumer (SPSC) ring buffer, w/ the same const
+//! generic capacity as [crate::RingBuffer].
+//!
+//! [channel] splits it into a [Producer] and a [Consumer] handle, which can be moved to
+//! different threads. Neither handle ever blocks or retries: [Producer::try_push] and
+//! [Consumer::try_pop] each finish in a bounded number of steps (they are wait-free).
+//!
+//! [async_channel] is the same, except that the [AsyncConsumer] can `.await` data.
+//!
+//! # How it works
+//!
+//! * `head` is the number of items ever pushed, and is only written by the producer.
+//! * `tail` is the number of items ever popped, and is only written by the consumer.
+//! * Both wrap around at the largest multiple of `N` that fits in a `usize`, so that the
+//!   slot of each item (its counter modulo `N`) doesn't jump when they wrap, for any `N`.
+//! * Both are in their own cache line (w/ [CachePadded]), so that the two threads don't
+//!   invalidate each other's cache lines when they write them (false sharing).
+//! * The slots between `tail` and `head` (modulo `N`) are initialized. The producer
+//!   publishes a slot w/ a `Release` store of `head`, and the consumer hands it back w/ a
+//!   `Release` store of `tail`.
+//!
+//! # Examples
+//!
+//! ```
+//! use ringbuffer::spsc;
+//!
+//! let (mut producer, mut consumer) = spsc::channel::<i32, 2>();
+//!
+//! let handle = std::thread::spawn(move || {
+//!     for item in 0..10 {
+//!         let mut item = item;
+//!         while let Err(it) = producer.try_push(item) {
+//!             item = it;
+//!             std::thread::yield_now();
+//!         }
+//!     }
+//! });
+//!
+//! let mut received = vec![];
+//! while received.len() < 10 {
+//!     match consumer.try_pop() {
+//!         Some(item) => received.push(item),
+//!         None => std::thread::yield_now(),
+//!     }
+//! }
+//! handle.join().unwrap();
+//!
+//! assert_eq!(received, (0..10).collect::<Vec<_>>());
+//! ```
+
+use crossbeam_utils::CachePadded;
+use std::{
+    future::poll_fn,
+    mem::MaybeUninit,
+    task::{Context, Poll},
+};
+use sync::{Arc, AtomicBool, AtomicUsize, AtomicWaker, Ordering, UnsafeCell};
+
+/// Swaps in the [loom](https://docs.rs/loom) types when testing w/ `--cfg loom`.
+#[cfg(loom)]
+mod sync {
+    pub(crate) use loom::{
+        cell::UnsafeCell,
+        future::AtomicWaker,
+        sync::{
+            Arc,
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+        },
+    };
+}
+
+/// The std types, w/ the same API as their [loom](https://docs.rs/loom) counterparts.
+#[cfg(not(loom))]
+mod sync {
+    pub(crate) use std::sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    };
+    use std::task::Waker;
+
+    #[derive(Debug)]
+    pub(crate) struct UnsafeCell<T>(std::cell::UnsafeCell<T>);
+
+    impl<T> UnsafeCell<T> {
+        pub(crate) fn new(data: T) -> Self {
+            Self(std::cell::UnsafeCell::new(data))
+        }
+
+        pub(crate) fn with_mut<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
+            f(self.0.get())
+        }
+    }
+
+    #[derive(Debug, Default)]
+    pub(crate) struct AtomicWaker(atomic_waker::AtomicWaker);
+
+    impl AtomicWaker {
+        pub(crate) fn new() -> Self {
+            Self::default()
+        }
+
+        pub(crate) fn register_by_ref(&self, waker: &Waker) {
+            self.0.register(waker);
+        }
+
+        pub(crate) fn wake(&self) {
+            self.0.wake();
+        }
+    }
+}
+
+/// Create a SPSC ring buffer that holds up to `N` items, and split it into its two
+/// handles.
+pub fn channel<T, const N: usize>() -> (Producer<T, N>, Consumer<T, N>) {
+    let shared = Arc::new(Shared::new());
+    (
+        Producer {
+            shared: shared.clone(),
+            head: 0,
+            cached_tail: 0,
+        },
+        Consumer {
+            shared,
+            tail: 0,
+            cached_head: 0,
+        },
+    )
+}
+
+/// Same as [channel], but the consumer can `.await` data.
+pub fn async_channel<T, const N: usize>() -> (AsyncProducer<T, N>, AsyncConsumer<T, N>) {
+    let (producer, consumer) = channel();
+    (AsyncProducer { producer }, AsyncConsumer { consumer })
+}
+
+mod shared {
+    use super::*;
+
+    /// The state that the [Producer] and [Consumer] share.
+    pub(crate) struct Shared<T, const N: usize> {
+        pub(crate) storage: [UnsafeCell<MaybeUninit<T>>; N],
+        /// Number of items ever pushed.
+        pub(crate) head: CachePadded<AtomicUsize>,
+        /// Number of items ever popped.
+        pub(crate) tail: CachePadded<AtomicUsize>,
+        pub(crate) producer_dropped: AtomicBool,
+        pub(crate) consumer_dropped: AtomicBool,
+        /// Woken when an item is pushed, or the producer is dropped.
+        pub(crate) consumer_waker: AtomicWaker,
+    }
+
+    // SAFETY: Each slot is only accessed by one side at a time. The producer owns the
+    // slots that aren't between `tail` and `head`, and the consumer owns the rest. Items
+    // are moved across threads, so `T: Send` is needed (but not `T: Sync`).
+    unsafe impl<T: Send, const N: usize> Send for Shared<T, N> {}
+    unsafe impl<T: Send, const N: usize> Sync for Shared<T, N> {}
+
+    impl<T, const N: usize> Shared<T, N> {
+        pub(crate) fn new() -> Self {
+            assert!(N > 0, "capacity must be greater than zero");
+            Self {
+                storage: std::array::from_fn(|_| UnsafeCell::new(MaybeUninit::uninit())),
+                head: CachePadded::new(AtomicUsize::new(0)),
+                tail: CachePadded::new(AtomicUsize::new(0)),
+                producer_dropped: AtomicBool::new(false),
+                consumer_dropped: AtomicBool::new(false),
+                consumer_waker: AtomicWaker::new(),
+            }
+        }
+
+        /// Where `head` and `tail` wrap around. If they wrapped at `usize::MAX + 1`
+        /// instead, then `index % N` would jump (and 2 items would share a slot) when they
+        /// do, unless `N` is a power of 2.
+        pub(crate) const WRAP: usize = N * (usize::MAX / N);
+
+        /// The counter after `index`.
+        pub(crate) fn next_index(index: usize) -> usize {
+            if index == Self::WRAP - 1 {
+                0
+            } else {
+                index + 1
+            }
+        }
+
+        /// The number of items between `tail` and `head`.
+        pub(crate) fn distance(head: usize, tail: usize) -> usize {
+            if head >= tail {
+                head - tail
+            } else {
+                head + (Self::WRAP - tail)
+            }
+        }
+
+        /// The slot for the `index`th item (modulo [Self::WRAP]) ever pushed.
+        pub(crate) fn slot(&self, index: usize) -> &UnsafeCell<MaybeUninit<T>> {
+            &self.storage[index % N]
+        }
+
+        /// Only approximate, since the other side may push or pop between the 2 loads.
+        pub(crate) fn len(&self) -> usize {
+            // Load tail first, so that len is never "negative". The consumer can pop and
+            // the producer can push again before head is loaded, which would make len
+            // briefly look larger than N, so clamp it.
+            let tail = self.tail.load(Ordering::Acquire);
+            let head = self.head.load(Ordering::Acquire);
+            Self::distance(head, tail).min(N)
+        }
+    }
+
+    impl<T, const N: usize> Drop for Shared<T, N> {
+        fn drop(&mut self) {
+            // Both handles are gone, so nothing else can touch the slots.
+            let head = self.head.load(Ordering::Acquire);
+            let mut tail = self.tail.load(Ordering::Acquire);
+            while tail != head {
+                // SAFETY: The slots between `tail` and `head` are initialized.
+                self.slot(tail)
+                    .with_mut(|slot| unsafe { (*slot).assume_init_drop() });
+                tail = Self::next_index(tail);
+            }
+        }
+    }
+}
+use shared::Shared;
+
+mod producer {
+    use super::*;
+
+    /// The writing half of a [channel]. It can be moved to another thread, but not
+    /// cloned.
+    pub struct Producer<T, const N: usize> {
+        pub(crate) shared: Arc<Shared<T, N>>,
+        /// Same as `shared.head`, which only this handle writes.
+        pub(crate) head: usize,
+        /// Last known value of `shared.tail`, which is only re-read when the buffer looks
+        /// full.
+        pub(crate) cached_tail: usize,
+    }
+
+    impl<T, const N: usize> Producer<T, N> {
+        /// Push the newest item, or return it if the buffer is full.
+        pub fn try_push(&mut self, value: T) -> Result<(), T> {
+            if Shared::<T, N>::distance(self.head, self.cached_tail) == N {
+                self.cached_tail = self.shared.tail.load(Ordering::Acquire);
+                if Shared::<T, N>::distance(self.head, self.cached_tail) == N {
+                    return Err(value);
+                }
+            }
+
+            // SAFETY: The slot at head isn't between `tail` and `head`, so the consumer
+            // doesn't touch it until `head` is published below.
+            self.shared
+                .slot(self.head)
+                .with_mut(|slot| unsafe { (*slot).write(value) });
+            self.head = Shared::<T, N>::next_index(self.head);
+            self.shared.head.store(self.head, Ordering::Release);
+
+            Ok(())
+        }
+
+        /// The number of items that haven't been popped yet. This is only approximate
+        /// (but never more than `N`), since the other side can change it at any time.
+        pub fn len(&self) -> usize {
+            self.shared.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        pub fn is_full(&self) -> bool {
+            self.len() == N
+        }
+
+        pub fn capacity(&self) -> usize {
+            N
+        }
+
+        /// `true` if the [Consumer] has been dropped, so pushed items will never be
+        /// popped.
+        pub fn is_abandoned(&self) -> bool {
+            self.shared.consumer_dropped.load(Ordering::Acquire)
+        }
+    }
+
+    impl<T, const N: usize> Drop for Producer<T, N> {
+        fn drop(&mut self) {
+            self.shared.producer_dropped.store(true, Ordering::Release);
+            self.shared.consumer_waker.wake();
+        }
+    }
+}
+pub use producer::Producer;
+
+mod consumer {
+    use super::*;
+
+    /// The reading half of a [channel]. It can be moved to another thread, but not
+    /// cloned.
+    pub struct Consumer<T, const N: usize> {
+        pub(crate) shared: Arc<Shared<T, N>>,
+        /// Same as `shared.tail`, which only this handle writes.
+        pub(crate) tail: usize,
+        /// Last known value of `shared.head`, which is only re-read when the buffer looks
+        /// empty.
+        pub(crate) cached_head: usize,
+    }
+
+    impl<T, const N: usize> Consumer<T, N> {
+        /// Pop the oldest item, if there is one.
+        pub fn try_pop(&mut self) -> Option<T> {
+            if self.tail == self.cached_head {
+                self.cached_head = self.shared.head.load(Ordering::Acquire);
+                if self.tail == self.cached_head {
+                    return None;
+                }
+            }
+
+            // SAFETY: The slot at tail is between `tail` and `head`, so it is initialized,
+            // and the producer doesn't touch it until `tail` is published below.
+            let value = self
+                .shared
+                .slot(self.tail)
+                .with_mut(|slot| unsafe { (*slot).assume_init_read() });
+            self.tail = Shared::<T, N>::next_index(self.tail);
+            self.shared.tail.store(self.tail, Ordering::Release);
+
+            Some(value)
+        }
+
+        /// The number of items that haven't been popped yet. This is only approximate
+        /// (but never more than `N`), since the other side can change it at any time.
+        pub fn len(&self) -> usize {
+            self.shared.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        pub fn is_full(&self) -> bool {
+            self.len() == N
+        }
+
+        pub fn capacity(&self) -> usize {
+            N
+        }
+
+        /// `true` if the [Producer] has been dropped, so no more items will be pushed.
+        /// There may still be items left to pop.
+        pub fn is_abandoned(&self) -> bool {
+            self.shared.producer_dropped.load(Ordering::Acquire)
+        }
+    }
+
+    impl<T, const N: usize> Drop for Consumer<T, N> {
+        fn drop(&mut self) {
+            self.shared.consumer_dropped.store(true, Ordering::Release);
+        }
+    }
+}
+pub use consumer::Consumer;
+
+mod asynchronous {
+    use super::*;
+
+    /// The writing half of an [async_channel]. Same as [Producer], but it also wakes the
+    /// [AsyncConsumer].
+    pub struct AsyncProducer<T, const N: usize> {
+        pub(crate) producer: Producer<T, N>,
+    }
+
+    impl<T, const N: usize> AsyncProducer<T, N> {
+        /// Push the newest item, or return it if the buffer is full.
+        pub fn try_push(&mut self, value: T) -> Result<(), T> {
+            self.producer.try_push(value)?;
+            self.producer.shared.consumer_waker.wake();
+            Ok(())
+        }
+
+        pub fn len(&self) -> usize {
+            self.producer.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.producer.is_empty()
+        }
+
+        pub fn is_full(&self) -> bool {
+            self.producer.is_full()
+        }
+
+        pub fn capacity(&self) -> usize {
+            N
+        }
+
+        pub fn is_abandoned(&self) -> bool {
+            self.producer.is_abandoned()
+        }
+    }
+
+    /// The reading half of an [async_channel].
+    pub struct AsyncConsumer<T, const N: usize> {
+        pub(crate) consumer: Consumer<T, N>,
+    }
+
+    impl<T, const N: usize> AsyncConsumer<T, N> {
+        /// Wait for the oldest item. Returns `None` once the [AsyncProducer] has been
+        /// dropped and all its items have been popped.
+        pub async fn pop(&mut self) -> Option<T> {
+            poll_fn(|cx| self.poll_pop(cx)).await
+        }
+
+        pub fn poll_pop(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+            if let Some(value) = self.consumer.try_pop() {
+                return Poll::Ready(Some(value));
+            }
+
+            self.consumer
+                .shared
+                .consumer_waker
+                .register_by_ref(cx.waker());
+
+            // Check again, in case an item was pushed (or the producer was dropped) before
+            // the waker was registered.
+            if self.consumer.is_abandoned() {
+                // Everything that was pushed is visible now.
+                return Poll::Ready(self.consumer.try_pop());
+            }
+            match self.consumer.try_pop() {
+                Some(value) => Poll::Ready(Some(value)),
+                None => Poll::Pending,
+            }
+        }
+
+        pub fn try_pop(&mut self) -> Option<T> {
+            self.consumer.try_pop()
+        }
+
+        pub fn len(&self) -> usize {
+            self.consumer.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.consumer.is_empty()
+        }
+
+        pub fn is_full(&self) -> bool {
+            self.consumer.is_full()
+        }
+
+        pub fn capacity(&self) -> usize {
+            N
+        }
+
+        pub fn is_abandoned(&self) -> bool {
+            self.consumer.is_abandoned()
+        }
+    }
+}
+pub use asynchronous::{AsyncConsumer, AsyncProducer};
+
+#[cfg(all(test, not(loom)))]
+mod tests_spsc {
+    use super::*;
+    use std::{rc::Rc, thread, time::Duration};
+
+    #[test]
+    fn test_spsc_push_pop() {
+        let (mut producer, mut consumer) = channel::<i32, 2>();
+        assert_eq!(producer.capacity(), 2);
+        assert!(consumer.is_empty());
+        assert_eq!(consumer.try_pop(), None);
+
+        assert_eq!(producer.try_push(1), Ok(()));
+        assert_eq!(producer.try_push(2), Ok(()));
+        assert!(producer.is_full());
+        assert_eq!(producer.try_push(3), Err(3));
+
+        assert_eq!(consumer.try_pop(), Some(1));
+        assert_eq!(producer.try_push(3), Ok(())); // Wrap around.
+        assert_eq!(consumer.len(), 2);
+        assert_eq!(consumer.try_pop(), Some(2));
+        assert_eq!(consumer.try_pop(), Some(3));
+        assert_eq!(consumer.try_pop(), None);
+
+        assert!(!consumer.is_abandoned());
+        drop(producer);
+        assert!(consumer.is_abandoned());
+    }
+
+    /// Same as [channel], but `head` and `tail` start at `start` instead of 0.
+    fn channel_starting_at<T, const N: usize>(start: usize) -> (Producer<T, N>, Consumer<T, N>) {
+        let (mut producer, mut consumer) = channel();
+        producer.shared.head.store(start, Ordering::Relaxed);
+        producer.shared.tail.store(start, Ordering::Relaxed);
+        producer.head = start;
+        producer.cached_tail = start;
+        consumer.tail = start;
+        consumer.cached_head = start;
+        (producer, consumer)
+    }
+
+    #[test]
+    fn test_spsc_counters_wrap_around() {
+        // 3 isn't a power of 2, so `usize::MAX + 1` isn't a multiple of it.
+        let start = usize::MAX - 4;
+        let (mut producer, mut consumer) = channel_starting_at::<usize, 3>(start);
+        let mut next_pop = 0;
+        for item in 0..20 {
+            if producer.is_full() {
+                assert_eq!(producer.try_push(item), Err(item));
+                assert_eq!(consumer.try_pop(), Some(next_pop));
+                next_pop += 1;
+            }
+            assert_eq!(producer.try_push(item), Ok(()));
+            assert_eq!(consumer.len(), item + 1 - next_pop);
+        }
+        while let Some(item) = consumer.try_pop() {
+            assert_eq!(item, next_pop);
+            next_pop += 1;
+        }
+        assert_eq!(next_pop, 20);
+
+        // The unpopped items are dropped across the wrap around too.
+        let item = Rc::new(());
+        {
+            let (mut producer, _consumer) =
+                channel_starting_at::<Rc<()>, 3>(Shared::<Rc<()>, 3>::WRAP - 1);
+            for _ in 0..3 {
+                producer.try_push(item.clone()).unwrap();
+            }
+            assert_eq!(Rc::strong_count(&item), 4);
+        }
+        assert_eq!(Rc::strong_count(&item), 1);
+    }
+
+    #[test]
+    fn test_spsc_across_threads() {
+        const COUNT: usize = 10_000;
+        let (mut producer, mut consumer) = channel::<usize, 16>();
+
+        let handle = thread::spawn(move || {
+            for item in 0..COUNT {
+                let mut item = item;
+                while let Err(it) = producer.try_push(item) {
+                    item = it;
+                    thread::yield_now();
+                }
+            }
+        });
+
+        let mut expected = 0;
+        while expected < COUNT {
+            match consumer.try_pop() {
+                Some(item) => {
+                    assert_eq!(item, expected);
+                    expected += 1;
+                }
+                None => thread::yield_now(),
+            }
+        }
+        handle.join().unwrap();
+        assert!(consumer.is_abandoned());
+    }
+
+    #[test]
+    fn test_spsc_drops_unpopped_items() {
+        let item = Rc::new(());
+        {
+            let (mut producer, mut consumer) = channel::<Rc<()>, 3>();
+            for _ in 0..3 {
+                producer.try_push(item.clone()).unwrap();
+            }
+            drop(consumer.try_pop());
+            assert_eq!(Rc::strong_count(&item), 3);
+        }
+        assert_eq!(Rc::strong_count(&item), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_spsc_async_consumer() {
+        let (mut producer, mut consumer) = async_channel::<usize, 4>();
+
+        let handle = tokio::spawn(async move {
+            for item in 0..10 {
+                let mut item = item;
+                while let Err(it) = producer.try_push(item) {
+                    item = it;
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                }
+            }
+        });
+
+        let mut received = vec![];
+        while let Some(item) = consumer.pop().await {
+            received.push(item);
+        }
+        handle.await.unwrap();
+
+        assert_eq!(received, (0..10).collect::<Vec<_>>());
+    }
+}
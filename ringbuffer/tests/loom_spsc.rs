@@ -0,0 +1,96 @@
+/*
+ *   Copyright (c) 2025 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Checks every interleaving of the SPSC ring buffer w/ [loom](https://docs.rs/loom).
+//! Run w/ `RUSTFLAGS="--cfg loom" cargo test --release --test loom_spsc`.
+
+#![cfg(loom)]
+
+use loom::{future::block_on, thread};
+use ringbuffer::spsc;
+
+#[test]
+fn test_push_pop_across_threads() {
+    loom::model(|| {
+        let (mut producer, mut consumer) = spsc::channel::<usize, 2>();
+
+        let handle = thread::spawn(move || {
+            for item in 0..3 {
+                let mut item = item;
+                while let Err(it) = producer.try_push(item) {
+                    item = it;
+                    thread::yield_now();
+                }
+            }
+        });
+
+        let mut received = vec![];
+        while received.len() < 3 {
+            assert!(consumer.len() <= 2);
+            match consumer.try_pop() {
+                Some(item) => received.push(item),
+                None => thread::yield_now(),
+            }
+        }
+        handle.join().unwrap();
+
+        assert_eq!(received, vec![0, 1, 2]);
+        assert_eq!(consumer.try_pop(), None);
+    });
+}
+
+#[test]
+fn test_unpopped_items_are_dropped() {
+    use loom::sync::Arc;
+
+    loom::model(|| {
+        let item = Arc::new(());
+        let (mut producer, mut consumer) = spsc::channel::<Arc<()>, 2>();
+
+        let item_clone = item.clone();
+        let handle = thread::spawn(move || {
+            let _ = producer.try_push(item_clone.clone());
+            let _ = producer.try_push(item_clone);
+        });
+
+        let popped = consumer.try_pop();
+        handle.join().unwrap();
+        drop(popped);
+        drop(consumer);
+
+        assert_eq!(Arc::strong_count(&item), 1);
+    });
+}
+
+#[test]
+fn test_async_consumer_is_woken() {
+    loom::model(|| {
+        let (mut producer, mut consumer) = spsc::async_channel::<usize, 1>();
+
+        let handle = thread::spawn(move || {
+            let _ = producer.try_push(1);
+            // Dropping the producer ends the stream.
+        });
+
+        let first = block_on(consumer.pop());
+        let second = block_on(consumer.pop());
+        handle.join().unwrap();
+
+        assert_eq!(first, Some(1));
+        assert_eq!(second, None);
+    });
+}
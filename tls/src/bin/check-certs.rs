@@ -17,31 +17,58 @@
 
 use crossterm::style::Stylize as _;
 use r3bl_tui::ok;
+use std::path::Path;
 use tls::{
-    certificate_ops::server_load_server_cert_chain, key_ops::server_load_single_private_key,
-    root_cert_store_ops::client_create_root_cert_store,
+    binary_data::{self, CA_CERT_PEM_FILENAME, CLIENT_CERT_PEM_FILENAME, SERVER_CERT_PEM_FILENAME},
+    cert_info,
+    constants::SERVER_NAME,
+    key_ops::server_load_single_private_key,
 };
 
 fn main() -> miette::Result<()> {
     // Load the server keys (from server-key.pem).
-    let server_key = server_load_single_private_key()?;
-    println!("{}: {:?}", "Server key".blue().underlined(), server_key);
+    _ = server_load_single_private_key()?;
+    println!("{}: {}", "Server key".blue().underlined(), "loaded".green());
 
-    // Load the server certificate (from server.pem).
-    let server_cert_chain = server_load_server_cert_chain()?;
-    println!(
-        "{}: {:?}",
-        "Server certificate".blue().underlined(),
-        server_cert_chain
-    );
+    // Display the CA certificate (from ca.pem) and the server certificate (from
+    // server.pem).
+    let ca_pem = binary_data::get_ca_cert_pem_for_client_binary();
+    let server_pem = binary_data::get_server_cert_pem_for_server_binary();
+    print_summaries(CA_CERT_PEM_FILENAME, ca_pem)?;
+    print_summaries(SERVER_CERT_PEM_FILENAME, server_pem)?;
 
-    // Create the root certificate store.
-    let root_store = client_create_root_cert_store()?;
-    println!(
-        "{}: {:?}",
-        "Root certificate store".blue().underlined(),
-        root_store
-    );
+    // Display the client certificate (from client.pem), if there is one, since it is only
+    // used for mutual TLS.
+    if Path::new(CLIENT_CERT_PEM_FILENAME).exists() {
+        let client_pem = std::fs::read(CLIENT_CERT_PEM_FILENAME)
+            .map_err(|it| miette::miette!("Failed to read {CLIENT_CERT_PEM_FILENAME}: {it}"))?;
+        print_summaries(CLIENT_CERT_PEM_FILENAME, &client_pem)?;
+    }
 
+    // Verify that the server certificate is signed by the CA, like the client does.
+    match cert_info::try_verify_server_cert(server_pem, ca_pem, SERVER_NAME) {
+        Ok(_) => println!(
+            "🎉 Server certificate is signed by CA, for {}: {}",
+            SERVER_NAME.blue(),
+            "verified".green()
+        ),
+        Err(error) => println!(
+            "❗ Server certificate is signed by CA, for {}: {} {error}",
+            SERVER_NAME.blue(),
+            "failed".red()
+        ),
+    }
+
+    ok!()
+}
+
+fn print_summaries(filename: &str, pem_data: &[u8]) -> miette::Result<()> {
+    let summaries = cert_info::try_summarize_certs(pem_data)?;
+    if summaries.is_empty() {
+        miette::bail!("No certificates found in the {filename} file");
+    }
+    for summary in summaries {
+        println!("{}\n{summary}", filename.blue().underlined());
+    }
     ok!()
 }
@@ -15,19 +15,18 @@
  *   limitations under the License.
  */
 
+use clap::Parser as _;
 use crossterm::style::Stylize as _;
 use miette::IntoDiagnostic;
-use r3bl_tui::ok;
-use tls::common_io;
+use r3bl_tui::{ok, ReadlineAsync};
+use tls::{common_io, constants::PROMPT, try_summarize_handshake, CliArgs};
 use tokio::io::split;
 
 #[tokio::main]
 async fn main() -> miette::Result<()> {
-    let addr = format!(
-        "{}:{}",
-        common_io::constants::HOST,
-        common_io::constants::PORT
-    );
+    let cli_args = CliArgs::parse();
+    let addr = cli_args.addr();
+    let tls_config = cli_args.tls.to_tls_config();
     println!(
         "{} {} {} {}",
         "Starting client to".yellow().italic(),
@@ -37,9 +36,9 @@ async fn main() -> miette::Result<()> {
     );
 
     /*
-    Handle SIGINT while waiting for incoming connection, before accepting it. This is a
-    background task that will run until the program exits. Even after the connection is
-    accepted, this will continue to run and exit the program when Ctrl+C is pressed.
+    Handle SIGINT while connecting to the server. This is a background task that will run
+    until the program exits. Once the user is typing, Ctrl+C is handled by
+    [ReadlineAsync] instead.
     */
     tokio::spawn(async {
         _ = tokio::signal::ctrl_c().await;
@@ -58,13 +57,13 @@ async fn main() -> miette::Result<()> {
         .into_diagnostic()?;
 
     // Upgrade to secure connection.
-    let tls_connector = tls::tls_ops::try_create_client_tls_connector()?;
-    let server_name = rustls::pki_types::ServerName::try_from(common_io::constants::SERVER_NAME)
-        .into_diagnostic()?;
+    let tls_connector = tls_config.try_create_client_tls_connector()?;
+    let server_name = tls_config.try_server_name()?;
     let secure_stream = tls_connector
         .connect(server_name, tcp_stream)
         .await
         .into_diagnostic()?;
+    let handshake_summary = try_summarize_handshake(secure_stream.get_ref().1)?;
     let (reader, writer) = split(secure_stream);
 
     println!(
@@ -73,13 +72,21 @@ async fn main() -> miette::Result<()> {
         "securely".green().italic().bold().underlined(),
         "to server".green().italic()
     );
+    println!("{handshake_summary}");
 
     /*
-    Read from client and write to client until either:
-    - Ctrl+C pressed by user.
-    - client side of connection sends EOF or fails.
+    Send what the user types to the server, and display what the server sends, until
+    either:
+    - Ctrl+C or Ctrl+D pressed by user.
+    - server side of connection sends EOF or fails.
+    If stdin isn't interactive (eg: a file is piped into the client), then send all of it.
     */
-    common_io::read_write(reader, writer).await?;
+    match ReadlineAsync::try_new(Some(PROMPT)).await? {
+        Some(readline_async) => {
+            common_io::read_write_interactive(reader, writer, readline_async).await?
+        }
+        None => common_io::read_write(reader, writer).await?,
+    }
 
     println!(
         "{} {}",
@@ -15,19 +15,29 @@
  *   limitations under the License.
  */
 
+use clap::Parser as _;
 use crossterm::style::Stylize;
 use miette::IntoDiagnostic;
 use r3bl_tui::ok;
-use tls::common_io;
-use tokio::{io::split, net::TcpListener};
+use std::{net::SocketAddr, sync::Arc};
+use tls::{
+    serve, try_peer_identity, try_read_line_until_shutdown, try_watch_cert_files, CliArgs,
+    ReloadableServerConfig,
+};
+use tokio::{
+    io::{AsyncWriteExt as _, BufReader},
+    net::{TcpListener, TcpStream},
+};
+use tokio_rustls::server::TlsStream;
+use tokio_util::sync::CancellationToken;
 
 #[tokio::main]
 async fn main() -> miette::Result<()> {
-    let addr = format!(
-        "{}:{}",
-        common_io::constants::HOST,
-        common_io::constants::PORT
-    );
+    let cli_args = CliArgs::parse();
+    let addr = cli_args.addr();
+    let server_config = Arc::new(ReloadableServerConfig::try_new(
+        cli_args.tls.to_tls_config(),
+    )?);
     println!(
         "{} {} {} {}",
         "Starting".yellow().italic(),
@@ -36,12 +46,16 @@ async fn main() -> miette::Result<()> {
         addr.as_str().blue().underlined()
     );
 
+    // Reload the certificate when the cert, key, or CA files change. New handshakes use
+    // the new certificate, and existing connections keep working.
+    let _watcher = try_watch_cert_files(server_config.clone())?;
+
     /*
-    Handle SIGINT while waiting for incoming connection, before accepting it. This is a
-    background task that will run until the program exits. Even after the connection is
-    accepted, this will continue to run and exit the program when Ctrl+C is pressed.
+    Accept insecure connections from clients, and upgrade each one to a secure connection
+    in its own task, until Ctrl+C is pressed. Then wait for the open connections to finish.
     */
-    tokio::spawn(async {
+    let listener = TcpListener::bind(addr.as_str()).await.into_diagnostic()?;
+    let shutdown = async {
         _ = tokio::signal::ctrl_c().await;
         println!(
             "{} {} {}",
@@ -49,46 +63,58 @@ async fn main() -> miette::Result<()> {
             "SIGINT".red().italic(),
             "while running server".blue()
         );
-        std::process::exit(0);
-    });
+    };
+    serve(listener, server_config, shutdown, echo).await?;
 
-    /*
-    Accept insecure connections from clients. `listener.accept()` is a blocking call
-    that will wait until a client connects to the server.
-    */
-    let listener = TcpListener::bind(addr.as_str()).await.into_diagnostic()?;
-    let (stream, _) = listener.accept().await.into_diagnostic()?;
+    println!(
+        "{} {}",
+        "Exiting".yellow().italic(),
+        "server".yellow().italic()
+    );
 
-    // Upgrade to secure connection.
-    let tls_acceptor = tls::tls_ops::try_create_server_tls_acceptor()?;
-    let secure_stream = tls_acceptor.accept(stream).await.into_diagnostic()?;
-    let (reader, writer) = split(secure_stream);
+    ok!()
+}
 
+/// Write each line that the client sends back to it, until either:
+/// - the client side of the connection sends EOF or fails.
+/// - the server is shutting down, and the client isn't in the middle of sending a line.
+async fn echo(
+    secure_stream: TlsStream<TcpStream>,
+    addr: SocketAddr,
+    shutdown: CancellationToken,
+) -> miette::Result<()> {
+    // The client's identity, if it presented a certificate (mutual TLS).
+    let client = match try_peer_identity(secure_stream.get_ref().1)? {
+        Some(it) => format!("{addr} ({})", it.subject),
+        None => addr.to_string(),
+    };
     println!(
         "{} {} {}",
         "Accepted".green().italic(),
         "secure".green().italic().bold().underlined(),
-        "connection from client".green().italic()
+        format!("connection from client: {client}").green().italic()
     );
 
-    /*
-    Read from client and write to client until either:
-    - Ctrl+C pressed by user.
-    - client side of connection sends EOF or fails.
-    */
-    common_io::read_write(reader, writer).await?;
-
-    // Close all connections and exit.
-    println!(
-        "{} {}",
-        "Closing".yellow().italic(),
-        "all connections".yellow().italic()
-    );
+    // On shutdown, the line that is being received is still echoed.
+    let (reader, mut writer) = tokio::io::split(secure_stream);
+    let mut reader = BufReader::new(reader);
+    while let Some(line) = try_read_line_until_shutdown(&mut reader, &shutdown).await? {
+        println!("{} {}", format!("{client}:").blue(), line);
+        writer
+            .write_all(format!("{line}\n").as_bytes())
+            .await
+            .into_diagnostic()?;
+        writer.flush().await.into_diagnostic()?;
+    }
 
+    // Send the TLS close_notify alert.
+    writer.shutdown().await.into_diagnostic()?;
     println!(
         "{} {}",
-        "Exiting".yellow().italic(),
-        "server".yellow().italic()
+        "Closed".yellow().italic(),
+        format!("connection from client: {client}")
+            .yellow()
+            .italic()
     );
 
     ok!()
@@ -0,0 +1,77 @@
+/*
+ *   Copyright (c) 2024 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! A TLS proxy in front of a plaintext service (eg: `tcp-api-server` or `rcat`), or in
+//! front of a TLS service for plaintext clients. For example:
+//!
+//! ```sh
+//! cargo run --bin tls-proxy -- --listen localhost:8443 --upstream localhost:3000
+//! cargo run --bin tls-proxy -- --mode originate --listen localhost:3001 \
+//!   --upstream localhost:8443 --server-name localhost
+//! ```
+
+use clap::Parser as _;
+use crossterm::style::Stylize as _;
+use miette::IntoDiagnostic as _;
+use r3bl_tui::ok;
+use tls::{serve_proxy, ProxyCliArgs};
+use tokio::net::TcpListener;
+
+#[tokio::main]
+async fn main() -> miette::Result<()> {
+    let cli_args = ProxyCliArgs::parse();
+    let tls_config = cli_args.tls.to_tls_config();
+    println!(
+        "{} {} {} {} {}",
+        "Starting".yellow().italic(),
+        format!("{} proxy", cli_args.mode).yellow().italic().bold(),
+        "at:".yellow().italic(),
+        cli_args.listen.as_str().blue().underlined(),
+        format!("-> {}", cli_args.upstream).blue()
+    );
+
+    let listener = TcpListener::bind(cli_args.listen.as_str())
+        .await
+        .into_diagnostic()?;
+    let shutdown = async {
+        _ = tokio::signal::ctrl_c().await;
+        println!(
+            "{} {} {}",
+            "Received".red().italic(),
+            "SIGINT".red().italic(),
+            "while running proxy".blue()
+        );
+    };
+
+    serve_proxy(
+        listener,
+        cli_args.mode,
+        tls_config,
+        cli_args.upstream.clone(),
+        cli_args.idle_timeout(),
+        shutdown,
+    )
+    .await?;
+
+    println!(
+        "{} {}",
+        "Exiting".yellow().italic(),
+        "proxy".yellow().italic()
+    );
+
+    ok!()
+}
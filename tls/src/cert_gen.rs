@@ -0,0 +1,324 @@
+/*
+ *   Copyright (c) 2024 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Generate the CA, server, and client certificates in process (w/ [rcgen]), instead of
+//! downloading `cfssl` and shelling out to it. The same `cfssl` JSON config files in
+//! `certs/config` drive this.
+//!
+//! | Config file       | Struct      | Description                                              |
+//! |-------------------|-------------|----------------------------------------------------------|
+//! | `ca-config.json`  | [CaConfig]  | Validity period and key usages of each profile.          |
+//! | `ca-csr.json`     | [CsrConfig] | Subject and key type of the CA.                          |
+//! | `server-csr.json` | [CsrConfig] | Subject, SANs (`hosts`), and key type of the server.     |
+//! | `client-csr.json` | [CsrConfig] | Subject and key type of the client (for mTLS).           |
+
+use miette::IntoDiagnostic as _;
+use r3bl_tui::ok;
+use rcgen::{
+    BasicConstraints, CertificateParams, DistinguishedName, DnType, ExtendedKeyUsagePurpose, IsCa,
+    KeyPair, KeyUsagePurpose, RsaKeySize, PKCS_ECDSA_P256_SHA256, PKCS_ECDSA_P384_SHA384,
+    PKCS_ED25519, PKCS_RSA_SHA256,
+};
+use serde::Deserialize;
+use std::{collections::HashMap, fs, path::Path, time::Duration};
+use time::OffsetDateTime;
+
+/// `ca-config.json`, eg:
+/// ```json
+/// { "signing": { "default": { "expiry": "87600h" },
+///                "profiles": { "server": { "expiry": "87600h", "usages": ["server auth"] } } } }
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct CaConfig {
+    pub signing: SigningConfig,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct SigningConfig {
+    /// Used for the CA, and for profiles that don't have their own `expiry`.
+    pub default: ProfileConfig,
+    #[serde(default)]
+    pub profiles: HashMap<String, ProfileConfig>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct ProfileConfig {
+    /// A `cfssl` (Go) duration, eg: `87600h` or `1h30m`.
+    pub expiry: Option<String>,
+    /// Eg: `signing`, `key encipherment`, `server auth`, `client auth`.
+    #[serde(default)]
+    pub usages: Vec<String>,
+}
+
+/// `ca-csr.json` or `server-csr.json`, eg:
+/// ```json
+/// { "CN": "server", "hosts": ["localhost"], "key": { "algo": "rsa", "size": 2048 },
+///   "names": [{ "C": "US", "ST": "Texas", "L": "Austin" }] }
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct CsrConfig {
+    #[serde(rename = "CN")]
+    pub common_name: String,
+    /// The SANs, which can be DNS names or IP addresses.
+    #[serde(default)]
+    pub hosts: Vec<String>,
+    #[serde(default)]
+    pub key: KeyConfig,
+    #[serde(default)]
+    pub names: Vec<NameConfig>,
+}
+
+/// The `algo` is `rsa` (w/ a `size` of 2048, 3072, or 4096), `ecdsa` (w/ a `size` of 256 or
+/// 384), or `ed25519`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct KeyConfig {
+    pub algo: String,
+    #[serde(default)]
+    pub size: u32,
+}
+
+impl Default for KeyConfig {
+    fn default() -> Self {
+        Self {
+            algo: "ecdsa".to_string(),
+            size: 256,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct NameConfig {
+    #[serde(rename = "C")]
+    pub country: Option<String>,
+    #[serde(rename = "ST")]
+    pub state: Option<String>,
+    #[serde(rename = "L")]
+    pub locality: Option<String>,
+    #[serde(rename = "O")]
+    pub organization: Option<String>,
+    #[serde(rename = "OU")]
+    pub organizational_unit: Option<String>,
+}
+
+/// The PEM encoded output for one certificate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneratedPem {
+    pub cert_pem: String,
+    pub key_pem: String,
+    pub csr_pem: String,
+}
+
+impl GeneratedPem {
+    /// Write `<name>.pem`, `<name>-key.pem`, and `<name>.csr` to `dir`, like `cfssljson -bare
+    /// <name>` does.
+    pub fn try_write(&self, dir: &Path, name: &str) -> miette::Result<()> {
+        fs::write(dir.join(format!("{name}.pem")), &self.cert_pem).into_diagnostic()?;
+        fs::write(dir.join(format!("{name}-key.pem")), &self.key_pem).into_diagnostic()?;
+        fs::write(dir.join(format!("{name}.csr")), &self.csr_pem).into_diagnostic()?;
+        ok!()
+    }
+}
+
+/// A CA that can issue certificates w/ [GeneratedCa::try_issue].
+pub struct GeneratedCa {
+    pub pem: GeneratedPem,
+    certificate: rcgen::Certificate,
+    key_pair: KeyPair,
+}
+
+pub fn try_load_json<T: for<'de> Deserialize<'de>>(path: &Path) -> miette::Result<T> {
+    let json = fs::read_to_string(path)
+        .into_diagnostic()
+        .map_err(|it| it.wrap_err(format!("Failed to read {}", path.display())))?;
+    let it = serde_json::from_str(&json)
+        .into_diagnostic()
+        .map_err(|it| it.wrap_err(format!("Failed to parse {}", path.display())))?;
+    ok!(it)
+}
+
+/// Like `cfssl gencert -initca ca-csr.json`.
+pub fn try_generate_ca(csr: &CsrConfig, ca_config: &CaConfig) -> miette::Result<GeneratedCa> {
+    let key_pair = try_generate_key_pair(&csr.key)?;
+    let mut params = try_create_params(csr, ca_config.signing.default.expiry.as_deref())?;
+    let csr_pem = try_serialize_csr(&params, &key_pair)?;
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params.key_usages = vec![
+        KeyUsagePurpose::DigitalSignature,
+        KeyUsagePurpose::KeyCertSign,
+        KeyUsagePurpose::CrlSign,
+    ];
+    let certificate = params.self_signed(&key_pair).into_diagnostic()?;
+    let pem = GeneratedPem {
+        cert_pem: certificate.pem(),
+        key_pem: key_pair.serialize_pem(),
+        csr_pem,
+    };
+    ok!(GeneratedCa {
+        pem,
+        certificate,
+        key_pair,
+    })
+}
+
+impl GeneratedCa {
+    /// Like `cfssl gencert -ca ca.pem -ca-key ca-key.pem -config ca-config.json -profile
+    /// <profile> <csr>.json`. The `profile` must be in the `ca_config`.
+    pub fn try_issue(
+        &self,
+        csr: &CsrConfig,
+        ca_config: &CaConfig,
+        profile: &str,
+    ) -> miette::Result<GeneratedPem> {
+        let Some(profile_config) = ca_config.signing.profiles.get(profile) else {
+            miette::bail!("No `{profile}` profile in the CA config");
+        };
+        let expiry = profile_config.expiry.as_deref().or(ca_config
+            .signing
+            .default
+            .expiry
+            .as_deref());
+
+        let key_pair = try_generate_key_pair(&csr.key)?;
+        let mut params = try_create_params(csr, expiry)?;
+        let csr_pem = try_serialize_csr(&params, &key_pair)?;
+        params.use_authority_key_identifier_extension = true;
+        for usage in &profile_config.usages {
+            match usage.as_str() {
+                "signing" | "digital signature" => {
+                    params.key_usages.push(KeyUsagePurpose::DigitalSignature)
+                }
+                "key encipherment" => params.key_usages.push(KeyUsagePurpose::KeyEncipherment),
+                "server auth" => params
+                    .extended_key_usages
+                    .push(ExtendedKeyUsagePurpose::ServerAuth),
+                "client auth" => params
+                    .extended_key_usages
+                    .push(ExtendedKeyUsagePurpose::ClientAuth),
+                _ => miette::bail!("Unsupported usage `{usage}` in the `{profile}` profile"),
+            }
+        }
+
+        let certificate = params
+            .signed_by(&key_pair, &self.certificate, &self.key_pair)
+            .into_diagnostic()?;
+        ok!(GeneratedPem {
+            cert_pem: certificate.pem(),
+            key_pem: key_pair.serialize_pem(),
+            csr_pem,
+        })
+    }
+}
+
+/// The subject (w/ the common name and [NameConfig]s), SANs, and validity period. The
+/// certificate is valid from now.
+fn try_create_params(csr: &CsrConfig, expiry: Option<&str>) -> miette::Result<CertificateParams> {
+    let mut params = CertificateParams::new(csr.hosts.clone()).into_diagnostic()?;
+
+    let mut distinguished_name = DistinguishedName::new();
+    distinguished_name.push(DnType::CommonName, csr.common_name.as_str());
+    for name in &csr.names {
+        for (dn_type, value) in [
+            (DnType::CountryName, &name.country),
+            (DnType::StateOrProvinceName, &name.state),
+            (DnType::LocalityName, &name.locality),
+            (DnType::OrganizationName, &name.organization),
+            (DnType::OrganizationalUnitName, &name.organizational_unit),
+        ] {
+            if let Some(value) = value {
+                distinguished_name.push(dn_type, value.as_str());
+            }
+        }
+    }
+    params.distinguished_name = distinguished_name;
+
+    let validity = match expiry {
+        Some(expiry) => try_parse_expiry(expiry)?,
+        None => constants::DEFAULT_VALIDITY,
+    };
+    params.not_before = OffsetDateTime::now_utc();
+    // The validity can be longer than what a date can hold (past the year 9999).
+    let not_after = time::Duration::try_from(validity)
+        .ok()
+        .and_then(|it| params.not_before.checked_add(it));
+    let Some(not_after) = not_after else {
+        miette::bail!("Expiry is too long: {validity:?}");
+    };
+    params.not_after = not_after;
+    ok!(params)
+}
+
+/// The CSR only has the subject and SANs (like the ones that `cfssl` generates), so this is
+/// called before the CA and usage params are set.
+fn try_serialize_csr(params: &CertificateParams, key_pair: &KeyPair) -> miette::Result<String> {
+    let csr = params.serialize_request(key_pair).into_diagnostic()?;
+    csr.pem().into_diagnostic()
+}
+
+pub fn try_generate_key_pair(key: &KeyConfig) -> miette::Result<KeyPair> {
+    let key_pair = match (key.algo.as_str(), key.size) {
+        ("rsa", 2048) => KeyPair::generate_rsa_for(&PKCS_RSA_SHA256, RsaKeySize::_2048),
+        ("rsa", 3072) => KeyPair::generate_rsa_for(&PKCS_RSA_SHA256, RsaKeySize::_3072),
+        ("rsa", 4096) => KeyPair::generate_rsa_for(&PKCS_RSA_SHA256, RsaKeySize::_4096),
+        ("ecdsa", 256) => KeyPair::generate_for(&PKCS_ECDSA_P256_SHA256),
+        ("ecdsa", 384) => KeyPair::generate_for(&PKCS_ECDSA_P384_SHA384),
+        ("ed25519", _) => KeyPair::generate_for(&PKCS_ED25519),
+        (algo, size) => miette::bail!("Unsupported key type: {algo} w/ size {size}"),
+    };
+    key_pair.into_diagnostic()
+}
+
+/// Parse a `cfssl` (Go) duration, which is a sequence of numbers w/ units, eg: `87600h`
+/// or `1h30m`. The units are `h`, `m`, and `s`.
+pub fn try_parse_expiry(expiry: &str) -> miette::Result<Duration> {
+    let mut total = Duration::ZERO;
+    let mut rest = expiry.trim();
+    if rest.is_empty() {
+        miette::bail!("Empty expiry");
+    }
+    while !rest.is_empty() {
+        let digits = rest.len()
+            - rest
+                .trim_start_matches(|it: char| it.is_ascii_digit())
+                .len();
+        let (number, unit_and_rest) = rest.split_at(digits);
+        let Ok(number) = number.parse::<u64>() else {
+            miette::bail!("Invalid expiry: {expiry}");
+        };
+        let seconds_per_unit = match unit_and_rest.chars().next() {
+            Some('h') => 60 * 60,
+            Some('m') => 60,
+            Some('s') => 1,
+            _ => miette::bail!("Invalid expiry: {expiry}, the units are h, m, and s"),
+        };
+        let Some(seconds) = number.checked_mul(seconds_per_unit) else {
+            miette::bail!("Expiry is too long: {expiry}");
+        };
+        let Some(new_total) = total.checked_add(Duration::from_secs(seconds)) else {
+            miette::bail!("Expiry is too long: {expiry}");
+        };
+        total = new_total;
+        rest = &unit_and_rest[1..];
+    }
+    ok!(total)
+}
+
+pub mod constants {
+    use std::time::Duration;
+
+    /// Same as `cfssl`, 1 year.
+    pub const DEFAULT_VALIDITY: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+}
@@ -0,0 +1,218 @@
+/*
+ *   Copyright (c) 2024 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Inspect PEM encoded certificates (w/ [x509_parser]) and verify them (w/ [rustls]),
+//! instead of shelling out to `openssl x509 -noout -text` and `openssl verify`. Also,
+//! summarize what was negotiated during a handshake, like `openssl s_client` does.
+
+use crate::certificate_ops;
+use miette::IntoDiagnostic as _;
+use r3bl_tui::ok;
+use rustls::{
+    client::{danger::ServerCertVerifier as _, WebPkiServerVerifier},
+    pki_types::{CertificateDer, ServerName, UnixTime},
+    CommonState, RootCertStore,
+};
+use std::{fmt::Display, sync::Arc};
+use x509_parser::{extensions::GeneralName, parse_x509_certificate};
+
+/// The parts of a certificate that are useful to check at a glance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CertSummary {
+    pub subject: String,
+    /// The `CN` in the subject, if there is one.
+    pub common_name: Option<String>,
+    pub issuer: String,
+    /// DNS names and IP addresses.
+    pub sans: Vec<String>,
+    pub is_ca: bool,
+    pub not_before: String,
+    pub not_after: String,
+    /// Seconds until the certificate expires, which is negative if it has expired.
+    pub expires_in_secs: i64,
+}
+
+impl Display for CertSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "subject: {}", self.subject)?;
+        writeln!(f, "issuer: {}", self.issuer)?;
+        match self.sans.is_empty() {
+            true => writeln!(f, "SANs: none")?,
+            false => writeln!(f, "SANs: {}", self.sans.join(", "))?,
+        }
+        writeln!(f, "CA: {}", self.is_ca)?;
+        writeln!(f, "valid from: {}", self.not_before)?;
+        write!(
+            f,
+            "valid until: {} ({} days from now)",
+            self.not_after,
+            self.expires_in_secs / (24 * 60 * 60)
+        )
+    }
+}
+
+/// One summary for each certificate in the `pem_data`.
+pub fn try_summarize_certs(pem_data: &[u8]) -> miette::Result<Vec<CertSummary>> {
+    certificate_ops::load_certs_from_pem_data(pem_data)
+        .iter()
+        .map(try_summarize_cert)
+        .collect()
+}
+
+pub fn try_summarize_cert(der: &CertificateDer<'_>) -> miette::Result<CertSummary> {
+    let (_, cert) = parse_x509_certificate(der).into_diagnostic()?;
+
+    let sans = match cert.subject_alternative_name().into_diagnostic()? {
+        Some(extension) => extension
+            .value
+            .general_names
+            .iter()
+            .map(|name| match name {
+                GeneralName::DNSName(it) => it.to_string(),
+                GeneralName::IPAddress(it) => format_ip_address(it),
+                other => other.to_string(),
+            })
+            .collect(),
+        None => vec![],
+    };
+
+    let common_name = cert
+        .subject()
+        .iter_common_name()
+        .next()
+        .and_then(|it| it.as_str().ok())
+        .map(str::to_string);
+
+    let validity = cert.validity();
+    let expires_in_secs = validity.not_after.timestamp() - UnixTime::now().as_secs() as i64;
+
+    ok!(CertSummary {
+        subject: cert.subject().to_string(),
+        common_name,
+        issuer: cert.issuer().to_string(),
+        sans,
+        is_ca: cert.is_ca(),
+        not_before: validity.not_before.to_string(),
+        not_after: validity.not_after.to_string(),
+        expires_in_secs,
+    })
+}
+
+/// The identity of the peer after the handshake, from the first certificate that it
+/// presented, eg: the client's certificate (on the server side) w/ mutual TLS. This is
+/// [None] if the peer didn't present a certificate.
+///
+/// Pass the connection of a [tokio_rustls] stream, eg:
+/// `try_peer_identity(tls_stream.get_ref().1)`.
+pub fn try_peer_identity(connection: &CommonState) -> miette::Result<Option<CertSummary>> {
+    match connection.peer_certificates().and_then(|it| it.first()) {
+        Some(cert) => ok!(Some(try_summarize_cert(cert)?)),
+        None => ok!(None),
+    }
+}
+
+/// What was negotiated during the handshake, like `openssl s_client` displays.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HandshakeSummary {
+    pub protocol_version: Option<String>,
+    pub cipher_suite: Option<String>,
+    pub alpn_protocol: Option<String>,
+    /// The peer's certificate first, followed by the intermediates (if any).
+    pub peer_cert_chain: Vec<CertSummary>,
+}
+
+impl Display for HandshakeSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let or_none = |it: &Option<String>| it.clone().unwrap_or_else(|| "none".to_string());
+        writeln!(f, "protocol version: {}", or_none(&self.protocol_version))?;
+        writeln!(f, "cipher suite: {}", or_none(&self.cipher_suite))?;
+        write!(f, "ALPN protocol: {}", or_none(&self.alpn_protocol))?;
+        for (index, cert) in self.peer_cert_chain.iter().enumerate() {
+            write!(f, "\npeer certificate {index}:")?;
+            for line in cert.to_string().lines() {
+                write!(f, "\n  {line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Pass the connection of a [tokio_rustls] stream after the handshake, eg:
+/// `try_summarize_handshake(tls_stream.get_ref().1)`.
+pub fn try_summarize_handshake(connection: &CommonState) -> miette::Result<HandshakeSummary> {
+    let peer_cert_chain = match connection.peer_certificates() {
+        Some(certs) => certs
+            .iter()
+            .map(try_summarize_cert)
+            .collect::<miette::Result<_>>()?,
+        None => vec![],
+    };
+    ok!(HandshakeSummary {
+        protocol_version: connection
+            .protocol_version()
+            .and_then(|it| it.as_str())
+            .map(str::to_string),
+        cipher_suite: connection
+            .negotiated_cipher_suite()
+            .and_then(|it| it.suite().as_str())
+            .map(str::to_string),
+        alpn_protocol: connection
+            .alpn_protocol()
+            .map(|it| String::from_utf8_lossy(it).into_owned()),
+        peer_cert_chain,
+    })
+}
+
+fn format_ip_address(bytes: &[u8]) -> String {
+    match bytes.len() {
+        4 => std::net::Ipv4Addr::from(<[u8; 4]>::try_from(bytes).unwrap_or_default()).to_string(),
+        16 => std::net::Ipv6Addr::from(<[u8; 16]>::try_from(bytes).unwrap_or_default()).to_string(),
+        _ => format!("{bytes:?}"),
+    }
+}
+
+/// Verify that the first certificate in `cert_pem` is issued by the CA in `ca_pem`, and
+/// that it is valid for `server_name`, just like a client would during the handshake.
+pub fn try_verify_server_cert(
+    cert_pem: &[u8],
+    ca_pem: &[u8],
+    server_name: &str,
+) -> miette::Result<()> {
+    let mut root_cert_store = RootCertStore::empty();
+    for cert in certificate_ops::load_certs_from_pem_data(ca_pem) {
+        root_cert_store.add(cert).into_diagnostic()?;
+    }
+    let certs = certificate_ops::load_certs_from_pem_data(cert_pem);
+    let Some((end_entity, intermediates)) = certs.split_first() else {
+        miette::bail!("No certificates found");
+    };
+    let server_name = ServerName::try_from(server_name).into_diagnostic()?;
+
+    let verifier = WebPkiServerVerifier::builder(Arc::new(root_cert_store))
+        .build()
+        .into_diagnostic()?;
+    verifier
+        .verify_server_cert(
+            end_entity,
+            intermediates,
+            &server_name,
+            &[],
+            UnixTime::now(),
+        )
+        .into_diagnostic()?;
+    ok!()
+}
@@ -15,27 +15,89 @@
  *   limitations under the License.
  */
 
-use std::time::Duration;
-
-use futures_util::StreamExt as _;
 use miette::IntoDiagnostic;
-use r3bl_tui::test_fixtures::gen_input_stream_with_delay;
-use r3bl_tui::{ok, PinnedInputStream};
-use smallvec::smallvec;
-use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt as _};
+use r3bl_tui::{ok, ReadlineAsync, ReadlineEvent};
+use std::io::Write as _;
+use tokio::io::{AsyncBufReadExt as _, AsyncRead, AsyncWrite, AsyncWriteExt as _, BufReader};
 
 pub mod constants {
     pub const HOST: &str = "localhost";
     pub const PORT: u16 = 8080;
     pub const SERVER_NAME: &str = "r3bl.com";
+
+    pub const PROMPT: &str = "> ";
+}
+
+/// Send each line that the user types to the `writer`, and display each line that is
+/// read from the `reader`, until either:
+/// - The user presses Ctrl+C or Ctrl+D.
+/// - The other side of the connection sends EOF or fails.
+///
+/// Either way, the terminal is restored (w/ [ReadlineAsync::exit]) before returning, eg:
+/// so that it isn't left in raw mode after an error.
+///
+/// [ReadlineAsync] is used instead of [tokio::io::stdin], so that the lines that are
+/// read from the `reader` are displayed above the prompt (w/out clobbering what the user
+/// is typing), and so that the program doesn't hang on exit. Please refer to
+/// [read_write] for more information.
+pub async fn read_write_interactive<R, W>(
+    reader: R,
+    mut writer: W,
+    mut readline_async: ReadlineAsync,
+) -> miette::Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut shared_writer = readline_async.clone_shared_writer();
+    let mut lines = BufReader::new(reader).lines();
+
+    // Errors don't return right away, so that the terminal is always restored by `exit`.
+    let result: miette::Result<()> = async {
+        loop {
+            tokio::select! {
+                result_line = lines.next_line() => match result_line.into_diagnostic()? {
+                    Some(line) => _ = writeln!(shared_writer, "{line}"),
+                    None => break,
+                },
+                result_readline_event = readline_async.read_line() => {
+                    match result_readline_event? {
+                        ReadlineEvent::Line(input) => {
+                            readline_async.readline.add_history_entry(input.clone());
+                            writer
+                                .write_all(format!("{input}\n").as_bytes())
+                                .await
+                                .into_diagnostic()?;
+                            writer.flush().await.into_diagnostic()?;
+                        }
+                        ReadlineEvent::Eof | ReadlineEvent::Interrupted => break,
+                        ReadlineEvent::Resized => {}
+                    }
+                }
+            }
+        }
+        ok!()
+    }
+    .await;
+
+    // Send the TLS close_notify alert.
+    _ = writer.shutdown().await;
+
+    readline_async.flush().await;
+    let exit_result = readline_async
+        .exit(Some("Goodbye! 👋"))
+        .await
+        .into_diagnostic();
+
+    result.and(exit_result)
 }
 
-/// Can't use [tokio::io::stdin] because it is blocking on a thread and can't be
-/// terminated, which results in the program hanging when the user presses [Ctrl+C] and
-/// then Enter. Without the Enter, the program will stay hung.
+/// Used when stdin isn't interactive, eg: when a file is piped into the client. Send
+/// everything from stdin to the `writer`, and copy everything from the `reader` to
+/// stdout, until either side sends EOF or fails.
 ///
-/// Please refer to the [tokio::io::stdin] documentation for more information. Here's a
-/// excerpt:
+/// [tokio::io::stdin] is fine for this, but not for interactive use. Please refer to the
+/// [tokio::io::stdin] documentation for more information. Here's a excerpt:
 /// - This handle is best used for non-interactive uses, such as when a file is piped into
 ///   the application.
 /// - For technical reasons, stdin is implemented by using an ordinary blocking read on a
@@ -43,37 +105,28 @@ pub mod constants {
 /// - This can make shutdown of the runtime hang until the user presses enter. For
 ///   interactive uses, it is recommended to spawn a thread dedicated to user input and
 ///   use blocking IO directly in that thread.
-///
-/// In this function, a [PinnedInputStream] is used instead of reading user typed input
-/// from [tokio::io::stdin]. It simulates user input typing "one", "two", "three", with a
-/// delay in between each input.
-pub async fn read_write<R, W>(mut reader: R, writer: W) -> miette::Result<()>
+pub async fn read_write<R, W>(mut reader: R, mut writer: W) -> miette::Result<()>
 where
-    R: AsyncRead + Unpin + Send + 'static,
-    W: AsyncWrite + Unpin + Send + 'static,
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
 {
-    let input_stream = gen_input_stream_with_delay(
-        smallvec!["one\n", "two\n", "three\n", "\n"],
-        Duration::from_millis(500),
-    );
-
+    let mut stdin = tokio::io::stdin();
     let mut stdout = tokio::io::stdout();
 
+    let send = async {
+        tokio::io::copy(&mut stdin, &mut writer).await?;
+        // Send the TLS close_notify alert, and keep reading the response.
+        writer.shutdown().await?;
+        std::future::pending::<std::io::Result<()>>().await
+    };
+
     tokio::select! {
-        _ = read_from_input_stream_until_empty_and_write_to_writer(input_stream, writer) => {},
-        _ = tokio::io::copy(&mut reader, &mut stdout) => {},
+        it = send => it.into_diagnostic()?,
+        it = tokio::io::copy(&mut reader, &mut stdout) => {
+            it.into_diagnostic()?;
+        }
     }
+    stdout.flush().await.into_diagnostic()?;
 
     ok!()
 }
-
-async fn read_from_input_stream_until_empty_and_write_to_writer<W: AsyncWrite + Unpin>(
-    mut input_stream: PinnedInputStream<&str>,
-    mut writer: W,
-) -> miette::Result<()> {
-    while let Some(item) = input_stream.next().await {
-        writer.write_all(item.as_bytes()).await.into_diagnostic()?;
-        writer.flush().await.into_diagnostic()?;
-    }
-    ok!()
-}
@@ -16,9 +16,20 @@
  */
 
 // Attach.
+pub mod cert_gen;
+pub mod cert_info;
 pub mod common_io;
 pub mod tls;
+pub mod tls_args;
+pub mod tls_config;
+pub mod tls_proxy;
+pub mod tls_server;
 
 // Re-export.
+pub use cert_info::*;
 pub use common_io::*;
 pub use tls::*;
+pub use tls_args::*;
+pub use tls_config::*;
+pub use tls_proxy::*;
+pub use tls_server::*;
@@ -31,18 +31,20 @@
 //! | [key_ops::server_load_single_private_key]        | Private key.                                              |
 //! | [certificate_ops::server_load_server_cert_chain] | Server certificate signed by CA certificate.              |
 //! | [tls_ops::try_create_server_tls_acceptor]        | Server code to accept secure connections from the client. |
+//!
+//! These use the [TlsConfig::default], use a [crate::TlsConfigBuilder] to configure them.
 
-use crate::tls;
+use crate::TlsConfig;
 use miette::IntoDiagnostic as _;
 use r3bl_tui::ok;
 use rustls::{
     pki_types::{CertificateDer, PrivateKeyDer},
-    ClientConfig, RootCertStore, ServerConfig,
+    RootCertStore,
 };
 use rustls_pemfile::{self, read_one, Item};
 use std::fs;
 use std::sync::OnceLock;
-use std::{io::BufReader, iter, sync::Arc};
+use std::{io::BufReader, iter};
 use tokio_rustls::{TlsAcceptor /* server */, TlsConnector /* client */};
 
 pub mod tls_ops {
@@ -75,13 +77,7 @@ pub mod tls_ops {
     /// }
     /// ```
     pub fn try_create_client_tls_connector() -> miette::Result<TlsConnector> {
-        let root_cert_store = root_cert_store_ops::client_create_root_cert_store()?;
-        let client_config = ClientConfig::builder()
-            .with_root_certificates(root_cert_store)
-            .with_no_client_auth();
-        let client_config = Arc::new(client_config);
-        let tls_connector = TlsConnector::from(client_config);
-        ok!(tls_connector)
+        TlsConfig::default().try_create_client_tls_connector()
     }
 
     /// Try to create a [tokio_rustls::TlsAcceptor] that can be used by your server to
@@ -110,15 +106,7 @@ pub mod tls_ops {
     /// }
     /// ```
     pub fn try_create_server_tls_acceptor() -> miette::Result<TlsAcceptor> {
-        let server_cert_chain = tls::certificate_ops::server_load_server_cert_chain()?;
-        let server_private_key = tls::key_ops::server_load_single_private_key()?;
-        let server_config = ServerConfig::builder()
-            .with_no_client_auth()
-            .with_single_cert(server_cert_chain, server_private_key)
-            .into_diagnostic()?;
-        let server_config = Arc::new(server_config);
-        let tls_acceptor = TlsAcceptor::from(server_config);
-        ok!(tls_acceptor)
+        TlsConfig::default().try_create_server_tls_acceptor()
     }
 }
 
@@ -173,8 +161,11 @@ pub mod key_ops {
     ///    openssl rsa -in generated/server-key.pem -text -noout
     ///    ```
     ///
+    /// Keys in the `PKCS#8` (`BEGIN PRIVATE KEY`) and `SEC1` (`BEGIN EC PRIVATE KEY`)
+    /// formats are loaded too, eg: when the key is passed to a [crate::TlsConfigBuilder].
+    ///
     /// API Docs: <https://docs.rs/rustls-pemfile/latest/rustls_pemfile/>
-    fn load_private_key_from_pem_data(key_data: &[u8]) -> Vec<PrivateKeyDer<'static>> {
+    pub fn load_private_key_from_pem_data(key_data: &[u8]) -> Vec<PrivateKeyDer<'static>> {
         let mut reader = BufReader::new(key_data);
         let mut return_keys: Vec<PrivateKeyDer> = vec![];
         for item in iter::from_fn(|| read_one(&mut reader).transpose()) {
@@ -182,6 +173,12 @@ pub mod key_ops {
                 Ok(Item::Pkcs1Key(key)) => {
                     return_keys.push(PrivateKeyDer::Pkcs1(key));
                 }
+                Ok(Item::Pkcs8Key(key)) => {
+                    return_keys.push(PrivateKeyDer::Pkcs8(key));
+                }
+                Ok(Item::Sec1Key(key)) => {
+                    return_keys.push(PrivateKeyDer::Sec1(key));
+                }
                 _ => continue,
             }
         }
@@ -233,7 +230,7 @@ pub mod certificate_ops {
     /// It is in the `PEM-encoded X.509` format for certificates. While the data is from a
     /// PEM encoded file, `rustls` loads this into a the [CertificateDer] struct. PEM file
     /// format is human readable and Base64 encoded. DER format is binary.
-    fn load_certs_from_pem_data(pem_data: &[u8]) -> Vec<CertificateDer<'static>> {
+    pub fn load_certs_from_pem_data(pem_data: &[u8]) -> Vec<CertificateDer<'static>> {
         let mut reader = BufReader::new(pem_data);
         let mut return_value = vec![];
         for item in iter::from_fn(|| read_one(&mut reader).transpose()) {
@@ -248,13 +245,14 @@ pub mod certificate_ops {
     }
 }
 
-/// These are generated by running either:
-/// 1. `gen_certs.fish` script in the `certs` directory.
-/// 2. `cargo run --bin gen_certs` in the project root directory.
+/// These are generated by running `cargo run --bin gen-certs` in the root folder of the
+/// `tls` crate. The generated files are committed, but they expire (the client certificate
+/// after a year), so run `gen-certs` again to renew them. `cargo run --bin check-certs`
+/// shows when each one expires.
 ///
 /// These files are not loaded statically at compile time (through the use of
 /// [include_bytes!]). Rather they are loaded at runtime. This is due to the fact that
-/// when using the `gen_certs` binary:
+/// when using the `gen-certs` binary:
 /// 1. The files don't exist until the binary is run.
 /// 2. And the binary can't be built until the files exist. So it's a chicken and egg
 ///    problem.
@@ -297,4 +295,12 @@ pub mod binary_data {
     pub fn get_ca_cert_pem_for_client_binary() -> &'static Vec<u8> {
         CA_CERT_PEM.get_or_init(|| fs::read(CA_CERT_PEM_FILENAME).expect("Failed to read ca.pem"))
     }
+
+    /// - The `client.pem` file, which is the certificate that the **CLIENT** presents for
+    ///   mutual TLS.
+    /// - It is generated using the self-signed CA certificate.
+    /// - It isn't embedded, since it is optional, see [crate::TlsConfigBuilder::client_cert_path].
+    pub const CLIENT_CERT_PEM_FILENAME: &str = "certs/generated/client.pem";
+    /// The private key for the `client.pem` file.
+    pub const CLIENT_KEY_PEM_FILENAME: &str = "certs/generated/client-key.pem";
 }
@@ -0,0 +1,146 @@
+/*
+ *   Copyright (c) 2024 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Command line arguments for the `server`, `client`, and `tls-proxy` binaries, which are
+//! turned into a [TlsConfig] w/ [TlsArgs::to_tls_config].
+
+use crate::{
+    binary_data, common_io::constants, ClientAuth, ProxyMode, TlsConfig, TlsConfigBuilder,
+    TlsVersion,
+};
+use clap::{Args, Parser};
+use std::{path::PathBuf, time::Duration};
+
+#[derive(Parser, Debug, Clone)]
+#[command(version, about, long_about = None)]
+pub struct CliArgs {
+    /// Host to connect or listen to.
+    #[arg(long, default_value = constants::HOST)]
+    pub host: String,
+
+    /// Port to connect or listen to.
+    #[arg(long, default_value_t = constants::PORT)]
+    pub port: u16,
+
+    #[command(flatten)]
+    pub tls: TlsArgs,
+}
+
+impl CliArgs {
+    pub fn addr(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(version, about, long_about = None)]
+pub struct ProxyCliArgs {
+    /// Address to listen to, eg: `localhost:8443`.
+    #[arg(long, default_value = "localhost:8443")]
+    pub listen: String,
+
+    /// Address of the service to forward to, eg: `localhost:3000`.
+    #[arg(long)]
+    pub upstream: String,
+
+    /// Which side of the proxy uses TLS. The TLS options configure the server (for
+    /// `terminate`) or the client (for `originate`).
+    #[arg(long, value_enum, default_value_t = ProxyMode::default())]
+    pub mode: ProxyMode,
+
+    /// Close connections that haven't sent or received anything for this many seconds.
+    #[arg(long, default_value_t = 300)]
+    pub idle_timeout_secs: u64,
+
+    #[command(flatten)]
+    pub tls: TlsArgs,
+}
+
+impl ProxyCliArgs {
+    pub fn idle_timeout(&self) -> Duration {
+        Duration::from_secs(self.idle_timeout_secs)
+    }
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct TlsArgs {
+    /// Certificate (PEM) that the server presents.
+    #[arg(long, default_value = binary_data::SERVER_CERT_PEM_FILENAME)]
+    pub cert: PathBuf,
+
+    /// Private key (PEM) for the server certificate.
+    #[arg(long, default_value = binary_data::SERVER_KEY_PEM_FILENAME)]
+    pub key: PathBuf,
+
+    /// CA certificate (PEM) that the client uses to verify the server.
+    #[arg(long, default_value = binary_data::CA_CERT_PEM_FILENAME)]
+    pub ca: PathBuf,
+
+    /// SNI server name, which has to be in the server certificate's SANs.
+    #[arg(long, default_value = constants::SERVER_NAME)]
+    pub server_name: String,
+
+    /// ALPN protocols in order of preference, eg: `--alpn h2,http/1.1`.
+    #[arg(long = "alpn", value_delimiter = ',')]
+    pub alpn_protocols: Vec<String>,
+
+    /// Minimum TLS version.
+    #[arg(long, value_enum, default_value_t = TlsVersion::default())]
+    pub min_tls_version: TlsVersion,
+
+    /// Cipher suites to use, eg: `--cipher-suite TLS13_AES_256_GCM_SHA384`. The defaults
+    /// are used if this isn't set.
+    #[arg(long = "cipher-suite", value_delimiter = ',')]
+    pub cipher_suites: Vec<String>,
+
+    /// Whether the server asks clients for a certificate (mutual TLS).
+    #[arg(long, value_enum, default_value_t = ClientAuth::default())]
+    pub client_auth: ClientAuth,
+
+    /// Certificate (PEM) that the client presents for mutual TLS, eg:
+    /// `certs/generated/client.pem`.
+    #[arg(long, requires = "client_key")]
+    pub client_cert: Option<PathBuf>,
+
+    /// Private key (PEM) for the client certificate, eg: `certs/generated/client-key.pem`.
+    #[arg(long, requires = "client_cert")]
+    pub client_key: Option<PathBuf>,
+}
+
+impl TlsArgs {
+    pub fn to_tls_config(&self) -> TlsConfig {
+        let builder = TlsConfigBuilder::default()
+            .cert_path(&self.cert)
+            .key_path(&self.key)
+            .ca_path(&self.ca)
+            .server_name(&self.server_name)
+            .min_tls_version(self.min_tls_version)
+            .client_auth(self.client_auth);
+        let builder = match (&self.client_cert, &self.client_key) {
+            (Some(cert), Some(key)) => builder.client_cert_path(cert).client_key_path(key),
+            _ => builder,
+        };
+        let builder = self
+            .alpn_protocols
+            .iter()
+            .fold(builder, |it, protocol| it.alpn_protocol(protocol));
+        self.cipher_suites
+            .iter()
+            .fold(builder, |it, name| it.cipher_suite(name))
+            .build()
+    }
+}
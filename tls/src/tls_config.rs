@@ -0,0 +1,404 @@
+/*
+ *   Copyright (c) 2024 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Instead of the compiled in paths in [crate::binary_data] and the
+//! [crate::common_io::constants::SERVER_NAME], use a [TlsConfigBuilder] to configure the
+//! certificates, keys, SNI server name, ALPN protocols, minimum TLS version, cipher
+//! suites, and mutual TLS (see [ClientAuth]). Then create the [TlsAcceptor] (server) or [TlsConnector] (client) from the
+//! resulting [TlsConfig].
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use tls::{TlsConfigBuilder, TlsVersion};
+//! fn create() -> miette::Result<()> {
+//!     let config = TlsConfigBuilder::default()
+//!         .cert_path("certs/generated/server.pem")
+//!         .key_path("certs/generated/server-key.pem")
+//!         .ca_path("certs/generated/ca.pem")
+//!         .server_name("localhost")
+//!         .alpn_protocol("echo/1")
+//!         .min_tls_version(TlsVersion::Tls13)
+//!         .build();
+//!     let _tls_acceptor = config.try_create_server_tls_acceptor()?;
+//!     let _tls_connector = config.try_create_client_tls_connector()?;
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{binary_data, certificate_ops, common_io, key_ops};
+use miette::IntoDiagnostic as _;
+use r3bl_tui::ok;
+use rustls::{
+    crypto::{self, CryptoProvider},
+    pki_types::{CertificateDer, PrivateKeyDer, ServerName},
+    server::{danger::ClientCertVerifier, WebPkiClientVerifier},
+    ClientConfig, RootCertStore, ServerConfig, SupportedProtocolVersion,
+};
+use std::{borrow::Cow, fs, path::PathBuf, sync::Arc};
+use tokio_rustls::{TlsAcceptor /* server */, TlsConnector /* client */};
+
+/// Where to load PEM encoded data (certificates or keys) from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PemSource {
+    Path(PathBuf),
+    InMemory(Vec<u8>),
+}
+
+impl PemSource {
+    pub fn try_read(&self) -> miette::Result<Cow<'_, [u8]>> {
+        match self {
+            PemSource::Path(path) => {
+                let bytes = fs::read(path)
+                    .into_diagnostic()
+                    .map_err(|it| it.wrap_err(format!("Failed to read {}", path.display())))?;
+                ok!(Cow::Owned(bytes))
+            }
+            PemSource::InMemory(bytes) => ok!(Cow::Borrowed(bytes.as_slice())),
+        }
+    }
+
+    /// For error messages.
+    pub fn describe(&self) -> String {
+        match self {
+            PemSource::Path(path) => path.display().to_string(),
+            PemSource::InMemory(_) => "in memory PEM data".to_string(),
+        }
+    }
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    strum_macros::Display,
+    strum_macros::EnumString,
+    clap::ValueEnum,
+)]
+pub enum TlsVersion {
+    #[default]
+    #[strum(serialize = "1.2")]
+    #[value(name = "1.2")]
+    Tls12,
+    #[strum(serialize = "1.3")]
+    #[value(name = "1.3")]
+    Tls13,
+}
+
+const TLS13_ONLY: &[&SupportedProtocolVersion] = &[&rustls::version::TLS13];
+
+impl TlsVersion {
+    /// All the versions that are greater than or equal to this one.
+    pub fn protocol_versions(&self) -> &'static [&'static SupportedProtocolVersion] {
+        match self {
+            TlsVersion::Tls12 => rustls::ALL_VERSIONS,
+            TlsVersion::Tls13 => TLS13_ONLY,
+        }
+    }
+}
+
+/// Whether the server asks clients for a certificate (ie: mutual TLS), which is verified
+/// against the [TlsConfig::ca]. Use [crate::try_peer_identity] to get the client's identity
+/// after the handshake.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    strum_macros::Display,
+    strum_macros::EnumString,
+    clap::ValueEnum,
+)]
+#[strum(serialize_all = "lowercase")]
+pub enum ClientAuth {
+    #[default]
+    None,
+    /// Clients w/out a certificate are accepted, but ones w/ an untrusted certificate
+    /// aren't.
+    Optional,
+    Required,
+}
+
+/// Use [TlsConfigBuilder] to create this. The server uses the `cert` and `key`, and the
+/// client uses the `ca` (to verify the server) and the `server_name`. For mutual TLS, the
+/// server also uses the `ca` (to verify the client), and the client uses the `client_cert`
+/// and `client_key`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TlsConfig {
+    pub cert: PemSource,
+    pub key: PemSource,
+    pub ca: PemSource,
+    /// The SNI server name, which has to be in the server certificate's SANs.
+    pub server_name: String,
+    /// Eg: `h2` or `http/1.1`, in order of preference. Empty means no ALPN.
+    pub alpn_protocols: Vec<String>,
+    pub min_tls_version: TlsVersion,
+    /// Names of the cipher suites to use, eg: `TLS13_AES_256_GCM_SHA384`. Empty means the
+    /// defaults of the [CryptoProvider].
+    pub cipher_suites: Vec<String>,
+    pub client_auth: ClientAuth,
+    /// Certificate that the client presents, if the server asks for one.
+    pub client_cert: Option<PemSource>,
+    pub client_key: Option<PemSource>,
+}
+
+impl Default for TlsConfig {
+    /// The files generated by `gen-certs`, and the [common_io::constants::SERVER_NAME].
+    fn default() -> Self {
+        Self {
+            cert: PemSource::Path(binary_data::SERVER_CERT_PEM_FILENAME.into()),
+            key: PemSource::Path(binary_data::SERVER_KEY_PEM_FILENAME.into()),
+            ca: PemSource::Path(binary_data::CA_CERT_PEM_FILENAME.into()),
+            server_name: common_io::constants::SERVER_NAME.to_string(),
+            alpn_protocols: vec![],
+            min_tls_version: TlsVersion::default(),
+            cipher_suites: vec![],
+            client_auth: ClientAuth::default(),
+            client_cert: None,
+            client_key: None,
+        }
+    }
+}
+
+/// Starts w/ the values in [TlsConfig::default], and each method overrides one of them.
+#[derive(Clone, Debug, Default)]
+pub struct TlsConfigBuilder {
+    config: TlsConfig,
+}
+
+impl TlsConfigBuilder {
+    pub fn cert_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.cert = PemSource::Path(path.into());
+        self
+    }
+
+    pub fn cert_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.config.cert = PemSource::InMemory(pem.into());
+        self
+    }
+
+    pub fn key_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.key = PemSource::Path(path.into());
+        self
+    }
+
+    pub fn key_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.config.key = PemSource::InMemory(pem.into());
+        self
+    }
+
+    pub fn ca_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.ca = PemSource::Path(path.into());
+        self
+    }
+
+    pub fn ca_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.config.ca = PemSource::InMemory(pem.into());
+        self
+    }
+
+    pub fn server_name(mut self, server_name: impl Into<String>) -> Self {
+        self.config.server_name = server_name.into();
+        self
+    }
+
+    /// Can be called more than once, in order of preference.
+    pub fn alpn_protocol(mut self, protocol: impl Into<String>) -> Self {
+        self.config.alpn_protocols.push(protocol.into());
+        self
+    }
+
+    pub fn min_tls_version(mut self, version: TlsVersion) -> Self {
+        self.config.min_tls_version = version;
+        self
+    }
+
+    /// Can be called more than once. If it isn't called, the defaults are used.
+    pub fn cipher_suite(mut self, name: impl Into<String>) -> Self {
+        self.config.cipher_suites.push(name.into());
+        self
+    }
+
+    pub fn client_auth(mut self, client_auth: ClientAuth) -> Self {
+        self.config.client_auth = client_auth;
+        self
+    }
+
+    pub fn client_cert_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.client_cert = Some(PemSource::Path(path.into()));
+        self
+    }
+
+    pub fn client_cert_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.config.client_cert = Some(PemSource::InMemory(pem.into()));
+        self
+    }
+
+    pub fn client_key_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.client_key = Some(PemSource::Path(path.into()));
+        self
+    }
+
+    pub fn client_key_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.config.client_key = Some(PemSource::InMemory(pem.into()));
+        self
+    }
+
+    pub fn build(self) -> TlsConfig {
+        self.config
+    }
+}
+
+impl TlsConfig {
+    pub fn try_create_server_config(&self) -> miette::Result<ServerConfig> {
+        let cert_chain = self.try_load_cert_chain(&self.cert)?;
+        let private_key = self.try_load_private_key(&self.key)?;
+        let provider = self.try_create_provider()?;
+        let builder = ServerConfig::builder_with_provider(provider.clone())
+            .with_protocol_versions(self.min_tls_version.protocol_versions())
+            .into_diagnostic()?;
+        let builder = match self.client_auth {
+            ClientAuth::None => builder.with_no_client_auth(),
+            ClientAuth::Optional | ClientAuth::Required => {
+                builder.with_client_cert_verifier(self.try_create_client_cert_verifier(provider)?)
+            }
+        };
+        let mut server_config = builder
+            .with_single_cert(cert_chain, private_key)
+            .into_diagnostic()?;
+        server_config.alpn_protocols = self.alpn_protocols_as_bytes();
+        ok!(server_config)
+    }
+
+    pub fn try_create_client_config(&self) -> miette::Result<ClientConfig> {
+        let builder = ClientConfig::builder_with_provider(self.try_create_provider()?)
+            .with_protocol_versions(self.min_tls_version.protocol_versions())
+            .into_diagnostic()?
+            .with_root_certificates(self.try_create_root_cert_store()?);
+        let mut client_config = match (&self.client_cert, &self.client_key) {
+            (Some(cert), Some(key)) => builder
+                .with_client_auth_cert(
+                    self.try_load_cert_chain(cert)?,
+                    self.try_load_private_key(key)?,
+                )
+                .into_diagnostic()?,
+            (None, None) => builder.with_no_client_auth(),
+            _ => miette::bail!("Both the client certificate and key are needed for mutual TLS"),
+        };
+        client_config.alpn_protocols = self.alpn_protocols_as_bytes();
+        ok!(client_config)
+    }
+
+    pub fn try_create_server_tls_acceptor(&self) -> miette::Result<TlsAcceptor> {
+        let server_config = Arc::new(self.try_create_server_config()?);
+        ok!(TlsAcceptor::from(server_config))
+    }
+
+    pub fn try_create_client_tls_connector(&self) -> miette::Result<TlsConnector> {
+        let client_config = Arc::new(self.try_create_client_config()?);
+        ok!(TlsConnector::from(client_config))
+    }
+
+    /// Pass this to [TlsConnector::connect]. It is sent to the server as the SNI
+    /// extension, and the server certificate is verified against it.
+    pub fn try_server_name(&self) -> miette::Result<ServerName<'static>> {
+        let server_name = ServerName::try_from(self.server_name.clone()).into_diagnostic()?;
+        ok!(server_name)
+    }
+
+    /// The default [CryptoProvider], w/ only the cipher suites in
+    /// [TlsConfig::cipher_suites] (if any).
+    fn try_create_provider(&self) -> miette::Result<Arc<CryptoProvider>> {
+        let mut provider = crypto::aws_lc_rs::default_provider();
+        if !self.cipher_suites.is_empty() {
+            let name_of = |it: &rustls::SupportedCipherSuite| it.suite().as_str().unwrap_or("");
+            if let Some(unknown) = self.cipher_suites.iter().find(|name| {
+                !provider
+                    .cipher_suites
+                    .iter()
+                    .any(|it| name_of(it).eq_ignore_ascii_case(name))
+            }) {
+                let available = provider
+                    .cipher_suites
+                    .iter()
+                    .map(name_of)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                miette::bail!("Unknown cipher suite: {unknown}, available: {available}");
+            }
+            provider.cipher_suites.retain(|it| {
+                self.cipher_suites
+                    .iter()
+                    .any(|name| name_of(it).eq_ignore_ascii_case(name))
+            });
+        }
+        ok!(Arc::new(provider))
+    }
+
+    /// The [TlsConfig::ca], which the client uses to verify the server, and the server uses
+    /// to verify the client.
+    fn try_create_root_cert_store(&self) -> miette::Result<RootCertStore> {
+        let mut root_cert_store = RootCertStore::empty();
+        for cert in self.try_load_cert_chain(&self.ca)? {
+            root_cert_store.add(cert).into_diagnostic()?;
+        }
+        ok!(root_cert_store)
+    }
+
+    fn try_create_client_cert_verifier(
+        &self,
+        provider: Arc<CryptoProvider>,
+    ) -> miette::Result<Arc<dyn ClientCertVerifier>> {
+        let root_cert_store = Arc::new(self.try_create_root_cert_store()?);
+        let builder = WebPkiClientVerifier::builder_with_provider(root_cert_store, provider);
+        let builder = match self.client_auth {
+            ClientAuth::Optional => builder.allow_unauthenticated(),
+            _ => builder,
+        };
+        builder.build().into_diagnostic()
+    }
+
+    fn try_load_cert_chain(
+        &self,
+        source: &PemSource,
+    ) -> miette::Result<Vec<CertificateDer<'static>>> {
+        let certs = certificate_ops::load_certs_from_pem_data(&source.try_read()?);
+        if certs.is_empty() {
+            miette::bail!("No certificates found in {}", source.describe());
+        }
+        ok!(certs)
+    }
+
+    fn try_load_private_key(&self, source: &PemSource) -> miette::Result<PrivateKeyDer<'static>> {
+        match key_ops::load_private_key_from_pem_data(&source.try_read()?).pop() {
+            Some(key) => ok!(key),
+            None => miette::bail!("No keys found in {}", source.describe()),
+        }
+    }
+
+    fn alpn_protocols_as_bytes(&self) -> Vec<Vec<u8>> {
+        self.alpn_protocols
+            .iter()
+            .map(|it| it.as_bytes().to_vec())
+            .collect()
+    }
+}
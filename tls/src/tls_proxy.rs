@@ -0,0 +1,361 @@
+/*
+ *   Copyright (c) 2024 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Used by the `tls-proxy` binary, to put TLS in front of a plaintext service (see
+//! [ProxyMode::Terminate]), or to connect a plaintext client to a TLS service (see
+//! [ProxyMode::Originate]).
+//!
+//! - [serve_proxy] accepts clients, and forwards each one to the upstream, until the
+//!   `shutdown` future completes.
+//! - [copy_bidirectional_w_idle_timeout] copies the bytes for a single connection.
+
+use crate::{
+    serve, serve_tcp, try_peer_identity, try_watch_cert_files, ReloadableServerConfig, TlsConfig,
+    DRAIN_TIMEOUT,
+};
+use crossterm::style::Stylize as _;
+use miette::IntoDiagnostic as _;
+use r3bl_tui::ok;
+use std::{
+    future::Future,
+    io,
+    net::SocketAddr,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, AsyncWriteExt as _, ReadBuf},
+    net::{TcpListener, TcpStream},
+    time::Instant,
+};
+use tokio_util::sync::CancellationToken;
+
+/// How long a connection that is open on shutdown can keep copying, before the proxy
+/// closes it. This is less than [DRAIN_TIMEOUT], so the connection is closed cleanly (w/
+/// a `close_notify` on the TLS side) before it would be dropped.
+pub const SHUTDOWN_GRACE_PERIOD: Duration = DRAIN_TIMEOUT.saturating_sub(Duration::from_secs(1));
+
+/// How long the proxy has to connect to the upstream (including the TLS handshake for
+/// [ProxyMode::Originate]), so that an upstream that is down or stuck doesn't hold on to
+/// the client forever.
+pub const UPSTREAM_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    strum_macros::Display,
+    strum_macros::EnumString,
+    clap::ValueEnum,
+)]
+#[strum(serialize_all = "lowercase")]
+pub enum ProxyMode {
+    /// Accept TLS clients, and forward plaintext to the upstream.
+    #[default]
+    Terminate,
+    /// Accept plaintext clients, and forward TLS to the upstream.
+    Originate,
+}
+
+/// Accept clients on the `listener` until `shutdown` completes, and forward each one to
+/// the `upstream` (eg: `localhost:3000`), w/ TLS on the side that the `mode` says. The
+/// `tls_config` has the server's cert and key for [ProxyMode::Terminate] (which are
+/// reloaded when the files change), or the CA and server name of the upstream for
+/// [ProxyMode::Originate].
+///
+/// The connections that are open on shutdown keep going, until they close or the
+/// [SHUTDOWN_GRACE_PERIOD] is over. A connection is also closed when it is idle for
+/// `idle_timeout`, or when connecting to the upstream takes longer than
+/// [UPSTREAM_CONNECT_TIMEOUT]. Each one is printed when it opens and closes.
+pub async fn serve_proxy(
+    listener: TcpListener,
+    mode: ProxyMode,
+    tls_config: TlsConfig,
+    upstream: String,
+    idle_timeout: Duration,
+    shutdown: impl Future<Output = ()>,
+) -> miette::Result<()> {
+    let upstream = Arc::new(upstream);
+    match mode {
+        // TLS from the client, plaintext to the upstream.
+        ProxyMode::Terminate => {
+            let server_config = Arc::new(ReloadableServerConfig::try_new(tls_config)?);
+            let _watcher = try_watch_cert_files(server_config.clone())?;
+            serve(
+                listener,
+                server_config,
+                shutdown,
+                move |secure_stream, addr, shutdown| {
+                    let upstream = upstream.clone();
+                    async move {
+                        let client = match try_peer_identity(secure_stream.get_ref().1)? {
+                            Some(it) => format!("{addr} ({})", it.subject),
+                            None => addr.to_string(),
+                        };
+                        let connect = TcpStream::connect(upstream.as_str());
+                        let Some(upstream_stream) =
+                            try_connect_upstream(connect, &upstream, &shutdown).await?
+                        else {
+                            return ok!();
+                        };
+                        proxy_connection(
+                            secure_stream,
+                            upstream_stream,
+                            &client,
+                            &upstream,
+                            idle_timeout,
+                            shutdown,
+                        )
+                        .await
+                    }
+                },
+            )
+            .await
+        }
+        // Plaintext from the client, TLS to the upstream.
+        ProxyMode::Originate => {
+            let tls_connector = tls_config.try_create_client_tls_connector()?;
+            let server_name = tls_config.try_server_name()?;
+            serve_tcp(
+                listener,
+                shutdown,
+                move |stream, addr: SocketAddr, shutdown| {
+                    let upstream = upstream.clone();
+                    let tls_connector = tls_connector.clone();
+                    let server_name = server_name.clone();
+                    async move {
+                        let connect = async {
+                            let upstream_stream = TcpStream::connect(upstream.as_str()).await?;
+                            tls_connector.connect(server_name, upstream_stream).await
+                        };
+                        let Some(secure_stream) =
+                            try_connect_upstream(connect, &upstream, &shutdown).await?
+                        else {
+                            return ok!();
+                        };
+                        proxy_connection(
+                            stream,
+                            secure_stream,
+                            &addr.to_string(),
+                            &upstream,
+                            idle_timeout,
+                            shutdown,
+                        )
+                        .await
+                    }
+                },
+            )
+            .await
+        }
+    }
+}
+
+/// Run `connect` (to the `upstream`), unless it takes longer than
+/// [UPSTREAM_CONNECT_TIMEOUT]. Returns `None` if `shutdown` is cancelled first, since
+/// there is nothing in flight to drain yet.
+async fn try_connect_upstream<S>(
+    connect: impl Future<Output = io::Result<S>>,
+    upstream: &str,
+    shutdown: &CancellationToken,
+) -> miette::Result<Option<S>> {
+    tokio::select! {
+        _ = shutdown.cancelled() => ok!(None),
+        it = tokio::time::timeout(UPSTREAM_CONNECT_TIMEOUT, connect) => match it {
+            Ok(Ok(it)) => ok!(Some(it)),
+            Ok(Err(error)) => miette::bail!("Failed to connect to {upstream}: {error}"),
+            Err(_) => miette::bail!("Timed out connecting to {upstream}"),
+        },
+    }
+}
+
+/// Copy between the `client_stream` and `upstream_stream` until either side closes the
+/// connection, or it is idle for `idle_timeout`. Once `shutdown` is cancelled, the copy
+/// only continues for the [SHUTDOWN_GRACE_PERIOD]. The connection is printed when it
+/// opens and closes.
+async fn proxy_connection(
+    mut client_stream: impl AsyncRead + AsyncWrite + Unpin,
+    mut upstream_stream: impl AsyncRead + AsyncWrite + Unpin,
+    client: &str,
+    upstream: &str,
+    idle_timeout: Duration,
+    shutdown: CancellationToken,
+) -> miette::Result<()> {
+    let start = Instant::now();
+    println!(
+        "{} {} {}",
+        "🔗 Opened".green().italic(),
+        client.blue(),
+        format!("-> {upstream}").blue()
+    );
+
+    let result = {
+        let copy = copy_bidirectional_w_idle_timeout(
+            &mut client_stream,
+            &mut upstream_stream,
+            idle_timeout,
+        );
+        tokio::pin!(copy);
+        tokio::select! {
+            it = &mut copy => it,
+            // Let the data that is in flight get through.
+            _ = shutdown.cancelled() => {
+                match tokio::time::timeout(SHUTDOWN_GRACE_PERIOD, &mut copy).await {
+                    Ok(it) => it,
+                    Err(_) => Err(miette::miette!("Proxy is shutting down")),
+                }
+            }
+        }
+    };
+
+    // Best effort, so the TLS side gets a close_notify when the proxy closes the
+    // connection (on idle timeout or shutdown).
+    _ = client_stream.shutdown().await;
+    _ = upstream_stream.shutdown().await;
+
+    match result {
+        Ok(stats) => println!(
+            "{} {} {} {} {}",
+            "🔒 Closed".yellow().italic(),
+            client.blue(),
+            format!("after {:?},", start.elapsed()).yellow(),
+            format!("sent {} bytes,", stats.client_to_upstream).yellow(),
+            format!("received {} bytes", stats.upstream_to_client).yellow()
+        ),
+        Err(error) => println!(
+            "{} {} {} {error}",
+            "🔒 Closed".red().italic(),
+            client.blue(),
+            format!("after {:?}:", start.elapsed()).red()
+        ),
+    }
+
+    ok!()
+}
+
+/// The number of bytes copied in each direction by
+/// [copy_bidirectional_w_idle_timeout].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProxyStats {
+    pub client_to_upstream: u64,
+    pub upstream_to_client: u64,
+}
+
+/// [tokio::io::copy_bidirectional] between the `client` and the `upstream`, until either
+/// side closes the connection, or nothing is read or written for `idle_timeout`.
+pub async fn copy_bidirectional_w_idle_timeout<C, U>(
+    client: &mut C,
+    upstream: &mut U,
+    idle_timeout: Duration,
+) -> miette::Result<ProxyStats>
+where
+    C: AsyncRead + AsyncWrite + Unpin + ?Sized,
+    U: AsyncRead + AsyncWrite + Unpin + ?Sized,
+{
+    let last_activity = Arc::new(Mutex::new(Instant::now()));
+    let mut client = ActivityTracked {
+        inner: client,
+        last_activity: last_activity.clone(),
+    };
+    let mut upstream = ActivityTracked {
+        inner: upstream,
+        last_activity: last_activity.clone(),
+    };
+
+    let copy = tokio::io::copy_bidirectional(&mut client, &mut upstream);
+    tokio::pin!(copy);
+    loop {
+        let deadline = last_activity_of(&last_activity) + idle_timeout;
+        tokio::select! {
+            it = &mut copy => {
+                let (client_to_upstream, upstream_to_client) = it.into_diagnostic()?;
+                return Ok(ProxyStats {
+                    client_to_upstream,
+                    upstream_to_client,
+                });
+            }
+            // There may have been activity while sleeping, so check again.
+            _ = tokio::time::sleep_until(deadline) => {
+                if last_activity_of(&last_activity).elapsed() >= idle_timeout {
+                    miette::bail!("Idle for more than {idle_timeout:?}");
+                }
+            }
+        }
+    }
+}
+
+fn last_activity_of(last_activity: &Mutex<Instant>) -> Instant {
+    *last_activity.lock().unwrap_or_else(|it| it.into_inner())
+}
+
+/// Updates `last_activity` whenever bytes are read or written.
+struct ActivityTracked<'a, S: ?Sized> {
+    inner: &'a mut S,
+    last_activity: Arc<Mutex<Instant>>,
+}
+
+impl<S: ?Sized> ActivityTracked<'_, S> {
+    fn touch(&self) {
+        *self
+            .last_activity
+            .lock()
+            .unwrap_or_else(|it| it.into_inner()) = Instant::now();
+    }
+}
+
+impl<S: AsyncRead + Unpin + ?Sized> AsyncRead for ActivityTracked<'_, S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let filled_before = buf.filled().len();
+        let poll = Pin::new(&mut *self.inner).poll_read(cx, buf);
+        if buf.filled().len() > filled_before {
+            self.touch();
+        }
+        poll
+    }
+}
+
+impl<S: AsyncWrite + Unpin + ?Sized> AsyncWrite for ActivityTracked<'_, S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let poll = Pin::new(&mut *self.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(written)) = poll {
+            if written > 0 {
+                self.touch();
+            }
+        }
+        poll
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.inner).poll_shutdown(cx)
+    }
+}
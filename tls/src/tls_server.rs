@@ -0,0 +1,359 @@
+/*
+ *   Copyright (c) 2024 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! A server that accepts many concurrent clients, drains them on shutdown, and hot
+//! reloads its certificate.
+//!
+//! - [ReloadableServerConfig] holds the current [ServerConfig] in an [ArcSwap]. Each
+//!   handshake uses the config that is current when the client connects, so swapping it
+//!   doesn't affect existing sessions.
+//! - [try_watch_cert_files] reloads the [ReloadableServerConfig] when the cert, key, or CA
+//!   files change.
+//! - [serve] accepts clients until the `shutdown` future completes, and then waits (up to
+//!   [DRAIN_TIMEOUT]) for the connections that are still open to finish. [serve_tcp] does
+//!   the same w/out TLS.
+//! - [try_read_line_until_shutdown] lets a line based handler finish the request that is
+//!   in flight on shutdown, and then close the connection.
+
+use crate::{PemSource, TlsConfig};
+use arc_swap::ArcSwap;
+use crossterm::style::Stylize as _;
+use miette::IntoDiagnostic as _;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher as _};
+use r3bl_tui::ok;
+use rustls::ServerConfig;
+use std::{
+    collections::HashSet,
+    future::Future,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncBufReadExt as _, AsyncRead, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::{mpsc, watch},
+    task::JoinSet,
+};
+use tokio_rustls::{server::TlsStream, TlsAcceptor};
+use tokio_util::sync::CancellationToken;
+
+/// Editors and `gen-certs` write the cert and key files separately (and sometimes more
+/// than once), so wait for the changes to settle before reloading.
+pub const RELOAD_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// How long [serve] waits for open connections to finish on shutdown, before dropping
+/// them.
+pub const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long a client has to complete the TLS handshake, so that a slow or idle client
+/// doesn't hold on to a task forever.
+pub const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long [serve_tcp] waits before accepting again, after an error. Some errors (eg:
+/// `EMFILE`, too many open files) persist until a connection is closed, so retrying
+/// right away would spin.
+pub const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_millis(100);
+
+/// The longest line that [try_read_line_until_shutdown] reads (not counting the `\n`, but
+/// counting the `\r` of a `\r\n`), so that a client that never sends a `\n` can't use up
+/// all the server's memory.
+pub const MAX_LINE_LEN: usize = 64 * 1024;
+
+/// The outcome of the last [ReloadableServerConfig::try_reload], from
+/// [ReloadableServerConfig::subscribe_reload_status].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ReloadStatus {
+    #[default]
+    NotReloaded,
+    Reloaded,
+    /// The current [ServerConfig] was kept.
+    Failed(String),
+}
+
+/// A [ServerConfig] that can be swapped atomically, w/ [ReloadableServerConfig::try_reload].
+pub struct ReloadableServerConfig {
+    tls_config: TlsConfig,
+    current: ArcSwap<ServerConfig>,
+    reload_status: watch::Sender<ReloadStatus>,
+}
+
+impl ReloadableServerConfig {
+    pub fn try_new(tls_config: TlsConfig) -> miette::Result<Self> {
+        let current = ArcSwap::from_pointee(tls_config.try_create_server_config()?);
+        ok!(Self {
+            tls_config,
+            current,
+            reload_status: watch::Sender::new(ReloadStatus::default()),
+        })
+    }
+
+    /// Load the cert, key, and CA again. If that fails, the current [ServerConfig] is
+    /// kept, so a half written file doesn't take the server down.
+    pub fn try_reload(&self) -> miette::Result<()> {
+        match self.tls_config.try_create_server_config() {
+            Ok(server_config) => {
+                self.current.store(Arc::new(server_config));
+                self.reload_status.send_replace(ReloadStatus::Reloaded);
+                ok!()
+            }
+            Err(error) => {
+                self.reload_status
+                    .send_replace(ReloadStatus::Failed(error.to_string()));
+                Err(error)
+            }
+        }
+    }
+
+    /// Changes after each [ReloadableServerConfig::try_reload], eg: to wait for
+    /// [try_watch_cert_files] to pick up new files.
+    pub fn subscribe_reload_status(&self) -> watch::Receiver<ReloadStatus> {
+        self.reload_status.subscribe()
+    }
+
+    /// Use this for the next handshake.
+    pub fn acceptor(&self) -> TlsAcceptor {
+        TlsAcceptor::from(self.current.load_full())
+    }
+
+    pub fn tls_config(&self) -> &TlsConfig {
+        &self.tls_config
+    }
+
+    /// The files that [try_watch_cert_files] watches. In memory PEM data can't change, so
+    /// it isn't included.
+    pub fn watched_paths(&self) -> Vec<PathBuf> {
+        [
+            &self.tls_config.cert,
+            &self.tls_config.key,
+            &self.tls_config.ca,
+        ]
+        .into_iter()
+        .filter_map(|it| match it {
+            PemSource::Path(path) => Some(path.clone()),
+            PemSource::InMemory(_) => None,
+        })
+        .collect()
+    }
+}
+
+/// Reload `server_config` when any of its [ReloadableServerConfig::watched_paths] change.
+/// The parent directories are watched (instead of the files), since files are often
+/// replaced by renaming a new file over them. Must be called from a tokio runtime.
+///
+/// Keep the returned watcher alive for as long as the files should be watched.
+pub fn try_watch_cert_files(
+    server_config: Arc<ReloadableServerConfig>,
+) -> miette::Result<RecommendedWatcher> {
+    let watched_paths = server_config.watched_paths();
+    let file_names: HashSet<_> = watched_paths
+        .iter()
+        .filter_map(|it| it.file_name().map(ToOwned::to_owned))
+        .collect();
+
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else { return };
+        if event.kind.is_access() {
+            return;
+        }
+        let is_watched = event
+            .paths
+            .iter()
+            .any(|it| it.file_name().is_some_and(|it| file_names.contains(it)));
+        if is_watched {
+            _ = sender.send(());
+        }
+    })
+    .into_diagnostic()?;
+
+    let parent_dirs: HashSet<&Path> = watched_paths
+        .iter()
+        .map(|it| match it.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        })
+        .collect();
+    for dir in parent_dirs {
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .into_diagnostic()?;
+    }
+
+    // This ends when the watcher is dropped, since that drops the sender.
+    tokio::spawn(async move {
+        while receiver.recv().await.is_some() {
+            tokio::time::sleep(RELOAD_DEBOUNCE).await;
+            while receiver.try_recv().is_ok() {}
+            match server_config.try_reload() {
+                Ok(_) => println!("{}", "🔄 Reloaded the certificate".green().italic()),
+                Err(error) => println!(
+                    "{} {error}",
+                    "❗ Kept the current certificate, failed to reload it:".red()
+                ),
+            }
+        }
+    });
+
+    ok!(watcher)
+}
+
+/// Accept clients (each in its own task) until `shutdown` completes. Then stop accepting,
+/// and wait for the open connections to finish. The `handler` gets the
+/// [CancellationToken] that is cancelled on shutdown. It should finish the request that
+/// is in flight, and then return instead of waiting for the next one, eg: w/
+/// [try_read_line_until_shutdown]. Connections that are still open after
+/// [DRAIN_TIMEOUT] are dropped.
+///
+/// A failed handshake only affects that client, and is printed. So is one that doesn't
+/// finish in [HANDSHAKE_TIMEOUT].
+pub async fn serve<H, F>(
+    listener: TcpListener,
+    server_config: Arc<ReloadableServerConfig>,
+    shutdown: impl Future<Output = ()>,
+    handler: H,
+) -> miette::Result<()>
+where
+    H: Fn(TlsStream<TcpStream>, SocketAddr, CancellationToken) -> F + Clone + Send + 'static,
+    F: Future<Output = miette::Result<()>> + Send + 'static,
+{
+    let handler = move |stream, addr, cancellation_token: CancellationToken| {
+        let tls_acceptor = server_config.acceptor();
+        let handler = handler.clone();
+        async move {
+            let handshake = tokio::time::timeout(HANDSHAKE_TIMEOUT, tls_acceptor.accept(stream));
+            let secure_stream = tokio::select! {
+                _ = cancellation_token.cancelled() => return ok!(),
+                it = handshake => match it {
+                    Ok(Ok(it)) => it,
+                    Ok(Err(error)) => miette::bail!("Handshake failed: {error}"),
+                    Err(_) => miette::bail!("Handshake timed out"),
+                },
+            };
+            handler(secure_stream, addr, cancellation_token).await
+        }
+    };
+    serve_tcp(listener, shutdown, handler).await
+}
+
+/// Like [serve], but w/out TLS, eg: for a proxy that connects to a TLS server.
+pub async fn serve_tcp<H, F>(
+    listener: TcpListener,
+    shutdown: impl Future<Output = ()>,
+    handler: H,
+) -> miette::Result<()>
+where
+    H: Fn(TcpStream, SocketAddr, CancellationToken) -> F + Clone + Send + 'static,
+    F: Future<Output = miette::Result<()>> + Send + 'static,
+{
+    let mut connections = JoinSet::new();
+    let cancellation_token = CancellationToken::new();
+    tokio::pin!(shutdown);
+
+    loop {
+        let (stream, addr) = tokio::select! {
+            _ = &mut shutdown => break,
+            // Remove the connections that are finished.
+            Some(_) = connections.join_next(), if !connections.is_empty() => continue,
+            it = listener.accept() => match it {
+                Ok(it) => it,
+                Err(error) => {
+                    println!("{} {error}", "❗ Failed to accept connection:".red());
+                    tokio::time::sleep(ACCEPT_ERROR_BACKOFF).await;
+                    continue;
+                }
+            },
+        };
+
+        let handler = handler.clone();
+        let cancellation_token = cancellation_token.clone();
+        connections.spawn(async move {
+            if let Err(error) = handler(stream, addr, cancellation_token).await {
+                println!("{} {addr}: {error}", "❗ Connection failed w/".red());
+            }
+        });
+    }
+
+    // Drain.
+    println!(
+        "{} {} {}",
+        "Draining".yellow().italic(),
+        connections.len().to_string().blue(),
+        "connections".yellow().italic()
+    );
+    drop(listener);
+    cancellation_token.cancel();
+    let drain = async { while connections.join_next().await.is_some() {} };
+    if tokio::time::timeout(DRAIN_TIMEOUT, drain).await.is_err() {
+        connections.abort_all();
+        println!(
+            "{} {}",
+            "Dropping".red().italic(),
+            "connections that didn't finish in time".red().italic()
+        );
+    }
+
+    ok!()
+}
+
+/// Read the next line (w/out the `\n` or `\r\n`). Returns `None` on EOF, or when
+/// `shutdown` is cancelled while the client is between lines. A line that has started
+/// arriving is always read to the end, so a request that is in flight on shutdown isn't
+/// cut off (as long as it finishes in [DRAIN_TIMEOUT]).
+///
+/// Returns an error once the line is longer than [MAX_LINE_LEN], so the handler can
+/// close the connection.
+pub async fn try_read_line_until_shutdown<R: AsyncRead + Unpin>(
+    reader: &mut BufReader<R>,
+    shutdown: &CancellationToken,
+) -> miette::Result<Option<String>> {
+    let mut line = vec![];
+    loop {
+        let is_between_lines = line.is_empty() && reader.buffer().is_empty();
+        let (consumed, is_done) = {
+            let available = tokio::select! {
+                // Prefer the data that has already arrived.
+                biased;
+                it = reader.fill_buf() => it.into_diagnostic()?,
+                _ = shutdown.cancelled(), if is_between_lines => return ok!(None),
+            };
+            let (chunk, consumed, is_done) = match available.iter().position(|it| *it == b'\n') {
+                Some(index) => (&available[..index], index + 1, true),
+                // EOF ends the last line, even w/out a `\n`.
+                None if available.is_empty() => (available, 0, true),
+                None => (available, available.len(), false),
+            };
+            if line.len() + chunk.len() > MAX_LINE_LEN {
+                miette::bail!("Line is longer than {MAX_LINE_LEN} bytes");
+            }
+            line.extend_from_slice(chunk);
+            (consumed, is_done)
+        };
+        reader.consume(consumed);
+
+        if is_done {
+            if consumed == 0 && line.is_empty() {
+                return ok!(None);
+            }
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            return ok!(Some(String::from_utf8(line).into_diagnostic()?));
+        }
+    }
+}
@@ -0,0 +1,78 @@
+/*
+ *   Copyright (c) 2024 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Tests for the `cfssl` duration parser in [tls::cert_gen].
+
+use std::time::Duration;
+use tls::cert_gen::{
+    try_generate_ca, try_parse_expiry, CaConfig, CsrConfig, KeyConfig, ProfileConfig, SigningConfig,
+};
+
+#[test]
+fn test_parse_expiry() {
+    assert_eq!(
+        try_parse_expiry("87600h").unwrap(),
+        Duration::from_secs(87600 * 60 * 60)
+    );
+    assert_eq!(
+        try_parse_expiry("1h30m").unwrap(),
+        Duration::from_secs(90 * 60)
+    );
+    assert_eq!(try_parse_expiry(" 10s ").unwrap(), Duration::from_secs(10));
+
+    for invalid in ["", "10", "10d", "h", "1h30"] {
+        assert!(try_parse_expiry(invalid).is_err(), "{invalid:?}");
+    }
+}
+
+#[test]
+fn test_parse_expiry_overflow() {
+    // The number fits in a u64, but not once it is converted to seconds.
+    let error = try_parse_expiry("99999999999999999h").unwrap_err();
+    assert!(error.to_string().contains("too long"), "{error}");
+    assert!(try_parse_expiry("307445734561825861m").is_err());
+
+    // Each part fits, but not the sum.
+    let max = u64::MAX;
+    assert!(try_parse_expiry(&format!("{max}s1s")).is_err());
+    assert_eq!(
+        try_parse_expiry(&format!("{max}s")).unwrap(),
+        Duration::from_secs(max)
+    );
+}
+
+#[test]
+fn test_expiry_past_the_last_date_is_an_error() {
+    // Fits in a `Duration`, but not in a certificate's `not_after` date.
+    let profile = ProfileConfig {
+        expiry: Some(format!("{}s", u64::MAX)),
+        usages: vec![],
+    };
+    let ca_config = CaConfig {
+        signing: SigningConfig {
+            default: profile,
+            profiles: Default::default(),
+        },
+    };
+    let csr = CsrConfig {
+        common_name: "ca".to_string(),
+        hosts: vec![],
+        key: KeyConfig::default(),
+        names: vec![],
+    };
+    assert!(try_generate_ca(&csr, &ca_config).is_err());
+}
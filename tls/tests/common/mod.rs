@@ -0,0 +1,136 @@
+/*
+ *   Copyright (c) 2024 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Fixtures that are shared by the integration tests. The certificates are generated w/
+//! [tls::cert_gen] in a temporary folder, so they never expire. Each test file only uses
+//! some of these, hence the `allow(dead_code)`.
+
+#![allow(dead_code)]
+
+use std::{net::SocketAddr, path::Path};
+use tempfile::TempDir;
+use tls::{
+    cert_gen::{self, CaConfig, CsrConfig, GeneratedCa, KeyConfig, ProfileConfig, SigningConfig},
+    TlsConfig, TlsConfigBuilder,
+};
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
+
+/// The server name that the server certificates are issued for, and that the clients
+/// expect.
+pub const SERVER_NAME: &str = "localhost";
+
+/// Has a `server` and a `client` profile, and the certificates are valid for an hour.
+pub fn ca_config() -> CaConfig {
+    let profile = |usage: &str| ProfileConfig {
+        expiry: None,
+        usages: vec!["signing".to_string(), usage.to_string()],
+    };
+    CaConfig {
+        signing: SigningConfig {
+            default: ProfileConfig {
+                expiry: Some("1h".to_string()),
+                usages: vec![],
+            },
+            profiles: [
+                ("server".to_string(), profile("server auth")),
+                ("client".to_string(), profile("client auth")),
+            ]
+            .into(),
+        },
+    }
+}
+
+pub fn csr(common_name: &str, hosts: &[&str]) -> CsrConfig {
+    CsrConfig {
+        common_name: common_name.to_string(),
+        hosts: hosts.iter().map(|it| it.to_string()).collect(),
+        key: KeyConfig::default(),
+        names: vec![],
+    }
+}
+
+pub fn generate_ca(common_name: &str) -> GeneratedCa {
+    cert_gen::try_generate_ca(&csr(common_name, &[]), &ca_config()).unwrap()
+}
+
+/// Write `ca.pem`, `server.pem`, and `server-key.pem` to `dir`. The server certificate is
+/// issued to `common_name`, for [SERVER_NAME].
+pub fn write_server_certs(ca: &GeneratedCa, common_name: &str, dir: &Path) {
+    std::fs::write(dir.join("ca.pem"), &ca.pem.cert_pem).unwrap();
+    ca.try_issue(&csr(common_name, &[SERVER_NAME]), &ca_config(), "server")
+        .unwrap()
+        .try_write(dir, "server")
+        .unwrap();
+}
+
+/// Write `client.pem` and `client-key.pem` to `dir`.
+pub fn write_client_certs(ca: &GeneratedCa, common_name: &str, dir: &Path) {
+    ca.try_issue(&csr(common_name, &[]), &ca_config(), "client")
+        .unwrap()
+        .try_write(dir, "client")
+        .unwrap();
+}
+
+/// Write the CA, a `server` certificate, and a `client` certificate to a temporary
+/// folder, which is deleted when it is dropped.
+pub fn generate_certs() -> (GeneratedCa, TempDir) {
+    let dir = tempfile::tempdir().unwrap();
+    let ca = generate_ca("ca");
+    write_server_certs(&ca, "server", dir.path());
+    write_client_certs(&ca, "client", dir.path());
+    (ca, dir)
+}
+
+/// Uses the server certificate in `dir`, and trusts the CA in `dir`.
+pub fn server_tls_config_builder(dir: &Path) -> TlsConfigBuilder {
+    TlsConfigBuilder::default()
+        .cert_path(dir.join("server.pem"))
+        .key_path(dir.join("server-key.pem"))
+        .ca_path(dir.join("ca.pem"))
+}
+
+pub fn server_tls_config(dir: &Path) -> TlsConfig {
+    server_tls_config_builder(dir).build()
+}
+
+/// Trusts the CA in `dir`, and expects the server to be [SERVER_NAME]. It has no client
+/// certificate.
+pub fn client_tls_config_builder(dir: &Path) -> TlsConfigBuilder {
+    TlsConfigBuilder::default()
+        .ca_path(dir.join("ca.pem"))
+        .server_name(SERVER_NAME)
+}
+
+pub fn client_tls_config(dir: &Path) -> TlsConfig {
+    client_tls_config_builder(dir).build()
+}
+
+/// Connect to `addr` over TCP, and do a TLS handshake that trusts `ca_pem`.
+pub async fn connect_tls(addr: SocketAddr, ca_pem: &str) -> TlsStream<TcpStream> {
+    let client_config = TlsConfigBuilder::default()
+        .ca_pem(ca_pem)
+        .server_name(SERVER_NAME)
+        .build();
+    let tcp_stream = TcpStream::connect(addr).await.unwrap();
+    client_config
+        .try_create_client_tls_connector()
+        .unwrap()
+        .connect(client_config.try_server_name().unwrap(), tcp_stream)
+        .await
+        .unwrap()
+}
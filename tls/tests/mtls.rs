@@ -0,0 +1,159 @@
+/*
+ *   Copyright (c) 2024 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! End to end tests for mutual TLS, over an in memory duplex stream. The CA, server, and
+//! client certificates come from [common::generate_certs].
+
+mod common;
+
+use common::*;
+use std::path::Path;
+use tls::{try_peer_identity, CertSummary, ClientAuth, TlsConfig};
+use tokio::io::{duplex, AsyncReadExt as _, AsyncWriteExt as _};
+
+/// Run a handshake between a server using `server_config` and a client using
+/// `client_config`, and send a message from the client to the server. Returns the
+/// client's identity as seen by the server, or the server's error.
+async fn handshake(
+    server_config: TlsConfig,
+    client_config: TlsConfig,
+) -> miette::Result<Option<CertSummary>> {
+    let tls_acceptor = server_config.try_create_server_tls_acceptor()?;
+    let tls_connector = client_config.try_create_client_tls_connector()?;
+    let server_name = client_config.try_server_name()?;
+    let (client_stream, server_stream) = duplex(64 * 1024);
+
+    let client = tokio::spawn(async move {
+        // w/ TLS 1.3 the client finishes the handshake before the server verifies the
+        // client certificate, so a rejected client may only see the error later.
+        if let Ok(mut secure_stream) = tls_connector.connect(server_name, client_stream).await {
+            _ = secure_stream.write_all(b"hello").await;
+            _ = secure_stream.flush().await;
+            _ = secure_stream.read(&mut [0; 1]).await;
+        }
+    });
+
+    let result = async {
+        let mut secure_stream = tls_acceptor
+            .accept(server_stream)
+            .await
+            .map_err(|it| miette::miette!("{it}"))?;
+        let peer_identity = try_peer_identity(secure_stream.get_ref().1)?;
+        let mut buf = [0; 5];
+        secure_stream
+            .read_exact(&mut buf)
+            .await
+            .map_err(|it| miette::miette!("{it}"))?;
+        assert_eq!(&buf, b"hello");
+        Ok(peer_identity)
+    }
+    .await;
+
+    client.abort();
+    result
+}
+
+fn client_config_w_generated_cert(dir: &Path) -> TlsConfig {
+    client_tls_config_builder(dir)
+        .client_cert_path(dir.join("client.pem"))
+        .client_key_path(dir.join("client-key.pem"))
+        .build()
+}
+
+fn server_config(dir: &Path, client_auth: ClientAuth) -> TlsConfig {
+    server_tls_config_builder(dir)
+        .client_auth(client_auth)
+        .build()
+}
+
+/// A client certificate that is issued by a different CA than the one in `dir`.
+fn client_config_w_untrusted_cert(dir: &Path) -> TlsConfig {
+    let client = generate_ca("untrusted ca")
+        .try_issue(&csr("intruder", &[]), &ca_config(), "client")
+        .unwrap();
+    client_tls_config_builder(dir)
+        .client_cert_pem(client.cert_pem)
+        .client_key_pem(client.key_pem)
+        .build()
+}
+
+#[tokio::test]
+async fn test_valid_client_cert() {
+    let (_, dir) = generate_certs();
+    let peer_identity = handshake(
+        server_config(dir.path(), ClientAuth::Required),
+        client_config_w_generated_cert(dir.path()),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(peer_identity.common_name.as_deref(), Some("client"));
+    assert!(peer_identity.subject.contains("CN=client"));
+    assert!(peer_identity.issuer.contains("CN=ca"));
+    assert!(peer_identity.sans.is_empty());
+}
+
+#[tokio::test]
+async fn test_untrusted_client_cert() {
+    let (_, dir) = generate_certs();
+    for client_auth in [ClientAuth::Required, ClientAuth::Optional] {
+        let result = handshake(
+            server_config(dir.path(), client_auth),
+            client_config_w_untrusted_cert(dir.path()),
+        )
+        .await;
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("UnknownIssuer"), "{client_auth}: {error}");
+    }
+}
+
+#[tokio::test]
+async fn test_no_client_cert() {
+    let (_, dir) = generate_certs();
+    let no_client_cert = || client_tls_config(dir.path());
+
+    let result = handshake(
+        server_config(dir.path(), ClientAuth::Required),
+        no_client_cert(),
+    )
+    .await;
+    assert!(result.is_err());
+
+    let peer_identity = handshake(
+        server_config(dir.path(), ClientAuth::Optional),
+        no_client_cert(),
+    )
+    .await;
+    assert_eq!(peer_identity.unwrap(), None);
+
+    // The server doesn't ask for the certificate, so the client doesn't send it.
+    let peer_identity = handshake(
+        server_config(dir.path(), ClientAuth::None),
+        client_config_w_generated_cert(dir.path()),
+    )
+    .await;
+    assert_eq!(peer_identity.unwrap(), None);
+}
+
+#[test]
+fn test_client_cert_needs_key() {
+    let (_, dir) = generate_certs();
+    let config = client_tls_config_builder(dir.path())
+        .client_cert_path(dir.path().join("client.pem"))
+        .build();
+    assert!(config.try_create_client_config().is_err());
+}
@@ -0,0 +1,330 @@
+/*
+ *   Copyright (c) 2024 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Tests for [tls::copy_bidirectional_w_idle_timeout] over in memory duplex streams, and
+//! end to end tests for [tls::serve_proxy] over TCP on localhost. The certificates come
+//! from [common::generate_certs].
+
+mod common;
+
+use common::*;
+use std::{net::SocketAddr, path::Path, sync::Arc, time::Duration};
+use tls::{
+    copy_bidirectional_w_idle_timeout, serve, serve_proxy, ProxyMode, ProxyStats,
+    ReloadableServerConfig, TlsConfig, SHUTDOWN_GRACE_PERIOD, UPSTREAM_CONNECT_TIMEOUT,
+};
+use tokio::{
+    io::{duplex, AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _},
+    net::{TcpListener, TcpStream},
+    sync::{oneshot, Notify},
+    task::JoinHandle,
+    time::Instant,
+};
+
+#[tokio::test]
+async fn test_copy_both_ways() {
+    let (mut client, mut proxy_client_side) = duplex(1024);
+    let (mut proxy_upstream_side, mut upstream) = duplex(1024);
+    let proxy = tokio::spawn(async move {
+        copy_bidirectional_w_idle_timeout(
+            &mut proxy_client_side,
+            &mut proxy_upstream_side,
+            Duration::from_secs(5),
+        )
+        .await
+    });
+
+    client.write_all(b"ping").await.unwrap();
+    let mut buf = [0; 4];
+    upstream.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"ping");
+
+    upstream.write_all(b"pong!").await.unwrap();
+    let mut buf = [0; 5];
+    client.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"pong!");
+
+    // Closing both sides ends the copy.
+    drop(client);
+    drop(upstream);
+    let stats = proxy.await.unwrap().unwrap();
+    assert_eq!(
+        stats,
+        ProxyStats {
+            client_to_upstream: 4,
+            upstream_to_client: 5,
+        }
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_idle_timeout() {
+    let idle_timeout = Duration::from_millis(300);
+    let (mut client, mut proxy_client_side) = duplex(1024);
+    let (mut proxy_upstream_side, mut upstream) = duplex(1024);
+    let start = Instant::now();
+    let proxy = tokio::spawn(async move {
+        copy_bidirectional_w_idle_timeout(
+            &mut proxy_client_side,
+            &mut proxy_upstream_side,
+            idle_timeout,
+        )
+        .await
+    });
+
+    // Activity keeps the connection open past the idle timeout.
+    for _ in 0..5 {
+        client.write_all(b"x").await.unwrap();
+        upstream.read_exact(&mut [0; 1]).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(!proxy.is_finished());
+
+    // Then nothing happens, so it times out, counting from the last write (at 400ms).
+    let error = proxy.await.unwrap().unwrap_err();
+    assert!(error.to_string().contains("Idle"), "{error}");
+    assert!(start.elapsed() >= Duration::from_millis(400) + idle_timeout);
+}
+
+/// Write the bytes back to the client, until EOF.
+async fn echo(stream: impl AsyncRead + AsyncWrite) {
+    let (mut reader, mut writer) = tokio::io::split(stream);
+    _ = tokio::io::copy(&mut reader, &mut writer).await;
+    _ = writer.shutdown().await;
+}
+
+/// A plaintext echo service.
+async fn start_plain_upstream() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(echo(stream));
+        }
+    });
+    addr
+}
+
+/// A TLS echo service, that uses the server cert in `dir`.
+async fn start_tls_upstream(dir: &Path) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server_config = Arc::new(ReloadableServerConfig::try_new(server_tls_config(dir)).unwrap());
+    tokio::spawn(serve(
+        listener,
+        server_config,
+        std::future::pending(),
+        |secure_stream, _, _| async move {
+            echo(secure_stream).await;
+            Ok(())
+        },
+    ));
+    addr
+}
+
+struct TestProxy {
+    addr: SocketAddr,
+    shutdown: oneshot::Sender<()>,
+    /// Notified once the proxy has stopped accepting, and cancelled its connections.
+    shutdown_started: Arc<Notify>,
+    task: JoinHandle<miette::Result<()>>,
+}
+
+async fn start_proxy(mode: ProxyMode, tls_config: TlsConfig, upstream: SocketAddr) -> TestProxy {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (shutdown, shutdown_receiver) = oneshot::channel::<()>();
+    let shutdown_started = Arc::new(Notify::new());
+    // The proxy cancels its connections right after this future completes, w/out
+    // yielding in between.
+    let shutdown_future = {
+        let shutdown_started = shutdown_started.clone();
+        async move {
+            _ = shutdown_receiver.await;
+            shutdown_started.notify_one();
+        }
+    };
+    let task = tokio::spawn(serve_proxy(
+        listener,
+        mode,
+        tls_config,
+        upstream.to_string(),
+        Duration::from_secs(5),
+        shutdown_future,
+    ));
+    TestProxy {
+        addr,
+        shutdown,
+        shutdown_started,
+        task,
+    }
+}
+
+async fn send_and_receive(stream: &mut (impl AsyncRead + AsyncWrite + Unpin), message: &str) {
+    stream.write_all(message.as_bytes()).await.unwrap();
+    stream.flush().await.unwrap();
+    let mut buf = vec![0; message.len()];
+    stream.read_exact(&mut buf).await.unwrap();
+    assert_eq!(String::from_utf8(buf).unwrap(), message);
+}
+
+#[tokio::test]
+async fn test_terminate_tls_client_to_plain_upstream() {
+    let (ca, dir) = generate_certs();
+    let upstream = start_plain_upstream().await;
+    let proxy = start_proxy(
+        ProxyMode::Terminate,
+        server_tls_config(dir.path()),
+        upstream,
+    )
+    .await;
+
+    let mut client = connect_tls(proxy.addr, &ca.pem.cert_pem).await;
+    send_and_receive(&mut client, "hello").await;
+    send_and_receive(&mut client, "world").await;
+
+    proxy.shutdown.send(()).unwrap();
+}
+
+#[tokio::test]
+async fn test_originate_plain_client_to_tls_upstream() {
+    let (_, dir) = generate_certs();
+    let upstream = start_tls_upstream(dir.path()).await;
+    let proxy = start_proxy(
+        ProxyMode::Originate,
+        client_tls_config(dir.path()),
+        upstream,
+    )
+    .await;
+
+    let mut client = TcpStream::connect(proxy.addr).await.unwrap();
+    send_and_receive(&mut client, "hello").await;
+    send_and_receive(&mut client, "world").await;
+
+    proxy.shutdown.send(()).unwrap();
+}
+
+#[tokio::test]
+async fn test_open_connection_is_drained_on_shutdown() {
+    let (ca, dir) = generate_certs();
+    let upstream = start_plain_upstream().await;
+    let proxy = start_proxy(
+        ProxyMode::Terminate,
+        server_tls_config(dir.path()),
+        upstream,
+    )
+    .await;
+    let mut client = connect_tls(proxy.addr, &ca.pem.cert_pem).await;
+    send_and_receive(&mut client, "before").await;
+
+    // The connection keeps working after the proxy starts shutting down.
+    let start = Instant::now();
+    proxy.shutdown.send(()).unwrap();
+    proxy.shutdown_started.notified().await;
+    send_and_receive(&mut client, "after").await;
+
+    // Once the client closes it, the proxy exits w/out waiting for the grace period.
+    client.shutdown().await.unwrap();
+    let mut buf = vec![];
+    assert_eq!(client.read_to_end(&mut buf).await.unwrap(), 0);
+    proxy.task.await.unwrap().unwrap();
+    assert!(start.elapsed() < SHUTDOWN_GRACE_PERIOD);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_idle_connection_is_closed_after_grace_period_on_shutdown() {
+    let (ca, dir) = generate_certs();
+    let upstream = start_plain_upstream().await;
+    let proxy = start_proxy(
+        ProxyMode::Terminate,
+        server_tls_config(dir.path()),
+        upstream,
+    )
+    .await;
+    let mut client = connect_tls(proxy.addr, &ca.pem.cert_pem).await;
+    send_and_receive(&mut client, "hello").await;
+
+    // The client never closes the connection, so the proxy does (w/ close_notify).
+    let start = Instant::now();
+    proxy.shutdown.send(()).unwrap();
+    let mut buf = vec![];
+    assert_eq!(client.read_to_end(&mut buf).await.unwrap(), 0);
+    assert!(start.elapsed() >= SHUTDOWN_GRACE_PERIOD);
+    proxy.task.await.unwrap().unwrap();
+}
+
+/// An upstream that accepts TCP connections, but never does the TLS handshake. The
+/// [Notify] is notified when it accepts a connection.
+async fn start_stuck_upstream() -> (SocketAddr, Arc<Notify>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let accepted = Arc::new(Notify::new());
+    tokio::spawn({
+        let accepted = accepted.clone();
+        async move {
+            let mut streams = vec![];
+            while let Ok((stream, _)) = listener.accept().await {
+                streams.push(stream);
+                accepted.notify_one();
+            }
+        }
+    });
+    (addr, accepted)
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_upstream_connect_timeout() {
+    let (_, dir) = generate_certs();
+    let (upstream, _) = start_stuck_upstream().await;
+    let proxy = start_proxy(
+        ProxyMode::Originate,
+        client_tls_config(dir.path()),
+        upstream,
+    )
+    .await;
+
+    // The proxy gives up on the upstream, and closes the client's connection.
+    let start = Instant::now();
+    let mut client = TcpStream::connect(proxy.addr).await.unwrap();
+    let mut buf = vec![];
+    assert_eq!(client.read_to_end(&mut buf).await.unwrap(), 0);
+    assert!(start.elapsed() >= UPSTREAM_CONNECT_TIMEOUT);
+
+    proxy.shutdown.send(()).unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_upstream_connect_is_cancelled_on_shutdown() {
+    let (_, dir) = generate_certs();
+    let (upstream, upstream_accepted) = start_stuck_upstream().await;
+    let proxy = start_proxy(
+        ProxyMode::Originate,
+        client_tls_config(dir.path()),
+        upstream,
+    )
+    .await;
+    let mut client = TcpStream::connect(proxy.addr).await.unwrap();
+    upstream_accepted.notified().await;
+
+    // The proxy exits w/out waiting for the upstream.
+    let start = Instant::now();
+    proxy.shutdown.send(()).unwrap();
+    proxy.task.await.unwrap().unwrap();
+    assert!(start.elapsed() < UPSTREAM_CONNECT_TIMEOUT);
+    let mut buf = vec![];
+    assert_eq!(client.read_to_end(&mut buf).await.unwrap(), 0);
+}
@@ -0,0 +1,336 @@
+/*
+ *   Copyright (c) 2024 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! End to end tests for [tls::serve] and [tls::try_watch_cert_files], over TCP on
+//! localhost. The certificates are written to a temporary folder w/
+//! [common::write_server_certs].
+
+mod common;
+
+use common::*;
+use std::{future::Future, net::SocketAddr, sync::Arc, time::Duration};
+use tls::{
+    serve, try_peer_identity, try_read_line_until_shutdown, try_watch_cert_files, ReloadStatus,
+    ReloadableServerConfig, HANDSHAKE_TIMEOUT, MAX_LINE_LEN,
+};
+use tokio::{
+    io::{
+        duplex, AsyncBufReadExt as _, AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _,
+        BufReader,
+    },
+    net::{TcpListener, TcpStream},
+    sync::{oneshot, Notify},
+    task::JoinHandle,
+};
+use tokio_rustls::client::TlsStream;
+use tokio_util::sync::CancellationToken;
+
+/// Write each line back to the client, until EOF or shutdown.
+async fn echo(
+    secure_stream: tokio_rustls::server::TlsStream<TcpStream>,
+    _: SocketAddr,
+    shutdown: CancellationToken,
+) -> miette::Result<()> {
+    let (reader, writer) = tokio::io::split(secure_stream);
+    echo_lines(BufReader::new(reader), writer, &shutdown).await;
+    Ok(())
+}
+
+async fn echo_lines(
+    mut reader: BufReader<impl AsyncRead + Unpin>,
+    mut writer: impl AsyncWrite + Unpin,
+    shutdown: &CancellationToken,
+) {
+    while let Some(line) = try_read_line_until_shutdown(&mut reader, shutdown)
+        .await
+        .unwrap()
+    {
+        writer
+            .write_all(format!("{line}\n").as_bytes())
+            .await
+            .unwrap();
+        writer.flush().await.unwrap();
+    }
+    writer.shutdown().await.unwrap();
+}
+
+struct TestServer {
+    addr: SocketAddr,
+    shutdown: oneshot::Sender<()>,
+    task: JoinHandle<miette::Result<()>>,
+}
+
+async fn start_server(server_config: Arc<ReloadableServerConfig>) -> TestServer {
+    start_server_w_handler(server_config, echo).await
+}
+
+async fn start_server_w_handler<H, F>(
+    server_config: Arc<ReloadableServerConfig>,
+    handler: H,
+) -> TestServer
+where
+    H: Fn(tokio_rustls::server::TlsStream<TcpStream>, SocketAddr, CancellationToken) -> F
+        + Clone
+        + Send
+        + 'static,
+    F: Future<Output = miette::Result<()>> + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (shutdown, shutdown_receiver) = oneshot::channel::<()>();
+    let task = tokio::spawn(serve(
+        listener,
+        server_config,
+        async move { _ = shutdown_receiver.await },
+        handler,
+    ));
+    TestServer {
+        addr,
+        shutdown,
+        task,
+    }
+}
+
+async fn send_and_receive(secure_stream: &mut TlsStream<TcpStream>, line: &str) -> String {
+    secure_stream
+        .write_all(format!("{line}\n").as_bytes())
+        .await
+        .unwrap();
+    let mut buf = vec![0; line.len() + 1];
+    secure_stream.read_exact(&mut buf).await.unwrap();
+    String::from_utf8(buf).unwrap()
+}
+
+fn server_common_name(secure_stream: &TlsStream<TcpStream>) -> Option<String> {
+    try_peer_identity(secure_stream.get_ref().1)
+        .unwrap()
+        .and_then(|it| it.common_name)
+}
+
+#[tokio::test]
+async fn test_concurrent_clients_and_drain() {
+    let dir = tempfile::tempdir().unwrap();
+    let ca = generate_ca("ca");
+    write_server_certs(&ca, "server", dir.path());
+    let server_config =
+        Arc::new(ReloadableServerConfig::try_new(server_tls_config(dir.path())).unwrap());
+    let server = start_server(server_config).await;
+
+    // All the clients are connected at the same time.
+    let mut clients = vec![];
+    for _ in 0..5 {
+        clients.push(connect_tls(server.addr, &ca.pem.cert_pem).await);
+    }
+    for (index, client) in clients.iter_mut().enumerate() {
+        let line = format!("hello {index}");
+        assert_eq!(send_and_receive(client, &line).await, format!("{line}\n"));
+    }
+
+    // On shutdown, the open connections are closed (w/ close_notify), and then `serve`
+    // returns.
+    server.shutdown.send(()).unwrap();
+    for client in clients.iter_mut() {
+        let mut buf = vec![];
+        assert_eq!(client.read_to_end(&mut buf).await.unwrap(), 0);
+    }
+    tokio::time::timeout(Duration::from_secs(5), server.task)
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_in_flight_request_finishes_on_shutdown() {
+    let dir = tempfile::tempdir().unwrap();
+    let ca = generate_ca("ca");
+    write_server_certs(&ca, "server", dir.path());
+    let server_config =
+        Arc::new(ReloadableServerConfig::try_new(server_tls_config(dir.path())).unwrap());
+
+    // Like echo, but it waits for the start of the line to arrive, and then for the
+    // shutdown, before reading the line.
+    let line_started = Arc::new(Notify::new());
+    let handler = {
+        let line_started = line_started.clone();
+        move |secure_stream, _, shutdown: CancellationToken| {
+            let line_started = line_started.clone();
+            async move {
+                let (reader, writer) = tokio::io::split(secure_stream);
+                let mut reader = BufReader::new(reader);
+                reader.fill_buf().await.unwrap();
+                line_started.notify_one();
+                shutdown.cancelled().await;
+                echo_lines(reader, writer, &shutdown).await;
+                Ok(())
+            }
+        }
+    };
+    let server = start_server_w_handler(server_config, handler).await;
+    let mut client = connect_tls(server.addr, &ca.pem.cert_pem).await;
+
+    // Half of the line has arrived when the server starts shutting down.
+    client.write_all(b"hel").await.unwrap();
+    client.flush().await.unwrap();
+    line_started.notified().await;
+    server.shutdown.send(()).unwrap();
+
+    // The rest of it is still echoed, and then the connection is closed.
+    client.write_all(b"lo\n").await.unwrap();
+    let mut buf = vec![];
+    client.read_to_end(&mut buf).await.unwrap();
+    assert_eq!(buf, b"hello\n");
+    tokio::time::timeout(Duration::from_secs(5), server.task)
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_handshake_timeout() {
+    let dir = tempfile::tempdir().unwrap();
+    let ca = generate_ca("ca");
+    write_server_certs(&ca, "server", dir.path());
+    let server_config =
+        Arc::new(ReloadableServerConfig::try_new(server_tls_config(dir.path())).unwrap());
+    let server = start_server(server_config).await;
+
+    // A client that never starts the handshake is disconnected.
+    let started_at = tokio::time::Instant::now();
+    let mut idle_client = TcpStream::connect(server.addr).await.unwrap();
+    let mut buf = vec![];
+    assert_eq!(idle_client.read_to_end(&mut buf).await.unwrap(), 0);
+    assert!(started_at.elapsed() >= HANDSHAKE_TIMEOUT);
+
+    server.shutdown.send(()).unwrap();
+}
+
+#[tokio::test]
+async fn test_read_line_until_shutdown() {
+    let shutdown = CancellationToken::new();
+    let (mut client, server) = duplex(64);
+    let mut reader = BufReader::new(server);
+
+    client.write_all(b"one\r\ntwo\nthr").await.unwrap();
+    let mut read_line = async || {
+        try_read_line_until_shutdown(&mut reader, &shutdown)
+            .await
+            .unwrap()
+    };
+    assert_eq!(read_line().await.as_deref(), Some("one"));
+    assert_eq!(read_line().await.as_deref(), Some("two"));
+
+    // A line that has started arriving is finished, even after shutdown.
+    shutdown.cancel();
+    client.write_all(b"ee\nfour").await.unwrap();
+    assert_eq!(read_line().await.as_deref(), Some("three"));
+
+    // Buffered data is still a line in flight, but an idle client isn't.
+    drop(client);
+    assert_eq!(read_line().await.as_deref(), Some("four"));
+    assert_eq!(read_line().await, None);
+}
+
+#[tokio::test]
+async fn test_read_line_until_shutdown_max_line_len() {
+    let shutdown = CancellationToken::new();
+    let (mut client, server) = duplex(1024);
+    let mut reader = BufReader::new(server);
+
+    // The longest line that is allowed, and then one that never ends.
+    let writer = tokio::spawn(async move {
+        let line = "a".repeat(MAX_LINE_LEN);
+        client
+            .write_all(format!("{line}\n").as_bytes())
+            .await
+            .unwrap();
+        client.write_all(line.as_bytes()).await.unwrap();
+        client.write_all(b"a").await.unwrap();
+        client
+    });
+    let line = try_read_line_until_shutdown(&mut reader, &shutdown).await;
+    assert_eq!(line.unwrap().map(|it| it.len()), Some(MAX_LINE_LEN));
+    let error = try_read_line_until_shutdown(&mut reader, &shutdown)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("longer than"), "{error}");
+    drop(reader);
+    _ = writer.await;
+}
+
+#[tokio::test]
+async fn test_read_line_until_shutdown_when_idle() {
+    let shutdown = CancellationToken::new();
+    let (_client, server) = duplex(64);
+    let mut reader = BufReader::new(server);
+    shutdown.cancel();
+    let line = try_read_line_until_shutdown(&mut reader, &shutdown).await;
+    assert_eq!(line.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_hot_reload() {
+    let dir = tempfile::tempdir().unwrap();
+    let ca = generate_ca("ca");
+    write_server_certs(&ca, "server 1", dir.path());
+    let server_config =
+        Arc::new(ReloadableServerConfig::try_new(server_tls_config(dir.path())).unwrap());
+    let _watcher = try_watch_cert_files(server_config.clone()).unwrap();
+    let server = start_server(server_config.clone()).await;
+
+    let mut old_client = connect_tls(server.addr, &ca.pem.cert_pem).await;
+    assert_eq!(server_common_name(&old_client).as_deref(), Some("server 1"));
+
+    // New handshakes use the new certificate, once the change is picked up. Writing the
+    // files may be seen as more than 1 change, so wait for the reload that has both.
+    let mut reload_status = server_config.subscribe_reload_status();
+    write_server_certs(&ca, "server 2", dir.path());
+    let reloaded = async {
+        loop {
+            reload_status.changed().await.unwrap();
+            if *reload_status.borrow_and_update() != ReloadStatus::Reloaded {
+                continue;
+            }
+            let new_client = connect_tls(server.addr, &ca.pem.cert_pem).await;
+            if server_common_name(&new_client).as_deref() == Some("server 2") {
+                break;
+            }
+        }
+    };
+    tokio::time::timeout(Duration::from_secs(10), reloaded)
+        .await
+        .unwrap();
+
+    // The existing session keeps working.
+    assert_eq!(
+        send_and_receive(&mut old_client, "still here").await,
+        "still here\n"
+    );
+
+    // A broken file doesn't replace the current certificate.
+    std::fs::write(dir.path().join("server.pem"), "not a certificate").unwrap();
+    let failed = reload_status.wait_for(|it| matches!(it, ReloadStatus::Failed(_)));
+    tokio::time::timeout(Duration::from_secs(10), failed)
+        .await
+        .unwrap()
+        .unwrap();
+    let new_client = connect_tls(server.addr, &ca.pem.cert_pem).await;
+    assert_eq!(server_common_name(&new_client).as_deref(), Some("server 2"));
+
+    server.shutdown.send(()).unwrap();
+}